@@ -1,28 +1,51 @@
 use floem::{
-    event::EventListener,
+    event::{Event, EventListener},
     keyboard::{Key, Modifiers, NamedKey},
+    peniko::Color,
+    reactive::{create_rw_signal, SignalGet, SignalUpdate, SignalWith},
     unit::UnitExt,
     views::{dyn_view, Decorators},
     IntoView, View,
 };
 
 fn app_view() -> impl IntoView {
-    let view = dyn_view(move || "dropped file".to_string()).style(|s| {
+    let hovered = create_rw_signal(0);
+    let dropped = create_rw_signal(Vec::new());
+
+    let view = dyn_view(move || match hovered.get() {
+        0 if dropped.with(|d| d.is_empty()) => "drop files here".to_string(),
+        0 => dropped.with(|d| {
+            d.iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }),
+        count => format!("drop {count} files"),
+    })
+    .style(move |s| {
         s.size(100.pct(), 100.pct())
             .flex_col()
             .items_center()
             .justify_center()
+            .apply_if(hovered.get() > 0, |s| s.background(Color::LIGHT_BLUE))
     });
 
     let id = view.id();
     view.on_key_up(Key::Named(NamedKey::F11), Modifiers::empty(), move |_| {
         id.inspect()
     })
-    .on_event_stop(EventListener::PointerMove, |x| {
-        println!("PointerMove {:?}", x.point());
+    .on_event_stop(EventListener::FileDragEnter, move |event| {
+        if let Event::FileDragEnter(event) = event {
+            hovered.set(event.paths.len());
+        }
+    })
+    .on_event_stop(EventListener::FileDragLeave, move |_| {
+        hovered.set(0);
     })
-    .on_event_stop(EventListener::DroppedFile, |x| {
-        println!("DroppedFile {:?}", x);
+    .on_event_stop(EventListener::DroppedFile, move |event| {
+        if let Event::DroppedFile(event) = event {
+            dropped.set(event.paths.clone());
+        }
     })
 }
 
@@ -0,0 +1,399 @@
+//! Configuration of the surface a GPU renderer presents its frames to.
+
+use std::fmt;
+
+/// Which renderer a window is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendPreference {
+    /// vger on the GPU, or tiny-skia on the CPU if vger can't be created.
+    #[default]
+    Vger,
+    /// vger on the GPU only. Creating the renderer fails if vger can't be created.
+    VgerOnly,
+    /// tiny-skia on the CPU, even if a GPU is available.
+    TinySkia,
+}
+
+/// Which renderer a window is drawn with, and how a GPU renderer configures the surface of
+/// the window.
+///
+/// The configuration is checked against the capabilities of the surface when the renderer
+/// is created, see [`RendererConfig::resolve`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererConfig {
+    /// The renderer to use. Setting the `FLOEM_FORCE_TINY_SKIA` environment variable to `1`
+    /// overrides it with [`BackendPreference::TinySkia`].
+    pub backend: BackendPreference,
+    /// The format of the surface. If `None`, `Bgra8UnormSrgb` is used when the surface
+    /// supports it, and otherwise the first sRGB format the surface supports.
+    pub format: Option<wgpu::TextureFormat>,
+    pub present_mode: wgpu::PresentMode,
+    /// The number of samples per pixel frames are drawn with. With `1`, frames are drawn into
+    /// the surface directly instead of being resolved to it from a multisampled target.
+    pub sample_count: u32,
+    /// The format of a depth/stencil texture given to the engine, if any.
+    pub depth_stencil_format: Option<wgpu::TextureFormat>,
+}
+
+impl Default for RendererConfig {
+    fn default() -> Self {
+        Self {
+            backend: BackendPreference::default(),
+            format: None,
+            present_mode: wgpu::PresentMode::Fifo,
+            sample_count: 4,
+            depth_stencil_format: None,
+        }
+    }
+}
+
+impl RendererConfig {
+    /// Sets which renderer to use.
+    ///
+    /// The default is [`BackendPreference::Vger`].
+    pub fn backend(mut self, backend: BackendPreference) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Sets the format of the surface.
+    ///
+    /// The default is `Bgra8UnormSrgb`, or the first sRGB format the surface supports.
+    pub fn format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets how frames are presented.
+    ///
+    /// The default is `PresentMode::Fifo`, which waits for vsync and is supported everywhere.
+    pub fn present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// Sets the number of samples per pixel frames are drawn with, which must be 1, 2, 4, 8
+    /// or 16 and supported by the adapter for the format of the surface.
+    ///
+    /// The default is 4.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// Adds a depth/stencil texture with the given format, which matches the size and sample
+    /// count of the frame and is given to the engine. The UI is depth tested against it after
+    /// the engine drew its scene.
+    ///
+    /// The default is no depth/stencil texture.
+    pub fn depth_stencil(mut self, format: wgpu::TextureFormat) -> Self {
+        self.depth_stencil_format = Some(format);
+        self
+    }
+
+    /// Checks the configuration against the capabilities of a surface and the adapter
+    /// rendering to it, and returns the format of the frames the renderer will draw.
+    pub fn resolve(
+        &self,
+        surface_caps: &wgpu::SurfaceCapabilities,
+        adapter: &wgpu::Adapter,
+    ) -> Result<FrameFormat, RendererConfigError> {
+        let format = self.select_format(surface_caps)?;
+        self.check_sample_count(format, &adapter.get_texture_format_features(format))?;
+
+        if let Some(depth_stencil_format) = self.depth_stencil_format {
+            let features = adapter.get_texture_format_features(depth_stencil_format);
+            let supported = depth_stencil_format.is_depth_stencil_format()
+                && features
+                    .allowed_usages
+                    .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+                && features.flags.sample_count_supported(self.sample_count);
+            if !supported {
+                return Err(RendererConfigError::UnsupportedDepthStencilFormat {
+                    format: depth_stencil_format,
+                    sample_count: self.sample_count,
+                });
+            }
+        }
+
+        Ok(FrameFormat {
+            format,
+            sample_count: self.sample_count,
+            depth_stencil_format: self.depth_stencil_format,
+        })
+    }
+
+    /// Checks that frames in `format`, which has the given `features`, can be drawn with the
+    /// sample count, and resolved to the surface if they are multisampled.
+    fn check_sample_count(
+        &self,
+        format: wgpu::TextureFormat,
+        features: &wgpu::TextureFormatFeatures,
+    ) -> Result<(), RendererConfigError> {
+        let resolvable = self.sample_count == 1
+            || features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE);
+        if !features.flags.sample_count_supported(self.sample_count) || !resolvable {
+            return Err(RendererConfigError::UnsupportedSampleCount {
+                sample_count: self.sample_count,
+                format,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the format and present mode against the capabilities of a surface, and returns
+    /// the format of the surface.
+    fn select_format(
+        &self,
+        surface_caps: &wgpu::SurfaceCapabilities,
+    ) -> Result<wgpu::TextureFormat, RendererConfigError> {
+        let format = match self.format {
+            Some(format) if surface_caps.formats.contains(&format) => format,
+            Some(format) => {
+                return Err(RendererConfigError::UnsupportedFormat {
+                    format,
+                    supported: surface_caps.formats.clone(),
+                })
+            }
+            None => {
+                let preferred = wgpu::TextureFormat::Bgra8UnormSrgb;
+                if surface_caps.formats.contains(&preferred) {
+                    preferred
+                } else {
+                    surface_caps
+                        .formats
+                        .iter()
+                        .copied()
+                        .find(|format| format.is_srgb())
+                        .or_else(|| surface_caps.formats.first().copied())
+                        .ok_or(RendererConfigError::NoSupportedFormat)?
+                }
+            }
+        };
+
+        // The automatic modes fall back to a supported mode when the surface is configured.
+        let present_mode_supported = matches!(
+            self.present_mode,
+            wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+        ) || surface_caps.present_modes.contains(&self.present_mode);
+        if !present_mode_supported {
+            return Err(RendererConfigError::UnsupportedPresentMode {
+                present_mode: self.present_mode,
+                supported: surface_caps.present_modes.clone(),
+            });
+        }
+
+        Ok(format)
+    }
+}
+
+/// The format of the frames a GPU renderer draws, once its [`RendererConfig`] is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameFormat {
+    /// The format of the surface, and of the multisampled target the UI is drawn into if the
+    /// sample count is more than 1.
+    pub format: wgpu::TextureFormat,
+    /// The number of samples per pixel the frame is drawn with, see
+    /// [`RendererConfig::sample_count`].
+    pub sample_count: u32,
+    pub depth_stencil_format: Option<wgpu::TextureFormat>,
+}
+
+/// Possible errors when a [`RendererConfig`] is not supported by the surface.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RendererConfigError {
+    /// The surface doesn't report any format.
+    NoSupportedFormat,
+    UnsupportedFormat {
+        format: wgpu::TextureFormat,
+        supported: Vec<wgpu::TextureFormat>,
+    },
+    UnsupportedPresentMode {
+        present_mode: wgpu::PresentMode,
+        supported: Vec<wgpu::PresentMode>,
+    },
+    /// The sample count isn't 1, 2, 4, 8 or 16, or the adapter can't render and resolve the
+    /// format of the surface with it.
+    UnsupportedSampleCount {
+        sample_count: u32,
+        format: wgpu::TextureFormat,
+    },
+    /// The format isn't a depth/stencil format the adapter can render to with the sample count.
+    UnsupportedDepthStencilFormat {
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    },
+}
+
+impl fmt::Display for RendererConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RendererConfigError::NoSupportedFormat => {
+                write!(f, "The surface doesn't support any texture format")
+            }
+            RendererConfigError::UnsupportedFormat { format, supported } => write!(
+                f,
+                "The surface doesn't support the format {format:?}, supported formats are {supported:?}"
+            ),
+            RendererConfigError::UnsupportedPresentMode {
+                present_mode,
+                supported,
+            } => write!(
+                f,
+                "The surface doesn't support the present mode {present_mode:?}, supported modes are {supported:?}"
+            ),
+            RendererConfigError::UnsupportedSampleCount {
+                sample_count,
+                format,
+            } => write!(
+                f,
+                "The adapter can't render {format:?} with {sample_count} samples per pixel"
+            ),
+            RendererConfigError::UnsupportedDepthStencilFormat {
+                format,
+                sample_count,
+            } => write!(
+                f,
+                "The adapter can't use {format:?} as a depth/stencil target with {sample_count} samples per pixel"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RendererConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::{PresentMode, SurfaceCapabilities, TextureFormat};
+
+    fn caps(formats: &[TextureFormat], present_modes: &[PresentMode]) -> SurfaceCapabilities {
+        SurfaceCapabilities {
+            formats: formats.to_vec(),
+            present_modes: present_modes.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_format_prefers_bgra_srgb_then_any_srgb() {
+        let config = RendererConfig::default();
+        let fifo = [PresentMode::Fifo];
+
+        let surface = caps(
+            &[TextureFormat::Rgba8UnormSrgb, TextureFormat::Bgra8UnormSrgb],
+            &fifo,
+        );
+        assert_eq!(
+            config.select_format(&surface),
+            Ok(TextureFormat::Bgra8UnormSrgb)
+        );
+
+        let surface = caps(
+            &[TextureFormat::Rgba16Float, TextureFormat::Rgba8UnormSrgb],
+            &fifo,
+        );
+        assert_eq!(
+            config.select_format(&surface),
+            Ok(TextureFormat::Rgba8UnormSrgb)
+        );
+
+        let surface = caps(&[TextureFormat::Rgba16Float], &fifo);
+        assert_eq!(
+            config.select_format(&surface),
+            Ok(TextureFormat::Rgba16Float)
+        );
+
+        assert_eq!(
+            config.select_format(&caps(&[], &fifo)),
+            Err(RendererConfigError::NoSupportedFormat)
+        );
+    }
+
+    #[test]
+    fn requested_format_must_be_supported() {
+        let surface = caps(
+            &[TextureFormat::Bgra8UnormSrgb, TextureFormat::Rgba16Float],
+            &[PresentMode::Fifo],
+        );
+
+        let config = RendererConfig::default().format(TextureFormat::Rgba16Float);
+        assert_eq!(
+            config.select_format(&surface),
+            Ok(TextureFormat::Rgba16Float)
+        );
+
+        let config = RendererConfig::default().format(TextureFormat::Rgba8Unorm);
+        assert_eq!(
+            config.select_format(&surface),
+            Err(RendererConfigError::UnsupportedFormat {
+                format: TextureFormat::Rgba8Unorm,
+                supported: surface.formats.clone(),
+            })
+        );
+    }
+
+    #[test]
+    fn present_mode_must_be_supported_unless_automatic() {
+        let surface = caps(
+            &[TextureFormat::Bgra8UnormSrgb],
+            &[PresentMode::Fifo, PresentMode::Mailbox],
+        );
+
+        let config = RendererConfig::default().present_mode(PresentMode::Mailbox);
+        assert!(config.select_format(&surface).is_ok());
+
+        let config = RendererConfig::default().present_mode(PresentMode::AutoNoVsync);
+        assert!(config.select_format(&surface).is_ok());
+
+        let config = RendererConfig::default().present_mode(PresentMode::Immediate);
+        assert_eq!(
+            config.select_format(&surface),
+            Err(RendererConfigError::UnsupportedPresentMode {
+                present_mode: PresentMode::Immediate,
+                supported: surface.present_modes.clone(),
+            })
+        );
+    }
+
+    #[test]
+    fn sample_count_must_be_supported_and_resolvable() {
+        use wgpu::{TextureFormatFeatureFlags as Flags, TextureFormatFeatures, TextureUsages};
+
+        let format = TextureFormat::Bgra8UnormSrgb;
+        let features = |flags| TextureFormatFeatures {
+            allowed_usages: TextureUsages::RENDER_ATTACHMENT,
+            flags,
+        };
+        let unsupported = |sample_count| {
+            Err(RendererConfigError::UnsupportedSampleCount {
+                sample_count,
+                format,
+            })
+        };
+        let msaa = features(Flags::MULTISAMPLE_X4 | Flags::MULTISAMPLE_RESOLVE);
+
+        let config = RendererConfig::default();
+        assert_eq!(config.check_sample_count(format, &msaa), Ok(()));
+        assert_eq!(
+            config.check_sample_count(format, &features(Flags::MULTISAMPLE_X4)),
+            unsupported(4)
+        );
+
+        let config = RendererConfig::default().sample_count(1);
+        assert_eq!(
+            config.check_sample_count(format, &features(Flags::empty())),
+            Ok(())
+        );
+
+        for sample_count in [3, 8] {
+            let config = RendererConfig::default().sample_count(sample_count);
+            assert_eq!(
+                config.check_sample_count(format, &msaa),
+                unsupported(sample_count)
+            );
+        }
+    }
+}
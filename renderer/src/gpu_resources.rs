@@ -16,25 +16,74 @@ use wgpu::Backends;
 use floem_winit::window::{Window, WindowId};
 
 /// The acquired GPU resources needed for rendering with wgpu.
+///
+/// Everything but the surface can be shared by several windows, see
+/// [`with_surface`](Self::with_surface).
 pub struct GpuResources {
     /// The rendering surface, representing the window or screen where the graphics will be displayed.
     /// It is the interface between wgpu and the platform's windowing system, enabling rendering
     /// onto the screen.
     pub surface: Option<wgpu::Surface<'static>>,
 
+    /// The instance the surface and the adapter were created with.
+    pub instance: Arc<wgpu::Instance>,
+
     /// The adapter that represents the GPU or a rendering backend. It provides information about
     /// the capabilities of the hardware and is used to request a logical device (`wgpu::Device`).
-    pub adapter: wgpu::Adapter,
+    pub adapter: Arc<wgpu::Adapter>,
 
     /// The logical device that serves as an interface to the GPU. It is responsible for creating
     /// resources such as buffers, textures, and pipelines, and manages the execution of commands.
     /// The `device` provides a connection to the physical hardware represented by the `adapter`.
-    pub device: wgpu::Device,
+    pub device: Arc<wgpu::Device>,
 
     /// The command queue that manages the submission of command buffers to the GPU for execution.
     /// It is used to send rendering and computation commands to the device. The `queue` ensures
     /// that commands are executed in the correct order and manages synchronization.
-    pub queue: wgpu::Queue,
+    pub queue: Arc<wgpu::Queue>,
+}
+
+/// The adapter and device requested for rendering.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceConfig {
+    pub power_preference: wgpu::PowerPreference,
+    /// Features the device must support. If the adapter lacks any of them, the request fails
+    /// with [`GpuResourceError::MissingFeatures`].
+    pub required_features: wgpu::Features,
+    /// Features enabled only if the adapter supports them. Check `device.features()` before
+    /// using them.
+    pub optional_features: wgpu::Features,
+    pub limits: wgpu::Limits,
+}
+
+impl DeviceConfig {
+    /// Sets whether a low power or a high performance adapter is preferred.
+    ///
+    /// The default is `PowerPreference::None`.
+    pub fn power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// Adds features the device must support.
+    pub fn required_features(mut self, features: wgpu::Features) -> Self {
+        self.required_features |= features;
+        self
+    }
+
+    /// Adds features enabled only if the adapter supports them.
+    pub fn optional_features(mut self, features: wgpu::Features) -> Self {
+        self.optional_features |= features;
+        self
+    }
+
+    /// Sets the limits the device must support.
+    ///
+    /// The default is `Limits::default()`.
+    pub fn limits(mut self, limits: wgpu::Limits) -> Self {
+        self.limits = limits;
+        self
+    }
 }
 
 impl GpuResources {
@@ -43,17 +92,40 @@ impl GpuResources {
     /// # Parameters
     /// - `on_result`: Function to notify upon completion or error.
     /// - `window`: The window to associate with the created surface.
+    /// - `device_config`: The adapter and device to request.
+    /// - `shared`: Resources of another window to share the instance, adapter and device of.
+    ///   If their adapter can't present to `window`, new ones are requested instead.
     pub fn request<F: Fn(WindowId) + 'static>(
         on_result: F,
         window: Arc<Window>,
+        device_config: DeviceConfig,
+        shared: Option<&GpuResources>,
     ) -> Receiver<Result<Self, GpuResourceError>> {
+        // Channel passing to do async out-of-band within the winit event_loop since wasm can't
+        // execute futures with a return value
+        let (tx, rx) = channel::bounded(1);
+
+        if let Some(shared) = shared {
+            match shared.instance.create_surface(Arc::clone(&window)) {
+                Ok(surface) if shared.adapter.is_surface_supported(&surface) => {
+                    tx.send(Ok(shared.with_surface(Some(surface)))).unwrap();
+                    on_result(window.id());
+                    return rx;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    tx.send(Err(GpuResourceError::SurfaceCreationError(err)))
+                        .unwrap();
+                    on_result(window.id());
+                    return rx;
+                }
+            }
+        }
+
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::util::backend_bits_from_env().unwrap_or(Backends::all()),
             ..Default::default()
         });
-        // Channel passing to do async out-of-band within the winit event_loop since wasm can't
-        // execute futures with a return value
-        let (tx, rx) = channel::bounded(1);
         spawn({
             async move {
                 let surface = match instance.create_surface(Arc::clone(&window)) {
@@ -68,7 +140,7 @@ impl GpuResources {
 
                 let Some(adapter) = instance
                     .request_adapter(&wgpu::RequestAdapterOptions {
-                        power_preference: wgpu::PowerPreference::default(),
+                        power_preference: device_config.power_preference,
                         compatible_surface: Some(&surface),
                         force_fallback_adapter: false,
                     })
@@ -80,15 +152,23 @@ impl GpuResources {
                     return;
                 };
 
-                // Enable the TEXTURE_FORMAT_NV12 (YUV) feature
-                // let required_features = wgpu::Features::TEXTURE_FORMAT_NV12;
+                let missing_features = device_config.required_features - adapter.features();
+                if !missing_features.is_empty() {
+                    tx.send(Err(GpuResourceError::MissingFeatures(missing_features)))
+                        .unwrap();
+                    on_result(window.id());
+                    return;
+                }
+                let required_features = device_config.required_features
+                    | (device_config.optional_features & adapter.features());
 
                 tx.send(
                     adapter
                         .request_device(
                             &wgpu::DeviceDescriptor {
                                 label: None,
-                                // required_features,
+                                required_features,
+                                required_limits: device_config.limits,
                                 ..Default::default()
                             },
                             None,
@@ -97,9 +177,10 @@ impl GpuResources {
                         .map_err(GpuResourceError::DeviceRequestError)
                         .map(|(device, queue)| Self {
                             surface: Some(surface),
-                            adapter,
-                            device,
-                            queue,
+                            instance: Arc::new(instance),
+                            adapter: Arc::new(adapter),
+                            device: Arc::new(device),
+                            queue: Arc::new(queue),
                         }),
                 )
                 .unwrap();
@@ -108,6 +189,18 @@ impl GpuResources {
         });
         rx
     }
+
+    /// Returns resources sharing the instance, adapter, device and queue of these, with
+    /// another surface.
+    pub fn with_surface(&self, surface: Option<wgpu::Surface<'static>>) -> Self {
+        Self {
+            surface,
+            instance: self.instance.clone(),
+            adapter: self.adapter.clone(),
+            device: self.device.clone(),
+            queue: self.queue.clone(),
+        }
+    }
 }
 
 /// Possible errors during GPU resource setup.
@@ -115,6 +208,8 @@ impl GpuResources {
 pub enum GpuResourceError {
     SurfaceCreationError(wgpu::CreateSurfaceError),
     AdapterNotFoundError,
+    /// The adapter doesn't support these required features.
+    MissingFeatures(wgpu::Features),
     DeviceRequestError(wgpu::RequestDeviceError),
 }
 
@@ -127,11 +222,19 @@ impl std::fmt::Display for GpuResourceError {
             GpuResourceError::AdapterNotFoundError => {
                 write!(f, "Failed to find a suitable GPU adapter")
             }
+            GpuResourceError::MissingFeatures(features) => {
+                write!(
+                    f,
+                    "The GPU adapter doesn't support the features {features:?}"
+                )
+            }
             GpuResourceError::DeviceRequestError(err) => write!(f, "Device request error: {}", err),
         }
     }
 }
 
+impl std::error::Error for GpuResourceError {}
+
 /// Spawns a future for execution, adapting to the target environment.
 ///
 /// On WASM (`wasm32`), it uses `wasm_bindgen_futures::spawn_local` to avoid blocking
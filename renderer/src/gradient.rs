@@ -0,0 +1,256 @@
+//! CPU rasterization of gradients.
+//!
+//! Renderers without a native shader for a gradient kind rasterize it here instead,
+//! so every backend produces the same colors for the same `Gradient`.
+
+use std::hash::{Hash, Hasher};
+
+use image::RgbaImage;
+use peniko::{
+    kurbo::{Point, Rect, Vec2},
+    Color, ColorStop, Extend, Gradient, GradientKind,
+};
+
+/// Maps `t` into `0..=1` according to the gradient's extend mode.
+fn extend_offset(extend: Extend, t: f32) -> f32 {
+    match extend {
+        Extend::Pad => t.clamp(0.0, 1.0),
+        Extend::Repeat => t - t.floor(),
+        Extend::Reflect => {
+            let t = t.rem_euclid(2.0);
+            if t > 1.0 {
+                2.0 - t
+            } else {
+                t
+            }
+        }
+    }
+}
+
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color::rgba8(
+        lerp(from.r, to.r),
+        lerp(from.g, to.g),
+        lerp(from.b, to.b),
+        lerp(from.a, to.a),
+    )
+}
+
+/// Returns the color of `stops` at offset `t`, which must already be in `0..=1`.
+pub fn color_at(stops: &[ColorStop], t: f32) -> Color {
+    let Some(first) = stops.first() else {
+        return Color::TRANSPARENT;
+    };
+    if t <= first.offset {
+        return first.color;
+    }
+    for pair in stops.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if t <= to.offset {
+            let span = to.offset - from.offset;
+            if span <= f32::EPSILON {
+                return to.color;
+            }
+            return lerp_color(from.color, to.color, (t - from.offset) / span);
+        }
+    }
+    stops[stops.len() - 1].color
+}
+
+/// Returns the unextended gradient offset at `point`, in the gradient's own coordinates.
+///
+/// Radial gradients are two-point conical gradients. `None` is returned for points
+/// that no circle of the gradient passes through.
+pub fn offset_at(kind: &GradientKind, point: Point) -> Option<f32> {
+    match *kind {
+        GradientKind::Linear { start, end } => {
+            let axis = end - start;
+            let len2 = axis.hypot2();
+            if len2 <= f64::EPSILON {
+                return Some(0.0);
+            }
+            Some(((point - start).dot(axis) / len2) as f32)
+        }
+        GradientKind::Radial {
+            start_center,
+            start_radius,
+            end_center,
+            end_radius,
+        } => {
+            // Solve |p - c(t)| = r(t) with c(t) = c0 + t * (c1 - c0) and r(t) = r0 + t * (r1 - r0).
+            let q: Vec2 = point - start_center;
+            let d: Vec2 = end_center - start_center;
+            let r0 = start_radius as f64;
+            let dr = (end_radius - start_radius) as f64;
+
+            let a = d.hypot2() - dr * dr;
+            let b = -2.0 * (q.dot(d) + r0 * dr);
+            let c = q.hypot2() - r0 * r0;
+
+            let t = if a.abs() <= f64::EPSILON {
+                if b.abs() <= f64::EPSILON {
+                    return None;
+                }
+                -c / b
+            } else {
+                let discriminant = b * b - 4.0 * a * c;
+                if discriminant < 0.0 {
+                    return None;
+                }
+                let sqrt = discriminant.sqrt();
+                let t0 = (-b + sqrt) / (2.0 * a);
+                let t1 = (-b - sqrt) / (2.0 * a);
+                // Prefer the larger circle, as long as its radius isn't negative.
+                let (large, small) = if t0 > t1 { (t0, t1) } else { (t1, t0) };
+                if r0 + large * dr >= 0.0 {
+                    large
+                } else if r0 + small * dr >= 0.0 {
+                    small
+                } else {
+                    return None;
+                }
+            };
+            if r0 + t * dr < 0.0 {
+                return None;
+            }
+            Some(t as f32)
+        }
+        GradientKind::Sweep {
+            center,
+            start_angle,
+            end_angle,
+        } => {
+            let v = point - center;
+            let angle = v.y.atan2(v.x).rem_euclid(std::f64::consts::TAU) as f32;
+            let span = end_angle - start_angle;
+            if span.abs() <= f32::EPSILON {
+                return Some(0.0);
+            }
+            Some((angle - start_angle) / span)
+        }
+    }
+}
+
+/// Returns the color of `gradient` at `point`.
+pub fn gradient_color_at(gradient: &Gradient, point: Point) -> Color {
+    match offset_at(&gradient.kind, point) {
+        Some(t) => color_at(&gradient.stops, extend_offset(gradient.extend, t)),
+        None => Color::TRANSPARENT,
+    }
+}
+
+/// Rasterizes the part of `gradient` covering `rect` into a `width` x `height` image
+/// with straight alpha. `rect` is in the gradient's coordinates.
+pub fn rasterize(gradient: &Gradient, rect: Rect, width: u32, height: u32) -> Option<RgbaImage> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let sx = rect.width() / width as f64;
+    let sy = rect.height() / height as f64;
+    Some(RgbaImage::from_fn(width, height, |x, y| {
+        let point = Point::new(
+            rect.x0 + (x as f64 + 0.5) * sx,
+            rect.y0 + (y as f64 + 0.5) * sy,
+        );
+        let color = gradient_color_at(gradient, point);
+        image::Rgba([color.r, color.g, color.b, color.a])
+    }))
+}
+
+/// Returns a key identifying the rasterized image of `gradient` over `rect` at the given size.
+pub fn rasterized_hash(gradient: &Gradient, rect: Rect, width: u32, height: u32) -> Vec<u8> {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    match gradient.kind {
+        GradientKind::Linear { start, end } => {
+            0u8.hash(&mut hasher);
+            [start.x, start.y, end.x, end.y]
+                .map(f64::to_bits)
+                .hash(&mut hasher);
+        }
+        GradientKind::Radial {
+            start_center,
+            start_radius,
+            end_center,
+            end_radius,
+        } => {
+            1u8.hash(&mut hasher);
+            [start_center.x, start_center.y, end_center.x, end_center.y]
+                .map(f64::to_bits)
+                .hash(&mut hasher);
+            [start_radius, end_radius]
+                .map(f32::to_bits)
+                .hash(&mut hasher);
+        }
+        GradientKind::Sweep {
+            center,
+            start_angle,
+            end_angle,
+        } => {
+            2u8.hash(&mut hasher);
+            [center.x, center.y].map(f64::to_bits).hash(&mut hasher);
+            [start_angle, end_angle].map(f32::to_bits).hash(&mut hasher);
+        }
+    }
+    (gradient.extend as u8).hash(&mut hasher);
+    for stop in gradient.stops.iter() {
+        stop.offset.to_bits().hash(&mut hasher);
+        [stop.color.r, stop.color.g, stop.color.b, stop.color.a].hash(&mut hasher);
+    }
+    [rect.x0, rect.y0, rect.x1, rect.y1]
+        .map(f64::to_bits)
+        .hash(&mut hasher);
+    (width, height).hash(&mut hasher);
+    hasher.finish().to_le_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use peniko::{
+        kurbo::{Point, Rect},
+        Color, ColorStop, Gradient,
+    };
+
+    use super::{color_at, gradient_color_at, rasterize};
+
+    #[test]
+    fn multi_stop_colors() {
+        let stops = [
+            ColorStop {
+                offset: 0.0,
+                color: Color::rgb8(0, 0, 0),
+            },
+            ColorStop {
+                offset: 0.5,
+                color: Color::rgb8(200, 0, 0),
+            },
+            ColorStop {
+                offset: 1.0,
+                color: Color::rgb8(200, 100, 0),
+            },
+        ];
+        assert_eq!(color_at(&stops, 0.25), Color::rgb8(100, 0, 0));
+        assert_eq!(color_at(&stops, 0.5), Color::rgb8(200, 0, 0));
+        assert_eq!(color_at(&stops, 0.75), Color::rgb8(200, 50, 0));
+    }
+
+    #[test]
+    fn radial_and_sweep_offsets() {
+        let radial = Gradient::new_radial((50.0, 50.0), 50.0)
+            .with_stops([Color::rgb8(0, 0, 0), Color::rgb8(200, 200, 200)]);
+        assert_eq!(
+            gradient_color_at(&radial, Point::new(75.0, 50.0)),
+            Color::rgb8(100, 100, 100)
+        );
+
+        let sweep = Gradient::new_sweep((50.0, 50.0), 0.0, std::f32::consts::TAU)
+            .with_stops([Color::rgb8(0, 0, 0), Color::rgb8(200, 200, 200)]);
+        assert_eq!(
+            gradient_color_at(&sweep, Point::new(0.0, 49.999)),
+            Color::rgb8(100, 100, 100)
+        );
+
+        let image = rasterize(&sweep, Rect::new(0.0, 0.0, 100.0, 100.0), 10, 10).unwrap();
+        assert_eq!(image.dimensions(), (10, 10));
+    }
+}
@@ -6,13 +6,15 @@ use std::sync::Arc;
 use image::DynamicImage;
 use peniko::{
     kurbo::{Affine, Point, Rect, Shape},
-    BrushRef,
+    BlendMode, BrushRef,
 };
 pub use resvg::tiny_skia;
 pub use resvg::usvg;
 use text::TextLayout;
 
+pub mod config;
 pub mod gpu_resources;
+pub mod gradient;
 
 pub struct Svg<'a> {
     pub tree: &'a usvg::Tree,
@@ -25,6 +27,15 @@ pub struct Img<'a> {
     pub hash: &'a [u8],
 }
 
+/// Returns the key an image brush is stored under in a renderer's image cache.
+///
+/// Clones of a `peniko::Image` share their data, and so their key.
+pub fn image_brush_hash(image: &peniko::Image) -> Vec<u8> {
+    let mut hash = b"brush".to_vec();
+    hash.extend_from_slice(&image.data.id().to_le_bytes());
+    hash
+}
+
 pub trait Renderer {
     fn begin(&mut self, capture: bool);
 
@@ -37,6 +48,14 @@ pub trait Renderer {
 
     fn clear_clip(&mut self);
 
+    /// Start a layer. Everything drawn until the matching [`Renderer::pop_layer`] is
+    /// composited onto the content below as one image, with `alpha` and `blend_mode`,
+    /// and clipped to `clip`.
+    fn push_layer(&mut self, alpha: f32, blend_mode: impl Into<BlendMode>, clip: &impl Shape);
+
+    /// Composite the most recently pushed layer.
+    fn pop_layer(&mut self);
+
     /// Stroke a [`Shape`].
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64);
 
@@ -45,6 +64,10 @@ pub trait Renderer {
     /// [non-zero fill rule]: https://en.wikipedia.org/wiki/Nonzero-rule
     fn fill<'b>(&mut self, path: &impl Shape, brush: impl Into<BrushRef<'b>>, blur_radius: f64);
 
+    /// Fill a [`Shape`] with an image. One copy of the image is scaled to cover `rect`.
+    /// If `repeat` is set, copies of it are tiled over the rest of the shape.
+    fn fill_img(&mut self, path: &impl Shape, img: &peniko::Image, rect: Rect, repeat: bool);
+
     /// Draw a [`TextLayout`].
     ///
     /// The `pos` parameter specifies the upper-left corner of the layout object
@@ -55,6 +78,11 @@ pub trait Renderer {
 
     fn draw_img(&mut self, img: Img<'_>, rect: Rect);
 
+    /// Draw a GPU texture scaled to cover `rect`, for content rendered with wgpu outside of
+    /// the renderer. The texture must be filterable and have been rendered on the renderer's
+    /// device. Renderers without a GPU device ignore this.
+    fn draw_texture(&mut self, texture: &Arc<wgpu::TextureView>, rect: Rect);
+
     fn finish<F>(&mut self, callback: F) -> Option<DynamicImage>
     where
         F: FnOnce(
@@ -21,8 +21,10 @@ use crate::{
     app::{add_app_update_event, AppUpdateEvent},
     id::ViewId,
     menu::Menu,
+    theme::WindowTheme,
     update::{UpdateMessage, UPDATE_MESSAGES},
     view::View,
+    window::FramePacing,
     window_handle::{get_current_view, set_current_view},
 };
 
@@ -207,6 +209,20 @@ pub fn set_window_title(title: String) {
     add_update_message(UpdateMessage::SetWindowTitle { title });
 }
 
+/// Set the theme of the window and restyle all of its views
+///
+/// Pass `None` to remove the theme, leaving only the styles set on the views themselves.
+pub fn set_window_theme(theme: Option<WindowTheme>) {
+    add_update_message(UpdateMessage::SetTheme(theme));
+}
+
+/// Set when the window renders new frames
+///
+/// The change takes effect on the next frame, so it can be made while a scene is animating.
+pub fn set_frame_pacing(frame_pacing: FramePacing) {
+    add_update_message(UpdateMessage::SetFramePacing(frame_pacing));
+}
+
 /// Focus the window
 pub fn focus_window() {
     add_update_message(UpdateMessage::FocusWindow);
@@ -1,6 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use floem_reactive::WriteSignal;
+use floem_renderer::gpu_resources::DeviceConfig;
 use floem_winit::{
     event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy},
     monitor::MonitorHandle,
@@ -10,12 +11,15 @@ use parking_lot::Mutex;
 #[allow(deprecated)]
 use raw_window_handle::HasRawDisplayHandle;
 
+#[cfg(feature = "engine")]
+use crate::engine::{engine_factory, Engine};
 use crate::{
     action::{Timer, TimerToken},
     app_handle::ApplicationHandle,
     clipboard::Clipboard,
     inspector::Capture,
     profiler::Profile,
+    renderer::RendererError,
     view::{IntoView, View},
     window::WindowConfig,
 };
@@ -134,6 +138,41 @@ impl Application {
         self
     }
 
+    /// Sets the GPU adapter and device requested for every window of the application,
+    /// such as the features and limits they need.
+    ///
+    /// ```rust,no_run
+    /// use floem::{Application, DeviceConfig};
+    ///
+    /// Application::new().with_device_config(
+    ///     DeviceConfig::default()
+    ///         .power_preference(wgpu::PowerPreference::HighPerformance)
+    ///         .optional_features(wgpu::Features::TEXTURE_FORMAT_NV12),
+    /// );
+    /// ```
+    pub fn with_device_config(mut self, device_config: DeviceConfig) -> Self {
+        self.handle.as_mut().unwrap().device_config = device_config;
+        self
+    }
+
+    /// Calls `f` when the renderer of a window can't be created, instead of panicking.
+    ///
+    /// The window stays open but paints nothing. `f` can close it, or report the error and
+    /// open a new window with a different [`BackendPreference`](crate::window::BackendPreference).
+    pub fn on_renderer_error(mut self, f: impl Fn(WindowId, RendererError) + 'static) -> Self {
+        self.handle.as_mut().unwrap().renderer_error = Some(Box::new(f));
+        self
+    }
+
+    /// Renders an `E` under the UI of every window of the application.
+    ///
+    /// Each window creates its engine with [`Engine::init`] once its GPU resources are acquired.
+    #[cfg(feature = "engine")]
+    pub fn with_engine<E: Engine>(mut self) -> Self {
+        self.handle.as_mut().unwrap().engine = Some(engine_factory::<E>());
+        self
+    }
+
     /// Create a new window for the application, if you want multiple windows,
     /// just chain more window method to the builder.
     ///
@@ -174,7 +213,9 @@ impl Application {
                 }
                 floem_winit::event::Event::Suspended => {}
                 floem_winit::event::Event::Resumed => {}
-                floem_winit::event::Event::AboutToWait => {}
+                floem_winit::event::Event::AboutToWait => {
+                    handle.about_to_wait();
+                }
                 floem_winit::event::Event::LoopExiting => {
                     if let Some(action) = self.event_listener.as_ref() {
                         action(AppEvent::WillTerminate);
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, mem, rc::Rc, time::Duration};
+use std::{collections::HashMap, mem, rc::Rc};
 
 use floem_reactive::SignalUpdate;
 
@@ -12,19 +12,24 @@ use wgpu::web_sys;
 
 use floem_winit::{
     dpi::{LogicalPosition, LogicalSize, PhysicalSize},
-    event::WindowEvent,
+    event::{Force, WindowEvent},
     event_loop::{ControlFlow, EventLoopProxy, EventLoopWindowTarget},
     window::WindowId,
 };
 
+use floem_renderer::gpu_resources::{DeviceConfig, GpuResources};
 use peniko::kurbo::{Point, Size};
 
+#[cfg(feature = "engine")]
+use crate::engine::EngineFactory;
 use crate::{
     action::{Timer, TimerToken},
     app::{AppUpdateEvent, UserEvent, APP_UPDATE_EVENTS},
     ext_event::EXT_EVENT_HANDLER,
     inspector::Capture,
     profiler::{Profile, ProfileEvent},
+    renderer::RendererError,
+    theme::WindowTheme,
     view::View,
     window::WindowConfig,
     window_handle::WindowHandle,
@@ -34,6 +39,14 @@ use crate::{
 pub struct ApplicationHandle {
     pub window_handles: HashMap<floem_winit::window::WindowId, WindowHandle>,
     pub timers: HashMap<TimerToken, Timer>,
+    /// The adapter and device requested for each window.
+    pub(crate) device_config: DeviceConfig,
+    /// The instance, adapter, device and queue all windows render with.
+    pub(crate) gpu_context: GpuContext<GpuResources>,
+    /// Called when the renderer of a window can't be created.
+    pub(crate) renderer_error: Option<Box<dyn Fn(WindowId, RendererError)>>,
+    #[cfg(feature = "engine")]
+    pub(crate) engine: Option<EngineFactory>,
 }
 
 impl ApplicationHandle {
@@ -41,6 +54,11 @@ impl ApplicationHandle {
         Self {
             window_handles: HashMap::new(),
             timers: HashMap::new(),
+            device_config: DeviceConfig::default(),
+            gpu_context: GpuContext::None,
+            renderer_error: None,
+            #[cfg(feature = "engine")]
+            engine: None,
         }
     }
 
@@ -61,10 +79,19 @@ impl ApplicationHandle {
                 event_loop.exit();
             }
             UserEvent::GpuResourcesUpdate { window_id } => {
-                self.window_handles
-                    .get_mut(&window_id)
-                    .unwrap()
-                    .init_renderer();
+                self.gpu_resources_received(window_id, event_proxy);
+                let window_handle = self.window_handles.get_mut(&window_id).unwrap();
+                #[cfg(feature = "engine")]
+                let result = window_handle.init_renderer(self.engine.as_ref());
+                #[cfg(not(feature = "engine"))]
+                let result = window_handle.init_renderer();
+                if let Err(err) = result {
+                    match &self.renderer_error {
+                        Some(renderer_error) => renderer_error(window_id, err),
+                        None => panic!("Failed to create the renderer: {err}"),
+                    }
+                    return;
+                }
             }
         }
     }
@@ -183,17 +210,9 @@ impl ApplicationHandle {
         match event {
             WindowEvent::ActivationTokenDone { .. } => {}
             WindowEvent::Resized(size) => {
-                let logical_size: LogicalSize<f64> = size.to_logical(window_handle.scale);
-                let kurbo_size = Size::new(logical_size.width, logical_size.height);
-
-                if (window_handle.handle_window_resized.is_some()) {
-                    window_handle
-                        .handle_window_resized
-                        .as_mut()
-                        .expect("Couldn't get callback")(size, logical_size);
-                }
-
-                window_handle.size(kurbo_size);
+                let size: LogicalSize<f64> = size.to_logical(window_handle.scale);
+                let size = Size::new(size.width, size.height);
+                window_handle.size(size);
             }
             WindowEvent::Moved(position) => {
                 let position: LogicalPosition<f64> = position.to_logical(window_handle.scale);
@@ -209,8 +228,12 @@ impl ApplicationHandle {
             WindowEvent::DroppedFile(path) => {
                 window_handle.dropped_file(path);
             }
-            WindowEvent::HoveredFile(_) => {}
-            WindowEvent::HoveredFileCancelled => {}
+            WindowEvent::HoveredFile(path) => {
+                window_handle.hovered_file(path);
+            }
+            WindowEvent::HoveredFileCancelled => {
+                window_handle.hovered_file_cancelled();
+            }
             WindowEvent::Focused(focused) => {
                 window_handle.focused(focused);
             }
@@ -220,44 +243,19 @@ impl ApplicationHandle {
                 ..
             } => {
                 if !is_synthetic {
-                    window_handle.key_event(event.clone());
-
-                    if (window_handle.handle_keyboard_input.is_some()) {
-                        window_handle
-                            .handle_keyboard_input
-                            .as_mut()
-                            .expect("Couldn't get callback")(event.clone());
-                    }
+                    window_handle.key_event(event.into());
                 }
             }
             WindowEvent::ModifiersChanged(modifiers) => {
                 window_handle.modifiers_changed(modifiers.state());
-                if (window_handle.handle_modifiers_changed.is_some()) {
-                    window_handle
-                        .handle_modifiers_changed
-                        .as_mut()
-                        .expect("Couldn't get callback")(modifiers);
-                }
             }
             WindowEvent::Ime(ime) => {
                 window_handle.ime(ime);
             }
             WindowEvent::CursorMoved { position, .. } => {
-                let position_logical: LogicalPosition<f64> =
-                    position.to_logical(window_handle.scale);
-                let point = Point::new(position_logical.x, position_logical.y);
+                let position: LogicalPosition<f64> = position.to_logical(window_handle.scale);
+                let point = Point::new(position.x, position.y);
                 window_handle.pointer_move(point);
-                if (window_handle.handle_cursor_moved.is_some()) {
-                    window_handle
-                        .handle_cursor_moved
-                        .as_ref()
-                        .expect("Couldn't get callback")(
-                        position.x,
-                        position.y,
-                        position_logical.x,
-                        position_logical.y,
-                    );
-                }
             }
             WindowEvent::CursorEntered { .. } => {}
             WindowEvent::CursorLeft { .. } => {
@@ -265,28 +263,47 @@ impl ApplicationHandle {
             }
             WindowEvent::MouseWheel { delta, .. } => {
                 window_handle.mouse_wheel(delta);
-                if (window_handle.handle_mouse_wheel.is_some()) {
-                    window_handle
-                        .handle_mouse_wheel
-                        .as_mut()
-                        .expect("Couldn't get callback")(delta);
-                }
             }
             WindowEvent::MouseInput { state, button, .. } => {
                 window_handle.mouse_input(button, state);
-                if (window_handle.handle_mouse_input.is_some()) {
-                    window_handle
-                        .handle_mouse_input
-                        .as_ref()
-                        .expect("Couldn't get callback")(button, state);
-                }
             }
-            WindowEvent::TouchpadMagnify { .. } => {}
-            WindowEvent::SmartMagnify { .. } => {}
-            WindowEvent::TouchpadRotate { .. } => {}
-            WindowEvent::TouchpadPressure { .. } => {}
+            WindowEvent::TouchpadMagnify { delta, phase, .. } => {
+                window_handle.touchpad_magnify(delta, phase);
+            }
+            WindowEvent::SmartMagnify { .. } => {
+                window_handle.double_tap_zoom();
+            }
+            WindowEvent::TouchpadRotate { delta, phase, .. } => {
+                window_handle.touchpad_rotate(delta, phase);
+            }
+            WindowEvent::TouchpadPressure {
+                pressure, stage, ..
+            } => {
+                window_handle.touchpad_pressure(pressure, stage);
+            }
             WindowEvent::AxisMotion { .. } => {}
-            WindowEvent::Touch(_) => {}
+            WindowEvent::Touch(touch) => {
+                let position: LogicalPosition<f64> = touch.location.to_logical(window_handle.scale);
+                let point = Point::new(position.x, position.y);
+                match touch.force {
+                    // Only the touches of a pen report their altitude.
+                    Some(
+                        force @ Force::Calibrated {
+                            altitude_angle: Some(altitude),
+                            ..
+                        },
+                    ) => {
+                        let tilt = 90.0 - altitude.to_degrees();
+                        window_handle.pen(touch.phase, point, force.normalized(), Some(tilt))
+                    }
+                    force => window_handle.touch(
+                        touch.id,
+                        touch.phase,
+                        point,
+                        force.map(|force| force.normalized()),
+                    ),
+                }
+            }
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                 window_handle.scale(scale_factor);
             }
@@ -297,33 +314,9 @@ impl ApplicationHandle {
             WindowEvent::MenuAction(id) => {
                 window_handle.menu_action(id);
             }
-            // WindowEvent::RedrawRequested => {
-            //     window_handle.render_frame();
-            //     // window_handle.call_render_callback();
-            //     window_handle
-            //         .window
-            //         .as_ref()
-            //         .expect("Couldn't get window")
-            //         .request_redraw();
-            // }
             WindowEvent::RedrawRequested => {
-                // Control frame rate at the start of the handler
-                let frame_duration = Duration::from_secs_f64(1.0 / 60.0);
-                let start_time = Instant::now();
-
                 window_handle.render_frame();
-                // window_handle.call_render_callback();
-
-                window_handle
-                    .window
-                    .as_ref()
-                    .expect("Couldn't get window")
-                    .request_redraw();
-
-                let elapsed_time = start_time.elapsed();
-                if elapsed_time < frame_duration {
-                    std::thread::sleep(frame_duration - elapsed_time);
-                }
+                window_handle.schedule_next_frame();
             }
         }
 
@@ -364,9 +357,12 @@ impl ApplicationHandle {
             undecorated,
             window_level,
             apply_default_theme,
+            theme,
             mac_os_config,
             web_config,
             font_embolden,
+            frame_pacing,
+            renderer,
         }: WindowConfig,
     ) -> Option<WindowId> {
         let logical_size = size.map(|size| LogicalSize::new(size.width, size.height));
@@ -488,18 +484,68 @@ impl ApplicationHandle {
         let window_id = window.id();
         let window_handle = WindowHandle::new(
             window,
-            event_proxy,
             view_fn,
             transparent,
-            apply_default_theme,
+            theme.or_else(|| apply_default_theme.then(WindowTheme::default)),
             logical_size,
             font_embolden,
+            frame_pacing,
+            renderer,
         );
         self.window_handles.insert(window_id, window_handle);
+        self.request_gpu_resources(window_id, event_proxy);
 
         Some(window_id)
     }
 
+    /// Requests the GPU resources of a window, with those of the app if they were acquired.
+    ///
+    /// The first window requests the app's GPU context. Windows opened while that request is
+    /// pending, which can only happen on the web, wait for it to complete.
+    fn request_gpu_resources(
+        &mut self,
+        window_id: WindowId,
+        event_proxy: EventLoopProxy<UserEvent>,
+    ) {
+        let Some(window_handle) = self.window_handles.get_mut(&window_id) else {
+            return;
+        };
+        let Some(shared) = self.gpu_context.request(window_id) else {
+            return;
+        };
+        window_handle.request_gpu_resources(
+            event_proxy.clone(),
+            self.device_config.clone(),
+            shared,
+        );
+        // Natively, the request completes before it returns.
+        self.gpu_resources_received(window_id, event_proxy);
+    }
+
+    /// Stores the GPU resources of a window as the app's GPU context if they were requested as
+    /// such, and requests those of the windows waiting for them.
+    fn gpu_resources_received(
+        &mut self,
+        window_id: WindowId,
+        event_proxy: EventLoopProxy<UserEvent>,
+    ) {
+        if !self.gpu_context.is_pending_for(window_id) {
+            return;
+        }
+        let received = match self.window_handles.get_mut(&window_id) {
+            Some(window_handle) => window_handle.paint_state.received_gpu_resources(),
+            // The window was closed before its request completed.
+            None => Some(None),
+        };
+        let Some(gpu_resources) = received else {
+            return;
+        };
+        let gpu_resources = gpu_resources.map(|gpu_resources| gpu_resources.with_surface(None));
+        for window_id in self.gpu_context.received(gpu_resources) {
+            self.request_gpu_resources(window_id, event_proxy.clone());
+        }
+    }
+
     fn close_window(
         &mut self,
         window_id: WindowId,
@@ -517,6 +563,13 @@ impl ApplicationHandle {
         }
     }
 
+    /// Called once the event loop has handled all the pending platform events.
+    pub(crate) fn about_to_wait(&mut self) {
+        for handle in self.window_handles.values_mut() {
+            handle.flush_file_drag();
+        }
+    }
+
     fn capture_window(&mut self, window_id: WindowId) -> Option<Capture> {
         self.window_handles
             .get_mut(&window_id)
@@ -584,3 +637,96 @@ impl ApplicationHandle {
         self.fire_timer(event_loop);
     }
 }
+
+/// The instance, adapter, device and queue shared by the windows of the application.
+///
+/// They are requested along with the surface of the first window. Windows opened while that
+/// request is pending wait for it, so they don't request a device of their own.
+pub(crate) enum GpuContext<R> {
+    /// Not requested yet, or the request failed.
+    None,
+    /// Requested by `window_id`, with the windows waiting for it.
+    Pending {
+        window_id: WindowId,
+        queued: Vec<WindowId>,
+    },
+    Ready(R),
+}
+
+impl<R> GpuContext<R> {
+    /// Returns the resources `window_id` should share when it requests its own, or `None` if
+    /// it has to wait for the pending request.
+    ///
+    /// Without a context, the request of `window_id` becomes the app's.
+    fn request(&mut self, window_id: WindowId) -> Option<Option<&R>> {
+        match self {
+            GpuContext::None => {
+                *self = GpuContext::Pending {
+                    window_id,
+                    queued: Vec::new(),
+                };
+                Some(None)
+            }
+            GpuContext::Pending { queued, .. } => {
+                queued.push(window_id);
+                None
+            }
+            GpuContext::Ready(resources) => Some(Some(resources)),
+        }
+    }
+
+    fn is_pending_for(&self, id: WindowId) -> bool {
+        matches!(self, GpuContext::Pending { window_id, .. } if *window_id == id)
+    }
+
+    /// Completes the pending request, returning the windows that waited for it.
+    fn received(&mut self, resources: Option<R>) -> Vec<WindowId> {
+        let context = match resources {
+            Some(resources) => GpuContext::Ready(resources),
+            None => GpuContext::None,
+        };
+        match mem::replace(self, context) {
+            GpuContext::Pending { queued, .. } => queued,
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use floem_winit::window::WindowId;
+
+    use super::GpuContext;
+
+    #[test]
+    fn windows_opened_together_share_the_device() {
+        let (first, second) = (WindowId::from(1), WindowId::from(2));
+        let device = Arc::new("device");
+        let mut context = GpuContext::None;
+
+        // The first window requests the context, the second waits for it.
+        assert!(matches!(context.request(first), Some(None)));
+        assert!(context.request(second).is_none());
+        assert!(context.is_pending_for(first));
+
+        assert_eq!(context.received(Some(device.clone())), vec![second]);
+        let Some(Some(shared)) = context.request(second) else {
+            panic!("the second window should share the device of the first");
+        };
+        assert!(Arc::ptr_eq(shared, &device));
+    }
+
+    #[test]
+    fn failed_request_lets_the_next_window_request_the_context() {
+        let (first, second) = (WindowId::from(1), WindowId::from(2));
+        let mut context = GpuContext::<()>::None;
+
+        context.request(first);
+        context.request(second);
+        assert_eq!(context.received(None), vec![second]);
+        assert!(matches!(context.request(second), Some(None)));
+        assert!(context.is_pending_for(second));
+    }
+}
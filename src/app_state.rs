@@ -12,6 +12,7 @@ use crate::{
     menu::Menu,
     responsive::{GridBreakpoints, ScreenSizeBp},
     style::{CursorStyle, Style, StyleClassRef, StyleSelector},
+    touch::TouchId,
     view_storage::VIEW_STORAGE,
 };
 
@@ -23,6 +24,9 @@ pub struct AppState {
     /// when a view is active, it gets mouse event even when the mouse is
     /// not on it
     pub(crate) active: Option<ViewId>,
+    /// The view receiving the events of each finger on a touchscreen, which is the view
+    /// that handled the start of the touch.
+    pub(crate) touch_targets: HashMap<TouchId, ViewId>,
     pub(crate) root_view_id: ViewId,
     pub(crate) root: Option<NodeId>,
     pub(crate) root_size: Size,
@@ -30,12 +34,18 @@ pub struct AppState {
     pub(crate) scheduled_updates: Vec<FrameUpdate>,
     pub(crate) request_compute_layout: bool,
     pub(crate) request_paint: bool,
+    /// Whether a view asked for another frame while the last one was painted, see
+    /// [`PaintCx::request_next_frame`](crate::context::PaintCx::request_next_frame).
+    pub(crate) next_frame_requested: bool,
     pub(crate) disabled: HashSet<ViewId>,
     pub(crate) keyboard_navigable: HashSet<ViewId>,
     pub(crate) draggable: HashSet<ViewId>,
     pub(crate) dragging: Option<DragState>,
     pub(crate) drag_start: Option<(ViewId, Point)>,
     pub(crate) dragging_over: HashSet<ViewId>,
+    /// The views under the cursor while files from another application are dragged over the
+    /// window.
+    pub(crate) file_drag_over: HashSet<ViewId>,
     pub(crate) screen_size_bp: ScreenSizeBp,
     pub(crate) grid_bps: GridBreakpoints,
     pub(crate) clicking: HashSet<ViewId>,
@@ -60,11 +70,13 @@ impl AppState {
             focus: None,
             prev_focus: None,
             active: None,
+            touch_targets: HashMap::new(),
             scale: 1.0,
             root_size: Size::ZERO,
             screen_size_bp: ScreenSizeBp::Xs,
             scheduled_updates: Vec::new(),
             request_paint: false,
+            next_frame_requested: false,
             request_compute_layout: false,
             disabled: HashSet::new(),
             keyboard_navigable: HashSet::new(),
@@ -72,6 +84,7 @@ impl AppState {
             dragging: None,
             drag_start: None,
             dragging_over: HashSet::new(),
+            file_drag_over: HashSet::new(),
             clicking: HashSet::new(),
             hovered: HashSet::new(),
             cursor: None,
@@ -118,6 +131,7 @@ impl AppState {
         self.keyboard_navigable.remove(&id);
         self.draggable.remove(&id);
         self.dragging_over.remove(&id);
+        self.file_drag_over.remove(&id);
         self.clicking.remove(&id);
         self.hovered.remove(&id);
         self.clicking.remove(&id);
@@ -131,6 +145,7 @@ impl AppState {
         if self.active == Some(id) {
             self.active = None;
         }
+        self.touch_targets.retain(|_, target| *target != id);
     }
 
     pub(crate) fn can_focus(&self, id: ViewId) -> bool {
@@ -164,6 +179,30 @@ impl AppState {
             .unwrap_or(false)
     }
 
+    /// Whether the view is a drop target accepting the payload being dragged.
+    pub(crate) fn accepts_drop(&self, id: ViewId) -> bool {
+        let Some(payload) = self
+            .dragging
+            .as_ref()
+            .filter(|dragging| dragging.released_at.is_none())
+            .and_then(|dragging| dragging.payload.as_ref())
+        else {
+            return false;
+        };
+        let accepts = id
+            .state()
+            .borrow()
+            .drop_target
+            .as_ref()
+            .map(|target| target.accepts.clone());
+        accepts.is_some_and(|accepts| accepts(payload))
+    }
+
+    /// Whether a payload the view accepts is dragged over it.
+    pub(crate) fn is_drag_over(&self, id: &ViewId) -> bool {
+        self.dragging_over.contains(id) && self.accepts_drop(*id)
+    }
+
     pub fn set_root_size(&mut self, size: Size) {
         self.root_size = size;
         self.compute_layout();
@@ -4,23 +4,27 @@ use std::sync::{Arc, Mutex, MutexGuard};
 use std::usize;
 
 use crate::event::{Event, EventListener, EventPropagation};
+use crate::id::ViewId;
 use crate::kurbo::Point;
 use crate::peniko::{Brush, Color, ColorStop, ColorStops, Extend, Gradient, GradientKind};
 use crate::reactive::RwSignal;
-use crate::style::{Background, CursorStyle, Transition};
+use crate::style::{Background, CursorStyle, Style, StyleClass, StyleClassRef, Transition};
+use crate::style_class;
 use crate::taffy::AlignItems;
+use crate::theme::Theme;
+use crate::view::View;
 use crate::views::{
     container, dyn_container, empty, label, scroll, stack, static_label, tab, text_input, tooltip,
     virtual_stack, VirtualDirection, VirtualItemSize,
 };
 use crate::views::{h_stack, Decorators};
-use crate::views::{svg, v_stack};
+use crate::views::{svg, v_stack, Svg, SvgClass};
 use crate::{views::button, IntoView};
+
+use super::icon;
 use floem_reactive::{ReadSignal, SignalGet};
 
 use crate::unit::{DurationUnitExt, UnitExt};
-use once_cell::sync::Lazy;
-use std::collections::HashMap;
 use std::time::Duration;
 use uuid::Uuid;
 
@@ -33,68 +37,216 @@ pub fn rgb_to_wgpu(r: u8, g: u8, b: u8, a: f32) -> [f32; 4] {
     ]
 }
 
-static ICON_CACHE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// The buttons in this module are styled through these classes, so a `Theme` can restyle them.
+// Their default styles are generated from the theme's tokens in `button_styles`.
+style_class!(pub CommonButtonClass);
+style_class!(pub PrimaryButtonClass);
+style_class!(pub SecondaryButtonClass);
+style_class!(pub GhostButtonClass);
+style_class!(pub DangerButtonClass);
+style_class!(pub SmallButtonClass);
+style_class!(pub MediumButtonClass);
+style_class!(pub LargeButtonClass);
+style_class!(pub SelectedButtonClass);
+style_class!(pub IconButtonClass);
+style_class!(pub ChoiceButtonClass);
+style_class!(pub NavButtonClass);
+style_class!(pub OptionButtonClass);
+style_class!(pub TabButtonClass);
+
+/// The color scheme of a button from the `common` widget kit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ButtonVariant {
+    Primary,
+    #[default]
+    Secondary,
+    Ghost,
+    Danger,
+}
+
+impl ButtonVariant {
+    fn class_ref(self) -> StyleClassRef {
+        match self {
+            ButtonVariant::Primary => PrimaryButtonClass::class_ref(),
+            ButtonVariant::Secondary => SecondaryButtonClass::class_ref(),
+            ButtonVariant::Ghost => GhostButtonClass::class_ref(),
+            ButtonVariant::Danger => DangerButtonClass::class_ref(),
+        }
+    }
+
+    /// Replaces the variant class of `view`.
+    ///
+    /// ```rust
+    /// # use floem::common::{small_button, ButtonVariant};
+    /// # use floem::reactive::create_rw_signal;
+    /// let active = create_rw_signal(false);
+    /// let delete = ButtonVariant::Danger.apply(small_button("Delete", "trash", |_| {}, active));
+    /// ```
+    pub fn apply<D: Decorators>(self, view: D) -> D::DV {
+        let view = view.into_view();
+        let id = view.id();
+        for variant in [
+            ButtonVariant::Primary,
+            ButtonVariant::Secondary,
+            ButtonVariant::Ghost,
+            ButtonVariant::Danger,
+        ] {
+            id.remove_class(variant.class_ref());
+        }
+        id.add_class(self.class_ref());
+        keep_selected_last(id);
+        view
+    }
+}
+
+/// The size of a button from the `common` widget kit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ButtonSize {
+    Sm,
+    #[default]
+    Md,
+    Lg,
+}
+
+impl ButtonSize {
+    fn class_ref(self) -> StyleClassRef {
+        match self {
+            ButtonSize::Sm => SmallButtonClass::class_ref(),
+            ButtonSize::Md => MediumButtonClass::class_ref(),
+            ButtonSize::Lg => LargeButtonClass::class_ref(),
+        }
+    }
+
+    /// Replaces the size class of `view`.
+    pub fn apply<D: Decorators>(self, view: D) -> D::DV {
+        let view = view.into_view();
+        let id = view.id();
+        for size in [ButtonSize::Sm, ButtonSize::Md, ButtonSize::Lg] {
+            id.remove_class(size.class_ref());
+        }
+        id.add_class(self.class_ref());
+        keep_selected_last(id);
+        view
+    }
+}
 
-pub fn create_icon(name: &str) -> String {
-    // Try to retrieve from cache first
-    if let Some(icon) = ICON_CACHE.lock().unwrap().get(name) {
-        return icon.clone();
+/// Classes added later take precedence, so the selected style has to stay after the variant.
+fn keep_selected_last(id: ViewId) {
+    let selected = SelectedButtonClass::class_ref();
+    if id.state().borrow().classes.contains(&selected) {
+        id.remove_class(selected);
+        id.add_class(selected);
     }
+}
+
+/// The styles of the buttons in this module, generated from the tokens of `theme`.
+pub(crate) fn button_styles(theme: &Theme) -> Style {
+    let palette = &theme.palette;
+    let font_size = theme.typography.font_size;
+    let focus_visible_style = Style::new().border(2.).border_color(palette.focus);
 
-    // If not in cache, load and cache it
-    let icon = match name {
-        "plus" => include_str!("../assets/plus-thin.svg"),
-        "minus" => include_str!("../assets/minus-thin.svg"),
-        "windmill" => include_str!("../assets/windmill-thin.svg"),
-        "gear" => include_str!("../assets/gear-six-thin.svg"),
-        "brush" => include_str!("../assets/paint-brush-thin.svg"),
-        "shapes" => include_str!("../assets/shapes-thin.svg"),
-        "arrow-left" => include_str!("../assets/arrow-left-thin.svg"),
-        "polygon" => include_str!("../assets/polygon-thin.svg"),
-        "octagon" => include_str!("../assets/octagon-thin.svg"),
-        "square" => include_str!("../assets/square-thin.svg"),
-        "triangle" => include_str!("../assets/triangle-thin.svg"),
-        "dot" => include_str!("../assets/dot-outline-thin.svg"),
-        "dots-vertical" => include_str!("../assets/dots-three-outline-vertical-thin.svg"),
-        "sphere" => include_str!("../assets/sphere-thin.svg"),
-        "gizmo" => include_str!("../assets/vector-three-thin.svg"),
-        "book" => include_str!("../assets/book-open-thin.svg"),
-        "cube" => include_str!("../assets/cube-focus-thin.svg"),
-        "faders" => include_str!("../assets/faders-thin.svg"),
-        "map" => include_str!("../assets/map-trifold-thin.svg"),
-        "panorama" => include_str!("../assets/panorama-thin.svg"),
-        "speedometer" => include_str!("../assets/speedometer-thin.svg"),
-        "motion-arrow" => include_str!("../assets/arrow-fat-lines-right-thin.svg"),
-        "atom" => include_str!("../assets/atom-thin.svg"),
-        "brain" => include_str!("../assets/brain-thin.svg"),
-        "broadcast" => include_str!("../assets/broadcast-thin.svg"),
-        "circles" => include_str!("../assets/circles-three-thin.svg"),
-        "fast-forward" => include_str!("../assets/fast-forward-thin.svg"),
-        "folder-plus" => include_str!("../assets/folder-plus-thin.svg"),
-        "bone" => include_str!("../assets/bone-thin.svg"),
-        "caret-down" => include_str!("../assets/caret-down-thin.svg"),
-        "caret-right" => include_str!("../assets/caret-right-thin.svg"),
-        "translate" => include_str!("../assets/arrows-out-cardinal-thin.svg"),
-        "rotate" => include_str!("../assets/arrows-clockwise-thin.svg"),
-        "scale" => include_str!("../assets/resize-thin.svg"),
-        "image" => include_str!("../assets/image-thin.svg"),
-        "text" => include_str!("../assets/text-t-thin.svg"),
-        "video" => include_str!("../assets/video-thin.svg"),
-        "copy" => include_str!("../assets/copy-thin.svg"),
-        "trash" => include_str!("../assets/trash-thin.svg"),
-        "x" => include_str!("../assets/x-thin.svg"),
-        _ => "",
+    let variant = |background: Color, color: Color, hover: Color| {
+        Style::new()
+            .background(background)
+            .color(color)
+            .hover(|s| s.background(hover))
+    };
+    let size = |height: f64, padding: f64, font_size: f32, icon: f64| {
+        Style::new()
+            .height(height)
+            .padding_vert(2.)
+            .padding_horiz(padding)
+            .font_size(font_size)
+            .class(SvgClass, |s| s.size(icon, icon))
     };
 
-    // Store in cache
-    ICON_CACHE
-        .lock()
-        .unwrap()
-        .insert(name.to_string(), icon.to_string());
+    Style::new()
+        .class(CommonButtonClass, |s| {
+            s.justify_center()
+                .align_items(AlignItems::Center)
+                .border(0)
+                .border_radius(theme.radii.large)
+                .transition(Background, Transition::ease_in_out(200.millis()))
+                .focus_visible(|_| focus_visible_style)
+                .hover(|s| s.cursor(CursorStyle::Pointer))
+        })
+        .class(PrimaryButtonClass, |_| {
+            variant(
+                palette.primary,
+                palette.on_primary,
+                palette.primary.with_alpha_factor(0.85),
+            )
+        })
+        .class(SecondaryButtonClass, |_| {
+            variant(palette.surface, palette.control_text, palette.hover)
+        })
+        .class(GhostButtonClass, |_| {
+            variant(Color::TRANSPARENT, palette.text, palette.hover)
+        })
+        .class(DangerButtonClass, |_| {
+            variant(
+                palette.danger,
+                palette.on_primary,
+                palette.danger.with_alpha_factor(0.85),
+            )
+        })
+        .class(SmallButtonClass, |_| {
+            size(24., theme.spacing.medium + 3., font_size - 1., 16.)
+        })
+        .class(MediumButtonClass, |_| {
+            size(28., theme.spacing.medium * 2., font_size, 20.)
+        })
+        .class(LargeButtonClass, |_| {
+            size(36., theme.spacing.medium * 3., font_size + 2., 24.)
+        })
+        .class(SelectedButtonClass, |s| {
+            s.background(palette.selected)
+                .color(palette.text)
+                .hover(|s| s.background(palette.selected_hover))
+        })
+        .class(IconButtonClass, |s| s.aspect_ratio(1.0).padding(0.))
+        .class(ChoiceButtonClass, |s| {
+            s.border(1).border_color(palette.border).padding(4.0)
+        })
+        .class(NavButtonClass, |s| {
+            s.size(70, 70)
+                .background(palette.control)
+                .box_shadow_blur(15)
+                .box_shadow_spread(4)
+                .box_shadow_color(palette.shadow.with_alpha_factor(0.36))
+                .hover(|s| s.background(palette.hover))
+                .class(SvgClass, |s| s.size(30, 30))
+        })
+        .class(OptionButtonClass, |s| {
+            s.size(60, 60)
+                .background(palette.control)
+                .border(1.0)
+                .border_color(palette.border)
+                .font_size(font_size - 3.)
+                .hover(|s| s.background(palette.hover))
+                .class(SvgClass, |s| s.size(24, 24))
+        })
+        .class(TabButtonClass, |s| {
+            s.width(90)
+                .height(30)
+                .background(palette.handle)
+                .color(palette.surface)
+                .border_radius(theme.radii.medium)
+                .hover(|s| s.background(palette.handle))
+        })
+}
 
-    icon.to_string()
+/// The icon `name` of a button, or an empty svg if no valid icon is registered as `name`.
+fn button_icon(name: &str) -> Svg {
+    icon(name).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        svg(String::new())
+    })
 }
 
+/// A secondary, medium button with an icon, which is shown as selected while `active` is true.
+///
+/// Shows no icon if no valid icon is registered as `icon_name`.
 pub fn small_button(
     text: &'static str,
     icon_name: &'static str,
@@ -103,7 +255,7 @@ pub fn small_button(
 ) -> impl IntoView {
     button(
         h_stack((
-            svg(create_icon(icon_name)).style(|s| s.width(24).height(24).color(Color::BLACK)),
+            button_icon(icon_name).style(|s| s.size(24, 24)),
             if text.len() > 0 {
                 label(move || text).style(|s| s.margin_left(4.0))
             } else {
@@ -113,23 +265,11 @@ pub fn small_button(
         .style(|s| s.justify_center().align_items(AlignItems::Center)),
     )
     .on_click_stop(action)
-    .style(move |s| {
-        s.height(28)
-            .justify_center()
-            .align_items(AlignItems::Center)
-            .background(if active.get() {
-                Color::LIGHT_GRAY
-            } else {
-                Color::WHITE
-            })
-            .border(0)
-            // .border_color(Color::BLACK)
-            .border_radius(15)
-            .transition(Background, Transition::ease_in_out(200.millis()))
-            .focus_visible(|s| s.border(2.).border_color(Color::BLUE))
-            .hover(|s| s.background(Color::LIGHT_GRAY).cursor(CursorStyle::Pointer))
-            .z_index(20)
-    })
+    .class(CommonButtonClass)
+    .class(SecondaryButtonClass)
+    .class(MediumButtonClass)
+    .class_if(move || active.get(), SelectedButtonClass)
+    .style(|s| s.z_index(20))
 }
 
 pub fn simple_button(text: String, action: impl FnMut(&Event) + 'static) -> impl IntoView {
@@ -142,18 +282,10 @@ pub fn simple_button(text: String, action: impl FnMut(&Event) + 'static) -> impl
         .style(|s| s.justify_center().align_items(AlignItems::Center)),
     )
     .on_click_stop(action)
-    .style(move |s| {
-        s.height(28)
-            .justify_center()
-            .align_items(AlignItems::Center)
-            .border(0)
-            // .border_color(Color::BLACK)
-            .border_radius(15)
-            .transition(Background, Transition::ease_in_out(200.millis()))
-            .focus_visible(|s| s.border(2.).border_color(Color::BLUE))
-            .hover(|s| s.background(Color::LIGHT_GRAY).cursor(CursorStyle::Pointer))
-            .z_index(20)
-    })
+    .class(CommonButtonClass)
+    .class(GhostButtonClass)
+    .class(MediumButtonClass)
+    .style(|s| s.z_index(20))
 }
 
 pub fn icon_button(
@@ -163,29 +295,22 @@ pub fn icon_button(
 ) -> impl IntoView {
     tooltip(
         button(
-            h_stack((
-                svg(create_icon(icon_name)).style(|s| s.width(20).height(20).color(Color::BLACK)),
-            ))
-            .style(|s| s.justify_center().align_items(AlignItems::Center)),
+            h_stack((button_icon(icon_name),))
+                .style(|s| s.justify_center().align_items(AlignItems::Center)),
         )
         .on_click_stop(action)
-        .style(move |s| {
-            s.height(28)
-                .width(28.0)
-                .justify_center()
-                .align_items(AlignItems::Center)
-                .border(0)
-                // .border_color(Color::BLACK)
-                .border_radius(15)
-                .transition(Background, Transition::ease_in_out(200.millis()))
-                .focus_visible(|s| s.border(2.).border_color(Color::BLUE))
-                .hover(|s| s.background(Color::LIGHT_GRAY).cursor(CursorStyle::Pointer))
-                .z_index(20)
-        }),
+        .class(CommonButtonClass)
+        .class(GhostButtonClass)
+        .class(MediumButtonClass)
+        .class(IconButtonClass)
+        .style(|s| s.z_index(20)),
         move || static_label(&tooltip_text),
     )
 }
 
+/// A button for one of several choices, which is shown as selected while `active` is `this_toggle`.
+///
+/// Shows no icon if no valid icon is registered as `icon_name`.
 pub fn toggle_button(
     text: &'static str,
     icon_name: &'static str,
@@ -195,7 +320,7 @@ pub fn toggle_button(
 ) -> impl IntoView {
     button(
         h_stack((
-            svg(create_icon(icon_name)).style(|s| s.width(24).height(24).color(Color::BLACK)),
+            button_icon(icon_name).style(|s| s.size(24, 24)),
             if text.len() > 0 {
                 label(move || text).style(|s| s.margin_left(4.0))
             } else {
@@ -205,22 +330,11 @@ pub fn toggle_button(
         .style(|s| s.justify_center().align_items(AlignItems::Center)),
     )
     .on_click_stop(action)
-    .style(move |s| {
-        s.height(28)
-            .justify_center()
-            .align_items(AlignItems::Center)
-            .background(Color::WHITE)
-            .border(1)
-            .border_color(Color::DARK_GRAY)
-            .border_radius(15)
-            .padding(4.0)
-            .transition(Background, Transition::ease_in_out(200.millis()))
-            .focus_visible(|s| s.border(2.).border_color(Color::BLUE))
-            .hover(|s| s.background(Color::LIGHT_GRAY).cursor(CursorStyle::Pointer))
-            .apply_if(this_toggle == active.get(), |s| {
-                s.background(Color::GRAY).color(Color::WHITE_SMOKE)
-            })
-    })
+    .class(CommonButtonClass)
+    .class(SecondaryButtonClass)
+    .class(MediumButtonClass)
+    .class(ChoiceButtonClass)
+    .class_if(move || this_toggle == active.get(), SelectedButtonClass)
 }
 
 pub fn success_button(
@@ -256,7 +370,7 @@ pub fn success_button(
 
     button(
         v_stack((
-            svg(create_icon(icon_name)).style(|s| s.width(24).height(24).color(Color::BLACK)),
+            button_icon(icon_name).style(|s| s.width(24).height(24).color(Color::BLACK)),
             label(move || text).style(|s| s.margin_top(4.0)),
         ))
         .style(|s| s.justify_center().align_items(AlignItems::Center)),
@@ -295,26 +409,14 @@ pub fn nav_button(
 ) -> impl IntoView {
     button(
         v_stack((
-            svg(create_icon(icon_name)).style(|s| s.width(30).height(30)),
+            button_icon(icon_name),
             label(move || text).style(|s| s.margin_top(4.0)),
         ))
         .style(|s| s.justify_center().align_items(AlignItems::Center)),
     )
     .action(action)
-    .style(move |s| {
-        s.width(70)
-            .height(70)
-            .justify_center()
-            .align_items(AlignItems::Center)
-            .border(0)
-            .border_radius(15)
-            .box_shadow_blur(15)
-            .box_shadow_spread(4)
-            .box_shadow_color(Color::rgba(0.0, 0.0, 0.0, 0.36))
-            .transition(Background, Transition::ease_in_out(200.millis()))
-            .focus_visible(|s| s.border(2.).border_color(Color::BLUE))
-            .hover(|s| s.background(Color::LIGHT_GRAY).cursor(CursorStyle::Pointer))
-    })
+    .class(CommonButtonClass)
+    .class(NavButtonClass)
 }
 
 pub fn option_button(
@@ -325,24 +427,14 @@ pub fn option_button(
 ) -> impl IntoView {
     button(
         v_stack((
-            svg(create_icon(icon_name)).style(|s| s.width(24).height(24)),
-            label(move || text).style(|s| s.margin_top(4.0).font_size(9.0)),
+            button_icon(icon_name),
+            label(move || text).style(|s| s.margin_top(4.0)),
         ))
         .style(|s| s.justify_center().align_items(AlignItems::Center)),
     )
     .action(action)
-    .style(move |s| {
-        s.width(60)
-            .height(60)
-            .justify_center()
-            .align_items(AlignItems::Center)
-            .border(1.0)
-            .border_color(Color::GRAY)
-            .border_radius(15)
-            .transition(Background, Transition::ease_in_out(200.millis()))
-            .focus_visible(|s| s.border(2.).border_color(Color::BLUE))
-            .hover(|s| s.background(Color::LIGHT_GRAY).cursor(CursorStyle::Pointer))
-    })
+    .class(CommonButtonClass)
+    .class(OptionButtonClass)
 }
 
 // pub fn option_button_once(
@@ -353,7 +445,7 @@ pub fn option_button(
 // ) -> impl IntoView {
 //     button(
 //         v_stack((
-//             svg(create_icon(icon_name)).style(|s| s.width(24).height(24)),
+//             icon(icon_name).style(|s| s.width(24).height(24)),
 //             label(move || text).style(|s| s.margin_top(4.0).font_size(9.0)),
 //         ))
 //         .style(|s| s.justify_center().align_items(AlignItems::Center)),
@@ -382,7 +474,7 @@ pub fn option_button(
 
 // pub fn layer_button(layer_name: String, icon_name: &'static str) -> impl IntoView {
 //     h_stack((
-//         svg(create_icon(icon_name))
+//         icon(icon_name)
 //             .style(|s| s.width(24).height(24).color(Color::BLACK))
 //             .style(|s| s.margin_right(4.0)),
 //         label(move || layer_name.to_string()),
@@ -412,28 +504,82 @@ pub fn tab_button(
 ) -> impl IntoView {
     button(
         v_stack((
-            // svg(create_icon(icon_name)).style(|s| s.width(30).height(30)),
+            // icon(icon_name).style(|s| s.width(30).height(30)),
             label(move || text).style(|s| s.margin_top(4.0)),
         ))
-        .style(|s| {
-            s.color(Color::WHITE)
-                .justify_center()
-                .align_items(AlignItems::Center)
-        }),
+        .style(|s| s.justify_center().align_items(AlignItems::Center)),
     )
     .action(action)
-    .style(move |s| {
-        s.width(90)
-            .height(30)
-            .justify_center()
-            .align_items(AlignItems::Center)
-            .border(0)
-            .background(Color::DARK_GRAY)
-            .border_radius(5.0)
-            .apply_if(this_tab == active.get(), |s| s.background(Color::BLACK))
-            .transition(Background, Transition::ease_in_out(200.millis()))
-            .focus_visible(|s| s.border(2.).border_color(Color::BLUE))
-            .hover(|s| s.background(Color::DARK_GRAY).cursor(CursorStyle::Pointer))
-            .margin_right(4.0)
-    })
+    .class(CommonButtonClass)
+    .class(TabButtonClass)
+    .class_if(move || this_tab == active.get(), SelectedButtonClass)
+    .style(|s| s.margin_right(4.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+    use floem_winit::keyboard::{Key, NamedKey};
+
+    use crate::{headless::test_window, style::StyleClass, theme::Theme};
+
+    use super::{
+        small_button, ButtonSize, ButtonVariant, DangerButtonClass, LargeButtonClass,
+        MediumButtonClass, SecondaryButtonClass, SelectedButtonClass,
+    };
+
+    #[test]
+    fn buttons_are_clicked_with_the_pointer_and_enter() {
+        let clicks = create_rw_signal(0);
+        let mut window = test_window((100.0, 100.0), move || {
+            small_button(
+                "Add",
+                "plus",
+                move |_| clicks.update(|c| *c += 1),
+                create_rw_signal(false),
+            )
+        });
+
+        window.click((10.0, 10.0));
+        assert_eq!(clicks.get_untracked(), 1);
+
+        // The click focused the button, so Enter clicks it again.
+        window.key_press(Key::Named(NamedKey::Enter));
+        assert_eq!(clicks.get_untracked(), 2);
+    }
+
+    #[test]
+    fn button_variant_and_size_replace_their_classes() {
+        let active = create_rw_signal(true);
+        let mut window = test_window((100.0, 100.0), move || {
+            ButtonSize::Lg.apply(ButtonVariant::Danger.apply(small_button(
+                "",
+                "plus",
+                |_| {},
+                active,
+            )))
+        });
+        let classes = window.root_view().state().borrow().classes.clone();
+        assert!(classes.contains(&DangerButtonClass::class_ref()));
+        assert!(!classes.contains(&SecondaryButtonClass::class_ref()));
+        assert!(classes.contains(&LargeButtonClass::class_ref()));
+        assert!(!classes.contains(&MediumButtonClass::class_ref()));
+        // The selected style still takes precedence over the new variant.
+        assert_eq!(classes.last(), Some(&SelectedButtonClass::class_ref()));
+
+        active.set(false);
+        let danger = Theme::light().palette.danger;
+        assert_eq!(
+            window.render().to_rgba8().get_pixel(2, 18).0,
+            [danger.r, danger.g, danger.b, 255]
+        );
+    }
+
+    #[test]
+    fn buttons_with_unknown_icons_still_render() {
+        let mut window = test_window((100.0, 100.0), || {
+            small_button("Add", "missing", |_| {}, create_rw_signal(false))
+        });
+        window.render();
+    }
 }
@@ -1,7 +1,9 @@
 use floem_reactive::create_rw_signal;
 
 use crate::peniko::Color;
-use crate::style::{Style, TextOverflow};
+use crate::style::{Style, StyleClass, StyleClassRef, TextOverflow};
+use crate::style_class;
+use crate::theme::Theme;
 use crate::view::View;
 use crate::views::container;
 use crate::views::{h_stack, label};
@@ -15,13 +17,30 @@ use floem_reactive::SignalGet;
 use floem_reactive::SignalUpdate;
 use std::fs;
 
+style_class!(pub CardClass);
+style_class!(pub AlertClass);
+style_class!(pub SuccessAlertClass);
+style_class!(pub InfoAlertClass);
+style_class!(pub ErrorAlertClass);
+style_class!(pub WarningAlertClass);
+
+/// The style of [`CardClass`] in the light theme.
+///
+/// A plain style can't follow the window's theme, so dark windows would get light cards.
+#[deprecated(note = "use `.class(CardClass)`, which follows the window's theme")]
 pub fn card_styles(s: Style) -> Style {
-    s.padding(20)
-        .background(Color::rgba(240.0, 240.0, 240.0, 255.0))
-        .border_radius(15)
+    s.apply(card_style(&Theme::light()))
+}
+
+/// The style of [`CardClass`], generated from the tokens of `theme`.
+fn card_style(theme: &Theme) -> Style {
+    Style::new()
+        .padding(theme.spacing.large * 2.)
+        .background(theme.palette.surface)
+        .border_radius(theme.radii.large)
         .box_shadow_blur(15)
         .box_shadow_spread(4)
-        .box_shadow_color(Color::rgba(0.0, 0.0, 0.0, 0.36))
+        .box_shadow_color(theme.palette.shadow.with_alpha_factor(0.36))
 }
 
 #[derive(Clone, Copy)]
@@ -33,46 +52,80 @@ pub enum AlertVariant {
 }
 
 impl AlertVariant {
-    fn get_colors(&self) -> (Color, Color) {
+    fn class_ref(&self) -> StyleClassRef {
         match self {
-            AlertVariant::Success => (
-                Color::rgb8(240, 253, 244), // bg-green-50
-                Color::rgb8(22, 163, 74),   // text-green-600
-            ),
-            AlertVariant::Info => (
-                Color::rgb8(167, 209, 242), // bg-blue-50
-                Color::rgb8(37, 99, 235),   // text-blue-600
-            ),
-            AlertVariant::Error => (
-                Color::rgb8(254, 242, 242), // bg-red-50
-                Color::rgb8(220, 38, 38),   // text-red-600
-            ),
-            AlertVariant::Warning => (
-                Color::rgb8(254, 252, 232), // bg-yellow-50
-                Color::rgb8(202, 138, 4),   // text-yellow-600
-            ),
+            AlertVariant::Success => SuccessAlertClass::class_ref(),
+            AlertVariant::Info => InfoAlertClass::class_ref(),
+            AlertVariant::Error => ErrorAlertClass::class_ref(),
+            AlertVariant::Warning => WarningAlertClass::class_ref(),
         }
     }
 }
 
-pub fn alert(variant: AlertVariant, message: String) -> impl View {
-    let (bg_color, text_color) = variant.get_colors();
+/// The styles of the cards and alerts in this module, generated from the tokens of `theme`.
+pub(crate) fn general_styles(theme: &Theme) -> Style {
+    let palette = &theme.palette;
+    let alert = |background: Color, color: Color| {
+        Style::new()
+            .background(background)
+            .border_color(color)
+            .color(color)
+    };
 
-    container(
-        (label(move || message.clone()).style(move |s| {
-            s.color(text_color)
-                .font_size(11.0)
-                .line_height(1.5)
+    Style::new()
+        .class(CardClass, |_| card_style(theme))
+        .class(AlertClass, |s| {
+            s.padding(6.0)
+                .border_radius(6.0)
+                .border(1.0)
                 .width(260.0)
-                .text_overflow(TextOverflow::Wrap)
-        })),
+                .font_size(theme.typography.font_size - 1.)
+                .line_height(1.5)
+        })
+        .class(SuccessAlertClass, |_| {
+            alert(palette.success_surface, palette.success)
+        })
+        .class(InfoAlertClass, |_| {
+            alert(palette.info_surface, palette.info)
+        })
+        .class(ErrorAlertClass, |_| {
+            alert(palette.danger_surface, palette.danger)
+        })
+        .class(WarningAlertClass, |_| {
+            alert(palette.warning_surface, palette.warning)
+        })
+}
+
+pub fn alert(variant: AlertVariant, message: String) -> impl View {
+    let alert = container(
+        label(move || message.clone()).style(|s| s.width_full().text_overflow(TextOverflow::Wrap)),
     )
-    .style(move |s| {
-        s.padding(6.0)
-            .border_radius(6.0)
-            .background(bg_color)
-            .border(1.0)
-            .border_color(text_color)
-            .width(260.0)
-    })
+    .class(AlertClass);
+    alert.id().add_class(variant.class_ref());
+    alert
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alert, AlertVariant};
+    use crate::{
+        headless::{HeadlessConfig, HeadlessWindow},
+        theme::{Palette, Theme},
+    };
+
+    #[test]
+    fn alert_colors_come_from_the_theme() {
+        let danger_surface = Palette::dark().danger_surface;
+        let mut window = HeadlessWindow::new(
+            || alert(AlertVariant::Error, "Failed".to_string()),
+            HeadlessConfig::default()
+                .size((300.0, 60.0))
+                .theme(Theme::dark()),
+        );
+        let image = window.render().to_rgba8();
+        assert_eq!(
+            image.get_pixel(250, 3).0,
+            [danger_surface.r, danger_surface.g, danger_surface.b, 255]
+        );
+    }
 }
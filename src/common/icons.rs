@@ -0,0 +1,323 @@
+//! A registry of named SVG icons.
+//!
+//! The registry starts out with the built-in Phosphor icons and can be extended at runtime
+//! with SVG strings, files or whole directories of icons. Each icon is parsed once, on first
+//! use, and the parsed document is shared by every view that shows it.
+//!
+//! ```rust,no_run
+//! use floem::common::{icon, with_icon_registry};
+//!
+//! with_icon_registry(|icons| {
+//!     icons.register("logo", r#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16"/>"#);
+//!     icons.register_dir("assets/icons")
+//! })
+//! .unwrap();
+//!
+//! let logo = icon("logo").unwrap();
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use floem_renderer::usvg;
+
+use crate::views::{parsed_svg, ParsedSvg, Svg};
+
+/// The built-in icons, by name.
+const BUILTIN_ICONS: &[(&str, &str)] = &[
+    ("plus", include_str!("../assets/plus-thin.svg")),
+    ("minus", include_str!("../assets/minus-thin.svg")),
+    ("windmill", include_str!("../assets/windmill-thin.svg")),
+    ("gear", include_str!("../assets/gear-six-thin.svg")),
+    ("brush", include_str!("../assets/paint-brush-thin.svg")),
+    ("shapes", include_str!("../assets/shapes-thin.svg")),
+    ("arrow-left", include_str!("../assets/arrow-left-thin.svg")),
+    ("polygon", include_str!("../assets/polygon-thin.svg")),
+    ("octagon", include_str!("../assets/octagon-thin.svg")),
+    ("square", include_str!("../assets/square-thin.svg")),
+    ("triangle", include_str!("../assets/triangle-thin.svg")),
+    ("dot", include_str!("../assets/dot-outline-thin.svg")),
+    (
+        "dots-vertical",
+        include_str!("../assets/dots-three-outline-vertical-thin.svg"),
+    ),
+    ("sphere", include_str!("../assets/sphere-thin.svg")),
+    ("gizmo", include_str!("../assets/vector-three-thin.svg")),
+    ("book", include_str!("../assets/book-open-thin.svg")),
+    ("cube", include_str!("../assets/cube-focus-thin.svg")),
+    ("faders", include_str!("../assets/faders-thin.svg")),
+    ("map", include_str!("../assets/map-trifold-thin.svg")),
+    ("panorama", include_str!("../assets/panorama-thin.svg")),
+    (
+        "speedometer",
+        include_str!("../assets/speedometer-thin.svg"),
+    ),
+    (
+        "motion-arrow",
+        include_str!("../assets/arrow-fat-lines-right-thin.svg"),
+    ),
+    ("atom", include_str!("../assets/atom-thin.svg")),
+    ("brain", include_str!("../assets/brain-thin.svg")),
+    ("broadcast", include_str!("../assets/broadcast-thin.svg")),
+    ("circles", include_str!("../assets/circles-three-thin.svg")),
+    (
+        "fast-forward",
+        include_str!("../assets/fast-forward-thin.svg"),
+    ),
+    (
+        "folder-plus",
+        include_str!("../assets/folder-plus-thin.svg"),
+    ),
+    ("bone", include_str!("../assets/bone-thin.svg")),
+    ("caret-down", include_str!("../assets/caret-down-thin.svg")),
+    (
+        "caret-right",
+        include_str!("../assets/caret-right-thin.svg"),
+    ),
+    (
+        "translate",
+        include_str!("../assets/arrows-out-cardinal-thin.svg"),
+    ),
+    (
+        "rotate",
+        include_str!("../assets/arrows-clockwise-thin.svg"),
+    ),
+    ("scale", include_str!("../assets/resize-thin.svg")),
+    ("image", include_str!("../assets/image-thin.svg")),
+    ("text", include_str!("../assets/text-t-thin.svg")),
+    ("video", include_str!("../assets/video-thin.svg")),
+    ("copy", include_str!("../assets/copy-thin.svg")),
+    ("trash", include_str!("../assets/trash-thin.svg")),
+    ("x", include_str!("../assets/x-thin.svg")),
+    ("git-fork", include_str!("../assets/git-fork-thin.svg")),
+    ("git-merge", include_str!("../assets/git-merge-thin.svg")),
+];
+
+/// Possible errors when registering or looking up an icon.
+#[derive(Debug)]
+pub enum IconError {
+    /// No icon is registered under this name.
+    NotFound(String),
+    /// The icon's SVG document could not be parsed.
+    Parse { name: String, error: usvg::Error },
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+}
+
+impl fmt::Display for IconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IconError::NotFound(name) => write!(f, "No icon is registered as \"{name}\""),
+            IconError::Parse { name, error } => {
+                write!(f, "Icon \"{name}\" is not a valid SVG: {error}")
+            }
+            IconError::Io { path, error } => {
+                write!(f, "Could not read icons from {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for IconError {}
+
+enum IconSource {
+    Builtin(&'static str),
+    Registered(String),
+}
+
+impl IconSource {
+    fn as_str(&self) -> &str {
+        match self {
+            IconSource::Builtin(svg) => svg,
+            IconSource::Registered(svg) => svg.as_str(),
+        }
+    }
+}
+
+/// Named SVG icons, parsed on first use.
+///
+/// Each UI thread has one registry, which is accessed with [`with_icon_registry`].
+pub struct IconRegistry {
+    sources: HashMap<String, IconSource>,
+    parsed: HashMap<String, ParsedSvg>,
+}
+
+impl Default for IconRegistry {
+    /// A registry with the built-in icons.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for &(name, svg) in BUILTIN_ICONS {
+            registry
+                .sources
+                .insert(name.to_string(), IconSource::Builtin(svg));
+        }
+        registry
+    }
+}
+
+impl IconRegistry {
+    /// A registry without any icons.
+    pub fn empty() -> Self {
+        Self {
+            sources: HashMap::new(),
+            parsed: HashMap::new(),
+        }
+    }
+
+    /// Registers `svg` as the icon `name`, replacing any icon registered under that name.
+    ///
+    /// The document is parsed the first time the icon is used.
+    pub fn register(&mut self, name: impl Into<String>, svg: impl Into<String>) {
+        let name = name.into();
+        self.parsed.remove(&name);
+        self.sources
+            .insert(name, IconSource::Registered(svg.into()));
+    }
+
+    /// Registers the SVG file at `path` as the icon `name`.
+    pub fn register_file(
+        &mut self,
+        name: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<(), IconError> {
+        let path = path.as_ref();
+        let svg = std::fs::read_to_string(path).map_err(|error| IconError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        self.register(name, svg);
+        Ok(())
+    }
+
+    /// Registers every `.svg` file in `dir` under its file name without the extension,
+    /// and returns the number of icons registered. Subdirectories are not searched.
+    pub fn register_dir(&mut self, dir: impl AsRef<Path>) -> Result<usize, IconError> {
+        let dir = dir.as_ref();
+        let io_error = |error| IconError::Io {
+            path: dir.to_path_buf(),
+            error,
+        };
+        let mut count = 0;
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if !path.is_file() || path.extension().map_or(true, |ext| ext != "svg") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            self.register_file(name.to_string(), &path)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Removes the icon `name`, returning whether it was registered.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.parsed.remove(name);
+        self.sources.remove(name).is_some()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.sources.contains_key(name)
+    }
+
+    /// The names of all registered icons, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sources.keys().map(String::as_str)
+    }
+
+    /// Returns the SVG source of the icon `name`.
+    pub fn source(&self, name: &str) -> Result<&str, IconError> {
+        self.sources
+            .get(name)
+            .map(IconSource::as_str)
+            .ok_or_else(|| IconError::NotFound(name.to_string()))
+    }
+
+    /// Returns the parsed icon `name`, parsing it if this is its first use.
+    pub fn get(&mut self, name: &str) -> Result<ParsedSvg, IconError> {
+        if let Some(svg) = self.parsed.get(name) {
+            return Ok(svg.clone());
+        }
+        let svg = ParsedSvg::parse(self.source(name)?).map_err(|error| IconError::Parse {
+            name: name.to_string(),
+            error,
+        })?;
+        self.parsed.insert(name.to_string(), svg.clone());
+        Ok(svg)
+    }
+}
+
+thread_local! {
+    static ICON_REGISTRY: RefCell<IconRegistry> = RefCell::new(IconRegistry::default());
+}
+
+/// Runs `f` with the icon registry of the current thread.
+pub fn with_icon_registry<R>(f: impl FnOnce(&mut IconRegistry) -> R) -> R {
+    ICON_REGISTRY.with_borrow_mut(f)
+}
+
+/// Returns the registered icon `name`.
+pub fn try_icon(name: &str) -> Result<ParsedSvg, IconError> {
+    with_icon_registry(|icons| icons.get(name))
+}
+
+/// An [`svg`](crate::views::svg) view showing the registered icon `name`, or the error if the icon is missing or
+/// invalid.
+pub fn icon(name: &str) -> Result<Svg, IconError> {
+    try_icon(name).map(parsed_svg)
+}
+
+/// Returns the SVG source of the registered icon `name`, or [`IconError::NotFound`] if there
+/// is none.
+///
+/// Prefer [`icon`], which shares one parsed document between all views showing the icon.
+pub fn create_icon(name: &str) -> Result<String, IconError> {
+    with_icon_registry(|icons| icons.source(name).map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use peniko::kurbo::Size;
+
+    use super::{create_icon, icon, IconError, IconRegistry};
+
+    #[test]
+    fn registered_icons_are_parsed_once() {
+        let mut icons = IconRegistry::default();
+        assert!(icons.contains("git-fork"));
+        assert!(matches!(
+            icons.get("missing"),
+            Err(IconError::NotFound(name)) if name == "missing"
+        ));
+
+        icons.register(
+            "square",
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="8" height="4"/>"#,
+        );
+        let first = icons.get("square").unwrap();
+        let second = icons.get("square").unwrap();
+        assert!(std::ptr::eq(first.tree(), second.tree()));
+        assert_eq!(first.size(), Size::new(8.0, 4.0));
+
+        icons.register("broken", "not svg");
+        assert!(matches!(icons.get("broken"), Err(IconError::Parse { .. })));
+    }
+
+    #[test]
+    fn missing_icons_are_errors() {
+        assert!(create_icon("git-fork").is_ok_and(|source| source.contains("<svg")));
+        assert!(matches!(
+            create_icon("missing"),
+            Err(IconError::NotFound(name)) if name == "missing"
+        ));
+        assert!(matches!(icon("missing"), Err(IconError::NotFound(_))));
+    }
+}
@@ -1,8 +1,18 @@
 mod buttons;
 pub use buttons::*;
 
+mod icons;
+pub use icons::*;
+
 mod inputs;
 pub use inputs::*;
 
 pub mod general;
 pub use general::*;
+
+use crate::{style::Style, theme::Theme};
+
+/// The styles of the widgets in this module, generated from the tokens of `theme`.
+pub(crate) fn theme_style(theme: &Theme) -> Style {
+    buttons::button_styles(theme).apply(general::general_styles(theme))
+}
@@ -1,8 +1,10 @@
 use floem_reactive::Scope;
+use floem_renderer::config::RendererConfig;
 use floem_renderer::gpu_resources::{self, GpuResourceError, GpuResources};
 use floem_renderer::Renderer as FloemRenderer;
 use floem_winit::dpi::PhysicalSize;
 use peniko::kurbo::{Affine, Point, Rect, RoundedRect, Shape, Size, Vec2};
+use peniko::BlendMode;
 use std::{
     ops::{Deref, DerefMut},
     rc::Rc,
@@ -17,18 +19,19 @@ use web_time::{Duration, Instant};
 use taffy::prelude::NodeId;
 
 use crate::animate::{AnimStateKind, RepeatMode};
-use crate::renderer::Renderer;
+use crate::renderer::{Renderer, RendererError};
 use crate::style::DisplayProp;
 use crate::view_state::IsHiddenState;
 use crate::window_handle::WindowSize;
 use crate::{
     action::{exec_after, show_context_menu},
     app_state::AppState,
+    drag::{DragPayload, DropEvent},
     event::{Event, EventListener, EventPropagation},
     id::ViewId,
     inspector::CaptureState,
     menu::Menu,
-    style::{Style, StyleProp, ZIndex},
+    style::{Style, StyleProp, StyleSelector, ZIndex},
     view::{paint_bg, paint_border, paint_outline, View},
     view_state::ChangeFlags,
 };
@@ -52,6 +55,7 @@ pub struct DragState {
     pub(crate) id: ViewId,
     pub(crate) offset: Vec2,
     pub(crate) released_at: Option<Instant>,
+    pub(crate) payload: Option<DragPayload>,
 }
 
 pub(crate) enum FrameUpdate {
@@ -108,6 +112,21 @@ impl<'a> EventCx<'a> {
         event: Event,
         directed: bool,
     ) -> EventPropagation {
+        let touch_start = match &event {
+            Event::TouchStart(touch) => Some(touch.id),
+            _ => None,
+        };
+        let propagation = self.view_event(view_id, event, directed);
+        if let Some(touch) = touch_start {
+            if propagation.is_processed() {
+                // The deepest view that handles the start of a touch receives the rest of it.
+                self.app_state.touch_targets.entry(touch).or_insert(view_id);
+            }
+        }
+        propagation
+    }
+
+    fn view_event(&mut self, view_id: ViewId, event: Event, directed: bool) -> EventPropagation {
         if view_id.style_has_hidden() {
             // we don't process events for hidden view
             return EventPropagation::Continue;
@@ -309,10 +328,12 @@ impl<'a> EventCx<'a> {
                             // start dragging when moved 1 px
                             self.app_state.active = None;
                             self.update_active(view_id);
+                            let payload = view_state.borrow().drag_payload.clone();
                             self.app_state.dragging = Some(DragState {
                                 id: view_id,
                                 offset: vec2,
                                 released_at: None,
+                                payload: payload.map(|payload| payload()),
                             });
                             self.app_state.request_paint(view_id);
                             view_id.apply_event(&EventListener::DragStart, &event);
@@ -336,14 +357,42 @@ impl<'a> EventCx<'a> {
                         if on_view {
                             if let Some(dragging) = self.app_state.dragging.as_mut() {
                                 let dragging_id = dragging.id;
-                                if view_id
-                                    .apply_event(&EventListener::Drop, &event)
-                                    .is_some_and(|prop| prop.is_processed())
+                                let payload = dragging.payload.clone();
+                                // A drop target accepting the payload takes it before the
+                                // `Drop` listeners.
+                                let on_drop = view_state
+                                    .borrow()
+                                    .drop_target
+                                    .as_ref()
+                                    .map(|target| target.on_drop.clone());
+                                let dropped = match (on_drop, payload) {
+                                    (Some(on_drop), Some(payload))
+                                        if self.app_state.accepts_drop(view_id) =>
+                                    {
+                                        on_drop(&DropEvent {
+                                            pos: pointer_event.pos,
+                                            payload,
+                                            source: dragging_id,
+                                        });
+                                        true
+                                    }
+                                    _ => false,
+                                };
+                                if dropped
+                                    || view_id
+                                        .apply_event(&EventListener::Drop, &event)
+                                        .is_some_and(|prop| prop.is_processed())
                                 {
                                     // if the drop is processed, we set dragging to none so that the animation
                                     // for the dragged view back to its original position isn't played.
                                     self.app_state.dragging = None;
                                     self.app_state.request_paint(view_id);
+                                    if self
+                                        .app_state
+                                        .has_style_for_sel(view_id, StyleSelector::DragOver)
+                                    {
+                                        view_id.request_style();
+                                    }
                                     dragging_id.apply_event(&EventListener::DragEnd, &event);
                                 }
                             }
@@ -425,6 +474,12 @@ impl<'a> EventCx<'a> {
                     }
                 }
             }
+            Event::FileDragOver(drag) => {
+                let rect = view_id.get_size().unwrap_or_default().to_rect();
+                if rect.contains(drag.pos) {
+                    self.app_state.file_drag_over.insert(view_id);
+                }
+            }
             Event::KeyDown(_) => {
                 if self.app_state.is_focused(&view_id) && event.is_keyboard_trigger() {
                     view_id.apply_event(&EventListener::Click, &event);
@@ -441,7 +496,10 @@ impl<'a> EventCx<'a> {
         if let Some(listener) = event.listener() {
             let event_listeners = view_state.borrow().event_listeners.clone();
             if let Some(handlers) = event_listeners.get(&listener).cloned() {
-                let should_run = if let Some(pos) = event.point() {
+                // The view receiving a finger gets all of its events, even outside of it.
+                let should_run = if directed && event.touch_id().is_some() {
+                    true
+                } else if let Some(pos) = event.point() {
                     let rect = view_id.get_size().unwrap_or_default().to_rect();
                     rect.contains(pos)
                 } else {
@@ -466,10 +524,14 @@ impl<'a> EventCx<'a> {
         let viewport = state.borrow().viewport;
 
         if let Some(layout) = id.get_layout() {
-            event.offset((
+            let event = event.offset((
                 layout.location.x as f64 - viewport.map(|rect| rect.x0).unwrap_or(0.0),
                 layout.location.y as f64 - viewport.map(|rect| rect.y0).unwrap_or(0.0),
-            ))
+            ));
+            match inverse_style_transform(id, &layout) {
+                Some(transform) => event.transform(transform),
+                None => event,
+            }
         } else {
             event
         }
@@ -484,13 +546,12 @@ impl<'a> EventCx<'a> {
         if let Some(point) = event.point() {
             let layout_rect = id.layout_rect();
             if let Some(layout) = id.get_layout() {
-                if layout_rect
-                    .with_origin(Point::new(
-                        layout.location.x as f64,
-                        layout.location.y as f64,
-                    ))
-                    .contains(point)
-                {
+                let point = point - Vec2::new(layout.location.x as f64, layout.location.y as f64);
+                let point = match inverse_style_transform(id, &layout) {
+                    Some(transform) => transform * point,
+                    None => point,
+                };
+                if layout_rect.with_origin(Point::ZERO).contains(point) {
                     return true;
                 }
             }
@@ -501,6 +562,15 @@ impl<'a> EventCx<'a> {
     }
 }
 
+/// The inverse of the translate, scale, rotate and skew props of a view, mapping points
+/// relative to its layout location into its own coordinates.
+/// Returns `None` for the identity and for transforms that can't be inverted.
+fn inverse_style_transform(id: ViewId, layout: &taffy::Layout) -> Option<Affine> {
+    let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+    let transform = id.state().borrow().layout_props.transform(size);
+    (transform != Affine::IDENTITY && transform.determinant() != 0.0).then(|| transform.inverse())
+}
+
 #[derive(Default)]
 pub struct InteractionState {
     pub(crate) is_hovered: bool,
@@ -508,6 +578,7 @@ pub struct InteractionState {
     pub(crate) is_disabled: bool,
     pub(crate) is_focused: bool,
     pub(crate) is_clicking: bool,
+    pub(crate) is_drag_over: bool,
     pub(crate) using_keyboard_navigation: bool,
 }
 
@@ -552,6 +623,7 @@ impl<'a> StyleCx<'a> {
             is_disabled: self.app_state.is_disabled(id),
             is_focused: self.app_state.is_focused(id),
             is_clicking: self.app_state.is_clicking(id),
+            is_drag_over: self.app_state.is_drag_over(id),
             using_keyboard_navigation: self.app_state.keyboard_navigation,
         }
     }
@@ -967,6 +1039,13 @@ impl<'a> PaintCx<'a> {
         false
     }
 
+    /// Asks for the window to render another frame after this one, at the pace of the window's
+    /// [`FramePacing`](crate::window::FramePacing). Views animating on every frame call this
+    /// each time they are painted.
+    pub fn request_next_frame(&mut self) {
+        self.app_state.next_frame_requested = true;
+    }
+
     /// paint the children of this view
     pub fn paint_children(&mut self, id: ViewId) {
         let children = id.children();
@@ -1003,11 +1082,33 @@ impl<'a> PaintCx<'a> {
                 self.set_z_index(z_index);
             }
 
+            // The opacity applies to the whole subtree, which is composited as one layer.
+            let opacity = view_style_props.opacity().clamp(0.0, 1.0);
+            if opacity < 1.0 {
+                // Children, outlines and shadows may paint outside the view, so the layer
+                // is only limited by the current clip or the window.
+                let bounds = match self.clip {
+                    Some(clip) => clip.rect(),
+                    None if self.transform.determinant() != 0.0 => self
+                        .transform
+                        .inverse()
+                        .transform_rect_bbox(self.app_state.root_size.to_rect()),
+                    None => Rect::ZERO,
+                };
+                self.paint_state
+                    .renderer_mut()
+                    .push_layer(opacity, BlendMode::default(), &bounds);
+            }
+
             paint_bg(self, &style, &view_style_props, size);
 
             view.borrow_mut().paint(self);
             paint_border(self, &layout_props, &view_style_props, size);
-            paint_outline(self, &view_style_props, size)
+            paint_outline(self, &view_style_props, size);
+
+            if opacity < 1.0 {
+                self.paint_state.renderer_mut().pop_layer();
+            }
         }
 
         let mut drag_set_to_none = false;
@@ -1133,53 +1234,26 @@ impl<'a> PaintCx<'a> {
                 y: offset.y as f64,
             });
 
-            let view_state = id.state();
-            let transform_x = match view_state.borrow().layout_props.translate_x() {
-                crate::unit::PxPct::Px(px) => px,
-                crate::unit::PxPct::Pct(pct) => pct / 100.,
-            };
-            let transform_y = match view_state.borrow().layout_props.translate_y() {
-                crate::unit::PxPct::Px(px) => px,
-                crate::unit::PxPct::Pct(pct) => pct / 100.,
-            };
-            self.transform *= Affine::translate(Vec2 {
-                x: transform_x,
-                y: transform_y,
-            });
-
-            let scale_x = match view_state.borrow().layout_props.scale_x() {
-                crate::unit::PxPct::Px(px) => px / layout.size.width as f64,
-                crate::unit::PxPct::Pct(pct) => pct / 100.,
-            };
-            let scale_y = match view_state.borrow().layout_props.scale_y() {
-                crate::unit::PxPct::Px(px) => px / layout.size.height as f64,
-                crate::unit::PxPct::Pct(pct) => pct / 100.,
-            };
-            let center_x = layout.size.width as f64 / 2.0;
-            let center_y = layout.size.height as f64 / 2.0;
-            self.transform *= Affine::translate(Vec2 {
-                x: center_x,
-                y: center_y,
-            });
-            self.transform *= Affine::scale_non_uniform(scale_x, scale_y);
-            self.transform *= Affine::translate(Vec2 {
-                x: -center_x,
-                y: -center_y,
-            });
-
-            // let rotation = view_state.borrow().layout_props.rotation().0;
-            // self.transform *= Affine::rotate(rotation);
+            let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+            let style_transform = id.state().borrow().layout_props.transform(size);
+            self.transform *= style_transform;
             self.paint_state.renderer_mut().transform(self.transform);
 
             if let Some(rect) = self.clip.as_mut() {
                 let raidus = rect.radii();
-                *rect = rect
+                let clip = rect
                     .rect()
-                    .with_origin(rect.origin() - Vec2::new(offset.x as f64, offset.y as f64))
-                    .to_rounded_rect(raidus);
+                    .with_origin(rect.origin() - Vec2::new(offset.x as f64, offset.y as f64));
+                // A rotated or skewed clip is approximated by its bounding box.
+                let clip = if style_transform.determinant() != 0.0 {
+                    style_transform.inverse().transform_rect_bbox(clip)
+                } else {
+                    clip
+                };
+                *rect = clip.to_rounded_rect(raidus);
             }
 
-            Size::new(layout.size.width as f64, layout.size.height as f64)
+            size
         } else {
             Size::ZERO
         }
@@ -1200,8 +1274,12 @@ pub enum PaintState {
     /// The renderer is not yet initialized. This state is used to wait for the GPU resources to be acquired.
     PendingGpuResources {
         window: Arc<dyn wgpu::WindowHandle>,
-        rx: crossbeam::channel::Receiver<Result<GpuResources, GpuResourceError>>,
+        /// The result of the GPU resource request, once it has been made.
+        rx: Option<crossbeam::channel::Receiver<Result<GpuResources, GpuResourceError>>>,
+        /// The result, once it was received from `rx`.
+        received: Option<Result<Arc<GpuResources>, GpuResourceError>>,
         font_embolden: f32,
+        renderer_config: RendererConfig,
         /// This field holds an instance of `Renderer::Uninitialized` until the GPU resources are acquired,
         /// which will be returned in `PaintState::renderer` and `PaintState::renderer_mut`.
         /// All calls to renderer methods will be no-ops until the renderer is initialized.
@@ -1217,49 +1295,101 @@ pub enum PaintState {
 }
 
 impl PaintState {
+    /// Creates a paint state waiting for GPU resources, which are requested with
+    /// [`set_gpu_request`](Self::set_gpu_request).
     pub fn new(
         window: Arc<dyn wgpu::WindowHandle>,
-        rx: crossbeam::channel::Receiver<Result<GpuResources, GpuResourceError>>,
         scale: f64,
         size: Size,
         font_embolden: f32,
+        renderer_config: RendererConfig,
     ) -> Self {
         Self::PendingGpuResources {
             window,
-            rx,
+            rx: None,
+            received: None,
             font_embolden,
+            renderer_config,
             renderer: Renderer::Uninitialized { scale, size },
         }
     }
 
+    /// Creates a paint state with a headless renderer which needs no window or GPU.
+    pub fn new_headless(scale: f64, size: Size, font_embolden: f32) -> Self {
+        Self::Initialized {
+            renderer: Renderer::new_headless(scale, size, font_embolden),
+        }
+    }
+
+    /// Sets the channel the requested GPU resources are received from.
+    pub(crate) fn set_gpu_request(
+        &mut self,
+        request: crossbeam::channel::Receiver<Result<GpuResources, GpuResourceError>>,
+    ) {
+        if let PaintState::PendingGpuResources { rx, .. } = self {
+            *rx = Some(request);
+        }
+    }
+
+    /// Returns the requested GPU resources once the request has completed, or `Some(None)` if
+    /// it failed.
+    pub(crate) fn received_gpu_resources(&mut self) -> Option<Option<&Arc<GpuResources>>> {
+        match self {
+            PaintState::PendingGpuResources { rx, received, .. } => {
+                if received.is_none() {
+                    *received = rx
+                        .as_ref()?
+                        .try_recv()
+                        .ok()
+                        .map(|result| result.map(Arc::new));
+                }
+                received.as_ref().map(|result| result.as_ref().ok())
+            }
+            PaintState::Initialized { renderer } => Some(renderer.gpu_resources()),
+        }
+    }
+
     pub fn init_renderer(
         &mut self,
         gpu_resources: Option<std::sync::Arc<GpuResources>>,
         inner_size: Option<WindowSize>,
-    ) {
+    ) -> Result<(), RendererError> {
         if let PaintState::PendingGpuResources {
             window,
             rx,
+            received,
             font_embolden,
+            renderer_config,
             renderer,
         } = self
         {
-            // let gpu_resources = rx.recv().unwrap().unwrap();
-            let gpu_resources = if gpu_resources.is_some() {
-                gpu_resources.expect("Couldn't get resources")
-            } else {
-                Arc::new(rx.recv().unwrap().unwrap())
+            let gpu_resources = match (gpu_resources, received.take()) {
+                (Some(gpu_resources), _) => Ok(gpu_resources),
+                (None, Some(result)) => result,
+                (None, None) => rx
+                    .as_ref()
+                    .expect("GPU resources weren't requested")
+                    .recv()
+                    .unwrap()
+                    .map(Arc::new),
             };
             let inner_size = inner_size.expect("Couldn't get inner size");
-            let renderer = crate::renderer::Renderer::new(
+            let scale = renderer.scale();
+            let size = Size::new(inner_size.width as f64, inner_size.height as f64);
+            let (renderer, result) = match Renderer::new(
                 window.clone(),
-                gpu_resources.clone(),
-                renderer.scale(),
-                // renderer.size(),
-                Size::new(inner_size.width as f64, inner_size.height as f64),
+                gpu_resources,
+                scale,
+                size,
                 *font_embolden,
-            );
+                *renderer_config,
+            ) {
+                Ok(renderer) => (renderer, Ok(())),
+                // Without a renderer, the window paints nothing.
+                Err(err) => (Renderer::Uninitialized { scale, size }, Err(err)),
+            };
             *self = PaintState::Initialized { renderer };
+            result
         } else {
             panic!("Called PaintState::init_renderer when it was already initialized");
         }
@@ -0,0 +1,228 @@
+//! Data carried by drag and drop inside a window.
+//!
+//! A view becomes draggable with [`Decorators::on_drag_start`], which gives the [`DragPayload`]
+//! of each drag, and a drop target with [`Decorators::on_drop`], which accepts or rejects
+//! payloads before they are dropped on it. While the pointer is dragged, the view is painted
+//! under the pointer with its [`dragging_style`](Decorators::dragging_style), and targets
+//! accepting the payload under the pointer get their [`drag_over`](crate::style::Style::drag_over)
+//! style.
+//!
+//! [`dyn_stack`](crate::views::dyn_stack) and [`virtual_stack`](crate::views::virtual_stack) can
+//! reorder their items by dragging them onto each other, see
+//! [`DynStack::reorderable`](crate::views::DynStack::reorderable).
+//!
+//! [`Decorators`]: crate::views::Decorators
+//! [`Decorators::on_drag_start`]: crate::views::Decorators::on_drag_start
+//! [`Decorators::on_drop`]: crate::views::Decorators::on_drop
+
+use std::{any::Any, cell::Cell, fmt, rc::Rc};
+
+use peniko::kurbo::Point;
+
+use crate::id::ViewId;
+
+/// The data dragged out of a view: a value of any type, or bytes tagged with a MIME type.
+#[derive(Clone)]
+pub struct DragPayload {
+    value: Rc<dyn Any>,
+    mime_type: Option<Rc<str>>,
+}
+
+impl DragPayload {
+    /// A payload holding `value`, which drop targets accept by its type.
+    pub fn new<T: Any>(value: T) -> Self {
+        Self {
+            value: Rc::new(value),
+            mime_type: None,
+        }
+    }
+
+    /// A payload holding `data` in the format `mime_type`, such as `text/plain`.
+    pub fn mime(mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            value: Rc::new(data.into()),
+            mime_type: Some(mime_type.into().into()),
+        }
+    }
+
+    /// Whether the payload holds a value of type `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        self.value.is::<T>()
+    }
+
+    /// The value of the payload, if it has the type `T`.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+
+    /// The MIME type of the payload, if it was created with [`DragPayload::mime`].
+    pub fn mime_type(&self) -> Option<&str> {
+        self.mime_type.as_deref()
+    }
+
+    /// The data of the payload, if it was created with [`DragPayload::mime`].
+    pub fn data(&self) -> Option<&[u8]> {
+        self.mime_type
+            .as_ref()
+            .and(self.get::<Vec<u8>>())
+            .map(Vec::as_slice)
+    }
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("DragPayload");
+        match &self.mime_type {
+            Some(mime_type) => s.field("mime_type", mime_type),
+            None => s.field("type_id", &(*self.value).type_id()),
+        };
+        s.finish()
+    }
+}
+
+/// A payload dropped on a view, see [`Decorators::on_drop`](crate::views::Decorators::on_drop).
+#[derive(Debug, Clone)]
+pub struct DropEvent {
+    /// The position of the pointer, relative to the drop target.
+    pub pos: Point,
+    pub payload: DragPayload,
+    /// The view the payload was dragged out of.
+    pub source: ViewId,
+}
+
+pub(crate) struct DropTarget {
+    pub(crate) accepts: Rc<dyn Fn(&DragPayload) -> bool>,
+    pub(crate) on_drop: Rc<dyn Fn(&DropEvent)>,
+}
+
+/// The payload of an item dragged inside a reorderable stack.
+struct ReorderItem {
+    stack: ViewId,
+    item: ViewId,
+}
+
+/// Lets the items of a stack be reordered by dragging them onto each other.
+#[derive(Clone)]
+pub(crate) struct Reorder {
+    on_reorder: Rc<dyn Fn(usize, usize)>,
+    /// The index in the whole list of the first child of the stack.
+    first_index: Rc<Cell<usize>>,
+}
+
+impl Reorder {
+    pub(crate) fn new(on_reorder: impl Fn(usize, usize) + 'static) -> Self {
+        Self {
+            on_reorder: Rc::new(on_reorder),
+            first_index: Rc::new(Cell::new(0)),
+        }
+    }
+
+    pub(crate) fn set_first_index(&self, first_index: usize) {
+        self.first_index.set(first_index);
+    }
+
+    /// Makes the children of `stack` draggable onto each other.
+    pub(crate) fn attach(&self, stack: ViewId) {
+        for item in stack.children() {
+            item.update_drag_payload(Rc::new(move || {
+                DragPayload::new(ReorderItem { stack, item })
+            }));
+            let reorder = self.clone();
+            item.update_drop_target(DropTarget {
+                accepts: Rc::new(move |payload| {
+                    payload
+                        .get::<ReorderItem>()
+                        .is_some_and(|dragged| dragged.stack == stack)
+                }),
+                on_drop: Rc::new(move |event| {
+                    let Some(dragged) = event.payload.get::<ReorderItem>() else {
+                        return;
+                    };
+                    let children = stack.children();
+                    let index = |id| children.iter().position(|child| *child == id);
+                    // The dragged item may have been scrolled out of a virtual stack.
+                    if let (Some(from), Some(to)) = (index(dragged.item), index(item)) {
+                        if from != to {
+                            let first = reorder.first_index.get();
+                            (reorder.on_reorder)(first + from, first + to);
+                        }
+                    }
+                }),
+            });
+            item.draggable();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+
+    use super::DragPayload;
+    use crate::{
+        headless::test_window,
+        pointer::PointerButton,
+        views::{container, dyn_stack, empty, h_stack, Decorators},
+    };
+
+    #[test]
+    fn drop_targets_accept_payloads_by_type() {
+        let dropped = create_rw_signal(Vec::new());
+        let mut window = test_window((100.0, 50.0), move || {
+            h_stack((
+                empty()
+                    .on_drag_start(|| DragPayload::new(7_u32))
+                    .style(|s| s.size(50.0, 50.0)),
+                container(
+                    empty()
+                        .on_drop(
+                            |payload| payload.is::<String>(),
+                            move |_| dropped.update(|d| d.push("text")),
+                        )
+                        .style(|s| s.size(50.0, 50.0)),
+                )
+                .on_drop(
+                    |payload| payload.is::<u32>(),
+                    move |event| {
+                        let number = event.payload.get::<u32>().copied();
+                        dropped.update(|d| d.push(if number == Some(7) { "7" } else { "?" }))
+                    },
+                ),
+            ))
+        });
+
+        window.pointer_move((25.0, 25.0));
+        window.pointer_down(PointerButton::Primary);
+        window.pointer_move((50.0, 25.0));
+        window.pointer_move((75.0, 25.0));
+        window.pointer_up(PointerButton::Primary);
+        // The inner target rejects the number, so it is dropped on its parent.
+        assert_eq!(dropped.get_untracked(), vec!["7"]);
+    }
+
+    #[test]
+    fn dragging_an_item_onto_another_reorders_the_stack() {
+        let items = create_rw_signal(vec![0, 1, 2]);
+        let mut window = test_window((100.0, 100.0), move || {
+            dyn_stack(
+                move || items.get(),
+                |item| *item,
+                |_| empty().style(|s| s.size(100.0, 20.0)),
+            )
+            .reorderable(move |from, to| {
+                items.update(|items| {
+                    let item = items.remove(from);
+                    items.insert(to, item);
+                })
+            })
+            .style(|s| s.flex_col())
+        });
+
+        window.pointer_move((50.0, 10.0));
+        window.pointer_down(PointerButton::Primary);
+        window.pointer_move((50.0, 30.0));
+        window.pointer_move((50.0, 50.0));
+        window.pointer_up(PointerButton::Primary);
+        assert_eq!(items.get_untracked(), vec![1, 2, 0]);
+    }
+}
@@ -1,8 +1,116 @@
+//! Files dragged into a window from other applications.
+//!
+//! While files are dragged over a window, the view under the cursor receives
+//! [`Event::FileDragOver`](crate::event::Event::FileDragOver) every time the cursor moves, and
+//! views receive [`FileDragEnter`](crate::event::Event::FileDragEnter) and
+//! [`FileDragLeave`](crate::event::Event::FileDragLeave) as the cursor enters and leaves them,
+//! so they can highlight themselves as drop zones. Dropping the files sends a single
+//! [`DroppedFile`](crate::event::Event::DroppedFile) with all of them to the view under the
+//! cursor, followed by a `FileDragLeave`.
+//!
+//! The events are only sent once the cursor position is known. Files dragged in from outside
+//! the window wait for the platform to report the cursor moving over it, and are dropped at
+//! that position if they were dropped before.
+
 use peniko::kurbo::Point;
 use std::path::PathBuf;
 
+/// Files dropped on the window.
 #[derive(Debug, Clone)]
 pub struct DroppedFileEvent {
-    pub path: PathBuf,
+    /// All the files of the drop.
+    pub paths: Vec<PathBuf>,
     pub pos: Point,
 }
+
+/// Files dragged over the window, before they are dropped.
+#[derive(Debug, Clone)]
+pub struct FileDragEvent {
+    /// All the files of the drag.
+    pub paths: Vec<PathBuf>,
+    pub pos: Point,
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+
+    use crate::{
+        event::{Event, EventListener},
+        headless::test_window,
+        views::{empty, h_stack, Decorators},
+    };
+
+    #[test]
+    fn files_dragged_from_outside_highlight_and_drop_together() {
+        let log = create_rw_signal(Vec::new());
+        let zone = move |name: &'static str| {
+            empty()
+                .on_event_cont(EventListener::FileDragEnter, move |event| {
+                    if let Event::FileDragEnter(event) = event {
+                        log.update(|l| l.push(format!("enter {name} {}", event.paths.len())));
+                    }
+                })
+                .on_event_cont(EventListener::FileDragLeave, move |_| {
+                    log.update(|l| l.push(format!("leave {name}")))
+                })
+                .on_event_stop(EventListener::DroppedFile, move |event| {
+                    if let Event::DroppedFile(event) = event {
+                        log.update(|l| l.push(format!("drop {name} {}", event.paths.len())));
+                    }
+                })
+                .style(|s| s.size(50.0, 50.0))
+        };
+        let mut window = test_window((100.0, 50.0), move || {
+            h_stack((zone("left"), zone("right")))
+        });
+
+        window.pointer_move((25.0, 25.0));
+        window.file_hover(["a.png", "b.png"]);
+        window.pointer_move((75.0, 25.0));
+        window.file_drop(["a.png", "b.png"]);
+        assert_eq!(
+            log.get_untracked(),
+            vec![
+                "enter left 2",
+                "leave left",
+                "enter right 2",
+                "drop right 2",
+                "leave right"
+            ]
+        );
+
+        window.file_hover(["c.png"]);
+        window.file_hover_cancelled();
+        assert_eq!(log.get_untracked()[5..], ["enter right 1", "leave right"]);
+    }
+
+    #[test]
+    fn files_dropped_before_the_pointer_moves_wait_for_its_position() {
+        let dropped = create_rw_signal(Vec::new());
+        let zone = move |name: &'static str| {
+            empty()
+                .on_event_stop(EventListener::DroppedFile, move |event| {
+                    if let Event::DroppedFile(event) = event {
+                        dropped.update(|d| d.push((name, event.paths.len())));
+                    }
+                })
+                .style(|s| s.size(50.0, 50.0))
+        };
+        let mut window = test_window((100.0, 50.0), move || {
+            h_stack((zone("left"), zone("right")))
+        });
+
+        window.file_drop(["a.png"]);
+        assert!(dropped.get_untracked().is_empty());
+        window.pointer_move((75.0, 25.0));
+        assert_eq!(dropped.get_untracked(), vec![("right", 1)]);
+
+        // The position is stale again once the pointer left the window.
+        window.pointer_leave();
+        window.file_drop(["b.png", "c.png"]);
+        assert_eq!(dropped.get_untracked().len(), 1);
+        window.pointer_move((25.0, 25.0));
+        assert_eq!(dropped.get_untracked()[1..], [("left", 2)]);
+    }
+}
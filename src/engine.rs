@@ -0,0 +1,126 @@
+//! Rendering a custom wgpu scene under the UI of a window.
+//!
+//! This is only available with the `engine` feature. Without it, windows render their views only.
+//! To render a scene inside the layout instead of under the whole window, see
+//! [`wgpu_viewport`](crate::views::wgpu_viewport).
+//!
+//! An [`Engine`] is registered for every window of an application with
+//! [`Application::with_engine`](crate::Application::with_engine):
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//!
+//! use floem::engine::{Engine, FrameFormat, GpuResources};
+//! use floem::event::Event;
+//! use floem::window_handle::WindowSize;
+//! use floem::Application;
+//!
+//! struct Scene {
+//!     gpu_resources: Arc<GpuResources>,
+//! }
+//!
+//! impl Engine for Scene {
+//!     fn init(
+//!         gpu_resources: &Arc<GpuResources>,
+//!         _size: WindowSize,
+//!         _frame_format: FrameFormat,
+//!     ) -> Self {
+//!         Scene {
+//!             gpu_resources: gpu_resources.clone(),
+//!         }
+//!     }
+//!
+//!     fn encode(
+//!         &mut self,
+//!         _encoder: &mut wgpu::CommandEncoder,
+//!         _view: &wgpu::TextureView,
+//!         _depth_stencil: Option<&wgpu::TextureView>,
+//!     ) {
+//!         // Record the scene's render passes into `view`.
+//!     }
+//!
+//!     fn input(&mut self, _event: &Event) {
+//!         // Move the camera.
+//!     }
+//! }
+//!
+//! Application::new()
+//!     .with_engine::<Scene>()
+//!     .window(|_| "Hello", None)
+//!     .0
+//!     .run();
+//! ```
+
+use std::{rc::Rc, sync::Arc};
+
+pub use floem_renderer::config::FrameFormat;
+pub use floem_renderer::gpu_resources::GpuResources;
+
+use crate::{event::Event, window_handle::WindowSize};
+
+/// A custom scene rendered under the UI of a window.
+///
+/// Each window creates its own engine once it has acquired its GPU resources,
+/// and only when it renders with wgpu.
+pub trait Engine: 'static {
+    /// Creates the engine of a window whose surface is `size` physical pixels.
+    ///
+    /// `frame_format` is the format and sample count of the view given to
+    /// [`encode`](Self::encode), which the engine's pipelines must be created with.
+    fn init(gpu_resources: &Arc<GpuResources>, size: WindowSize, frame_format: FrameFormat) -> Self
+    where
+        Self: Sized;
+
+    /// Called when the window's surface is resized to `size` physical pixels.
+    fn resize(&mut self, _size: WindowSize) {}
+
+    /// Records the commands drawing the scene for a frame, before the UI is drawn over it.
+    ///
+    /// `view` is the color target of the frame, in the format and sample count given to
+    /// [`init`](Self::init). It is multisampled unless the sample count is `1`.
+    ///
+    /// `depth_stencil` is the depth/stencil texture of the frame if the window's
+    /// [`RendererConfig`](crate::window::RendererConfig) has one. It is cleared to a depth of
+    /// `1.0` and a stencil of `0` before this is called. The UI is then drawn over the scene
+    /// at a depth of `0.0` with the `Less` comparison, without writing depth or stencil, so
+    /// the scene hides the UI wherever it leaves a depth of `0.0`.
+    fn encode(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        depth_stencil: Option<&wgpu::TextureView>,
+    );
+
+    /// Receives the pointer, wheel, touch, touchpad gesture and key events no view handled,
+    /// in window coordinates.
+    ///
+    /// Pens arrive as pointer events, whose [`PointerType`](crate::pointer::PointerType),
+    /// pressure and tilt tell them apart from the mouse.
+    ///
+    /// Key events are received unless the focused view edits text, like a
+    /// [`text_input`](crate::views::text_input), so keys typed into it never reach the engine.
+    /// Modifier changes are always received.
+    fn input(&mut self, _event: &Event) {}
+
+    /// Whether the scene changed and the window needs to render a new frame.
+    ///
+    /// This is checked after every frame and every event given to [`input`](Self::input).
+    /// Return `true` while the scene is animating to keep it rendering with
+    /// [`FramePacing::OnDemand`](crate::window::FramePacing::OnDemand).
+    fn needs_redraw(&self) -> bool {
+        false
+    }
+}
+
+/// Creates the engine of a window.
+pub(crate) type EngineFactory =
+    Rc<dyn Fn(&Arc<GpuResources>, WindowSize, FrameFormat) -> Box<dyn Engine>>;
+
+pub(crate) fn engine_factory<E: Engine>() -> EngineFactory {
+    Rc::new(
+        |gpu_resources: &Arc<GpuResources>,
+         size: WindowSize,
+         frame_format: FrameFormat|
+         -> Box<dyn Engine> { Box::new(E::init(gpu_resources, size, frame_format)) },
+    )
+}
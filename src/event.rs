@@ -2,12 +2,15 @@ use floem_winit::{
     keyboard::{KeyCode, PhysicalKey},
     window::Theme,
 };
-use peniko::kurbo::{Point, Size};
+use peniko::kurbo::{Affine, Point, Size};
 
 use crate::{
-    dropped_file::DroppedFileEvent,
-    keyboard::KeyEvent,
+    dropped_file::{DroppedFileEvent, FileDragEvent},
+    keyboard::{KeyEvent, Modifiers},
     pointer::{PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
+    touch::{
+        DoubleTapZoomEvent, PinchEvent, RotateEvent, TouchEvent, TouchId, TouchpadPressureEvent,
+    },
 };
 
 /// Control whether an event will continue propagating or whether it should stop.
@@ -38,6 +41,8 @@ pub enum EventListener {
     KeyDown,
     /// Receives [`Event::KeyUp`]
     KeyUp,
+    /// Receives [`Event::ModifiersChanged`]
+    ModifiersChanged,
     /// Receives [`Event::PointerUp`] or [`Event::KeyDown`]
     /// `KeyDown` occurs when using enter on a focused element, such as a button.
     Click,
@@ -97,6 +102,28 @@ pub enum EventListener {
     WindowMaximizeChanged,
     /// Receives [`Event::DroppedFile`]
     DroppedFile,
+    /// Receives [`Event::FileDragEnter`]
+    FileDragEnter,
+    /// Receives [`Event::FileDragOver`]
+    FileDragOver,
+    /// Receives [`Event::FileDragLeave`]
+    FileDragLeave,
+    /// Receives [`Event::TouchStart`]
+    TouchStart,
+    /// Receives [`Event::TouchMove`]
+    TouchMove,
+    /// Receives [`Event::TouchEnd`]
+    TouchEnd,
+    /// Receives [`Event::TouchCancel`]
+    TouchCancel,
+    /// Receives [`Event::PinchZoom`]
+    PinchZoom,
+    /// Receives [`Event::Rotate`]
+    Rotate,
+    /// Receives [`Event::DoubleTapZoom`]
+    DoubleTapZoom,
+    /// Receives [`Event::TouchpadPressure`]
+    TouchpadPressure,
 }
 
 #[derive(Debug, Clone)]
@@ -106,9 +133,28 @@ pub enum Event {
     PointerMove(PointerMoveEvent),
     PointerWheel(PointerWheelEvent),
     PointerLeave,
+    /// A finger touched the screen, see [`touch`](crate::touch).
+    TouchStart(TouchEvent),
+    TouchMove(TouchEvent),
+    TouchEnd(TouchEvent),
+    /// The platform took over the finger, for example for a system gesture.
+    TouchCancel(TouchEvent),
+    PinchZoom(PinchEvent),
+    Rotate(RotateEvent),
+    DoubleTapZoom(DoubleTapZoomEvent),
+    TouchpadPressure(TouchpadPressureEvent),
+    /// Files from another application were dropped, see [`dropped_file`](crate::dropped_file).
     DroppedFile(DroppedFileEvent),
+    /// Files from another application were dragged onto a view.
+    FileDragEnter(FileDragEvent),
+    /// Files from another application are dragged over a view.
+    FileDragOver(FileDragEvent),
+    /// Files from another application were dragged off a view, out of the window or dropped.
+    FileDragLeave(FileDragEvent),
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
+    /// The modifier keys held changed. Unlike key events, every view receives it.
+    ModifiersChanged(Modifiers),
     ImeEnabled,
     ImeDisabled,
     ImePreedit {
@@ -142,13 +188,25 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
             | Event::ThemeChanged(_)
+            | Event::ModifiersChanged(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
             | Event::WindowLostFocus
-            | Event::DroppedFile(_) => false,
+            | Event::TouchStart(_)
+            | Event::TouchMove(_)
+            | Event::TouchEnd(_)
+            | Event::TouchCancel(_)
+            | Event::PinchZoom(_)
+            | Event::Rotate(_)
+            | Event::DoubleTapZoom(_)
+            | Event::TouchpadPressure(_)
+            | Event::DroppedFile(_)
+            | Event::FileDragEnter(_)
+            | Event::FileDragOver(_)
+            | Event::FileDragLeave(_) => false,
             Event::KeyDown(_) | Event::KeyUp(_) => true,
         }
     }
@@ -169,13 +227,25 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
             | Event::ThemeChanged(_)
+            | Event::ModifiersChanged(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
             | Event::WindowLostFocus
-            | Event::DroppedFile(_) => false,
+            | Event::TouchStart(_)
+            | Event::TouchMove(_)
+            | Event::TouchEnd(_)
+            | Event::TouchCancel(_)
+            | Event::PinchZoom(_)
+            | Event::Rotate(_)
+            | Event::DoubleTapZoom(_)
+            | Event::TouchpadPressure(_)
+            | Event::DroppedFile(_)
+            | Event::FileDragEnter(_)
+            | Event::FileDragOver(_)
+            | Event::FileDragLeave(_) => false,
         }
     }
 
@@ -206,17 +276,29 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
             | Event::KeyDown(_)
-            | Event::KeyUp(_) => false,
+            | Event::KeyUp(_)
+            | Event::TouchStart(_)
+            | Event::TouchEnd(_)
+            | Event::PinchZoom(_)
+            | Event::Rotate(_)
+            | Event::DoubleTapZoom(_)
+            | Event::TouchpadPressure(_) => false,
             Event::PointerLeave
             | Event::PointerMove(_)
+            | Event::TouchMove(_)
+            | Event::TouchCancel(_)
             | Event::ThemeChanged(_)
+            | Event::ModifiersChanged(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowGotFocus
             | Event::WindowMaximizeChanged(_)
             | Event::WindowLostFocus
-            | Event::DroppedFile(_) => true,
+            | Event::DroppedFile(_)
+            | Event::FileDragEnter(_)
+            | Event::FileDragOver(_)
+            | Event::FileDragLeave(_) => true,
         }
     }
 
@@ -227,7 +309,18 @@ impl Event {
             }
             Event::PointerMove(pointer_event) => Some(pointer_event.pos),
             Event::PointerWheel(pointer_event) => Some(pointer_event.pos),
+            Event::TouchStart(event)
+            | Event::TouchMove(event)
+            | Event::TouchEnd(event)
+            | Event::TouchCancel(event) => Some(event.pos),
+            Event::PinchZoom(event) => Some(event.pos),
+            Event::Rotate(event) => Some(event.pos),
+            Event::DoubleTapZoom(event) => Some(event.pos),
+            Event::TouchpadPressure(event) => Some(event.pos),
             Event::DroppedFile(event) => Some(event.pos),
+            Event::FileDragEnter(event)
+            | Event::FileDragOver(event)
+            | Event::FileDragLeave(event) => Some(event.pos),
             Event::PointerLeave
             | Event::KeyDown(_)
             | Event::KeyUp(_)
@@ -237,6 +330,7 @@ impl Event {
             | Event::ImeDisabled
             | Event::ImePreedit { .. }
             | Event::ThemeChanged(_)
+            | Event::ModifiersChanged(_)
             | Event::ImeCommit(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
@@ -261,10 +355,39 @@ impl Event {
                 pointer_event.pos.x /= scale;
                 pointer_event.pos.y /= scale;
             }
+            Event::TouchStart(event)
+            | Event::TouchMove(event)
+            | Event::TouchEnd(event)
+            | Event::TouchCancel(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
+            Event::PinchZoom(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
+            Event::Rotate(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
+            Event::DoubleTapZoom(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
+            Event::TouchpadPressure(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
             Event::DroppedFile(event) => {
                 event.pos.x /= scale;
                 event.pos.y /= scale;
             }
+            Event::FileDragEnter(event)
+            | Event::FileDragOver(event)
+            | Event::FileDragLeave(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
             Event::PointerLeave
             | Event::KeyDown(_)
             | Event::KeyUp(_)
@@ -274,6 +397,7 @@ impl Event {
             | Event::ImeDisabled
             | Event::ImePreedit { .. }
             | Event::ThemeChanged(_)
+            | Event::ModifiersChanged(_)
             | Event::ImeCommit(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
@@ -296,9 +420,32 @@ impl Event {
             Event::PointerWheel(pointer_event) => {
                 pointer_event.pos -= offset;
             }
+            Event::TouchStart(event)
+            | Event::TouchMove(event)
+            | Event::TouchEnd(event)
+            | Event::TouchCancel(event) => {
+                event.pos -= offset;
+            }
+            Event::PinchZoom(event) => {
+                event.pos -= offset;
+            }
+            Event::Rotate(event) => {
+                event.pos -= offset;
+            }
+            Event::DoubleTapZoom(event) => {
+                event.pos -= offset;
+            }
+            Event::TouchpadPressure(event) => {
+                event.pos -= offset;
+            }
             Event::DroppedFile(event) => {
                 event.pos -= offset;
             }
+            Event::FileDragEnter(event)
+            | Event::FileDragOver(event)
+            | Event::FileDragLeave(event) => {
+                event.pos -= offset;
+            }
             Event::PointerLeave
             | Event::KeyDown(_)
             | Event::KeyUp(_)
@@ -308,6 +455,66 @@ impl Event {
             | Event::ImeDisabled
             | Event::ImePreedit { .. }
             | Event::ThemeChanged(_)
+            | Event::ModifiersChanged(_)
+            | Event::ImeCommit(_)
+            | Event::WindowClosed
+            | Event::WindowResized(_)
+            | Event::WindowMoved(_)
+            | Event::WindowMaximizeChanged(_)
+            | Event::WindowGotFocus
+            | Event::WindowLostFocus => {}
+        }
+        self
+    }
+
+    /// Maps the position of pointer, touch and file drop events through `transform`.
+    pub fn transform(mut self, transform: Affine) -> Event {
+        match &mut self {
+            Event::PointerDown(pointer_event) | Event::PointerUp(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::PointerMove(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::PointerWheel(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::TouchStart(event)
+            | Event::TouchMove(event)
+            | Event::TouchEnd(event)
+            | Event::TouchCancel(event) => {
+                event.pos = transform * event.pos;
+            }
+            Event::PinchZoom(event) => {
+                event.pos = transform * event.pos;
+            }
+            Event::Rotate(event) => {
+                event.pos = transform * event.pos;
+            }
+            Event::DoubleTapZoom(event) => {
+                event.pos = transform * event.pos;
+            }
+            Event::TouchpadPressure(event) => {
+                event.pos = transform * event.pos;
+            }
+            Event::DroppedFile(event) => {
+                event.pos = transform * event.pos;
+            }
+            Event::FileDragEnter(event)
+            | Event::FileDragOver(event)
+            | Event::FileDragLeave(event) => {
+                event.pos = transform * event.pos;
+            }
+            Event::PointerLeave
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::ImeEnabled
+            | Event::ImeDisabled
+            | Event::ImePreedit { .. }
+            | Event::ThemeChanged(_)
+            | Event::ModifiersChanged(_)
             | Event::ImeCommit(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
@@ -341,7 +548,30 @@ impl Event {
             Event::FocusLost => Some(EventListener::FocusLost),
             Event::FocusGained => Some(EventListener::FocusGained),
             Event::ThemeChanged(_) => Some(EventListener::ThemeChanged),
+            Event::ModifiersChanged(_) => Some(EventListener::ModifiersChanged),
             Event::DroppedFile(_) => Some(EventListener::DroppedFile),
+            Event::FileDragEnter(_) => Some(EventListener::FileDragEnter),
+            Event::FileDragOver(_) => Some(EventListener::FileDragOver),
+            Event::FileDragLeave(_) => Some(EventListener::FileDragLeave),
+            Event::TouchStart(_) => Some(EventListener::TouchStart),
+            Event::TouchMove(_) => Some(EventListener::TouchMove),
+            Event::TouchEnd(_) => Some(EventListener::TouchEnd),
+            Event::TouchCancel(_) => Some(EventListener::TouchCancel),
+            Event::PinchZoom(_) => Some(EventListener::PinchZoom),
+            Event::Rotate(_) => Some(EventListener::Rotate),
+            Event::DoubleTapZoom(_) => Some(EventListener::DoubleTapZoom),
+            Event::TouchpadPressure(_) => Some(EventListener::TouchpadPressure),
+        }
+    }
+
+    /// The finger of a touch event.
+    pub(crate) fn touch_id(&self) -> Option<TouchId> {
+        match self {
+            Event::TouchStart(event)
+            | Event::TouchMove(event)
+            | Event::TouchEnd(event)
+            | Event::TouchCancel(event) => Some(event.id),
+            _ => None,
         }
     }
 }
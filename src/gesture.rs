@@ -0,0 +1,564 @@
+//! Long presses, pans, swipes and pinches.
+//!
+//! Gestures are recognized from the pointer and touch events of a view, and are added to views
+//! with [`Decorators::on_long_press`], [`on_pan`](Decorators::on_pan),
+//! [`on_swipe`](Decorators::on_swipe) and [`on_pinch`](Decorators::on_pinch). Long presses,
+//! pans and swipes follow the mouse, a pen or one finger on a touchscreen.
+//!
+//! A view watches the pointer or finger pressed on it without stopping its events, so its
+//! parents see them too. Once the gesture is recognized, because the pointer moved further
+//! than [`DRAG_THRESHOLD`] or was held for [`LONG_PRESS_DELAY`], the view claims it: the rest
+//! of its events are only sent to this view, even outside of it. Views receive events before
+//! their parents, so the deepest view recognizing a gesture wins. A pan inside a
+//! [`scroll`](crate::views::scroll) claims the finger before the scroll view starts panning,
+//! and the gestures of parents waiting on the same pointer are cancelled.
+//!
+//! [`Decorators::on_long_press`]: crate::views::Decorators::on_long_press
+//! [`Decorators`]: crate::views::Decorators
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+#[cfg(target_arch = "wasm32")]
+use web_time::{Duration, Instant};
+
+use peniko::kurbo::{Point, Vec2};
+
+use crate::{
+    action::{exec_after, TimerToken},
+    event::{Event, EventListener, EventPropagation},
+    id::ViewId,
+    touch::{GesturePhase, TouchId},
+};
+
+/// The distance in pixels a pointer has to move before it pans or swipes.
+pub const DRAG_THRESHOLD: f64 = 8.0;
+/// How long a pointer has to be held still for a long press.
+pub const LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+/// The speed in pixels per second a pointer has to be released at to swipe.
+const SWIPE_MIN_SPEED: f64 = 300.0;
+/// A pointer that rests longer than this before it is released has no velocity.
+const MAX_REST: Duration = Duration::from_millis(100);
+
+thread_local! {
+    /// The view that recognized a gesture with each pointer or finger, until it is released.
+    static CLAIMS: RefCell<HashMap<Contact, ViewId>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl SwipeDirection {
+    /// The direction of a pointer released at `velocity`, if it is fast enough to swipe.
+    fn from_velocity(velocity: Vec2) -> Option<Self> {
+        if velocity.hypot() < SWIPE_MIN_SPEED {
+            return None;
+        }
+        Some(if velocity.x.abs() >= velocity.y.abs() {
+            if velocity.x > 0.0 {
+                Self::Right
+            } else {
+                Self::Left
+            }
+        } else if velocity.y > 0.0 {
+            Self::Down
+        } else {
+            Self::Up
+        })
+    }
+}
+
+/// The movement of a pan, see [`Decorators::on_pan`](crate::views::Decorators::on_pan).
+#[derive(Debug, Clone)]
+pub struct PanEvent {
+    /// The position of the pointer, relative to the view.
+    pub pos: Point,
+    /// The movement since the previous event of the pan. The first event of a pan has all the
+    /// movement since the pointer was pressed.
+    pub delta: Vec2,
+    /// The movement since the pointer was pressed.
+    pub translation: Vec2,
+    /// The velocity of the pointer in pixels per second. It is zero at the end of a pan if the
+    /// pointer rested before it was released.
+    pub velocity: Vec2,
+}
+
+/// The progress of a pinch, see [`Decorators::on_pinch`](crate::views::Decorators::on_pinch).
+#[derive(Debug, Clone)]
+pub struct PinchGesture {
+    /// The center of the pinch, relative to the view.
+    pub pos: Point,
+    /// The scale since the start of the pinch, so content scaled by `s` before the pinch
+    /// should now be scaled by `s * scale`.
+    pub scale: f64,
+    /// The change of scale since the previous event, see
+    /// [`PinchEvent::delta`](crate::touch::PinchEvent::delta).
+    pub delta: f64,
+    pub phase: GesturePhase,
+}
+
+/// The mouse or pen, or a finger on a touchscreen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Contact {
+    Pointer,
+    Touch(TouchId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContactPhase {
+    Pressed,
+    Moved,
+    Released,
+    Cancelled,
+}
+
+/// The pointer or finger an event is about, what happened to it and where.
+fn contact_event(event: &Event) -> Option<(Contact, ContactPhase, Point)> {
+    Some(match event {
+        Event::PointerDown(event) if event.button.is_primary() => {
+            (Contact::Pointer, ContactPhase::Pressed, event.pos)
+        }
+        Event::PointerMove(event) => (Contact::Pointer, ContactPhase::Moved, event.pos),
+        Event::PointerUp(event) if event.button.is_primary() => {
+            (Contact::Pointer, ContactPhase::Released, event.pos)
+        }
+        Event::TouchStart(touch) => (Contact::Touch(touch.id), ContactPhase::Pressed, touch.pos),
+        Event::TouchMove(touch) => (Contact::Touch(touch.id), ContactPhase::Moved, touch.pos),
+        Event::TouchEnd(touch) => (Contact::Touch(touch.id), ContactPhase::Released, touch.pos),
+        Event::TouchCancel(touch) => (Contact::Touch(touch.id), ContactPhase::Cancelled, touch.pos),
+        _ => return None,
+    })
+}
+
+/// Makes the rest of the events of `contact` go to `id` only.
+fn claim(id: ViewId, contact: Contact) {
+    CLAIMS.with_borrow_mut(|claims| claims.insert(contact, id));
+    match contact {
+        Contact::Pointer => {
+            // The release ending the gesture isn't a click.
+            id.cancel_click();
+            id.request_active();
+        }
+        Contact::Touch(touch) => id.capture_touch(touch),
+    }
+}
+
+fn unclaim(contact: Contact) {
+    CLAIMS.with_borrow_mut(|claims| claims.remove(&contact));
+}
+
+fn claimed_by_other(id: ViewId, contact: Contact) -> bool {
+    CLAIMS.with_borrow(|claims| claims.get(&contact).is_some_and(|owner| *owner != id))
+}
+
+/// A pointer pressed on a view, followed until it is released.
+struct Tracker {
+    contact: Contact,
+    start: Point,
+    pos: Point,
+    last_move: Instant,
+    velocity: Vec2,
+    claimed: bool,
+}
+
+impl Tracker {
+    fn new(contact: Contact, pos: Point) -> Self {
+        Self {
+            contact,
+            start: pos,
+            pos,
+            last_move: Instant::now(),
+            velocity: Vec2::ZERO,
+            claimed: false,
+        }
+    }
+
+    /// Moves the pointer to `pos`, returning the movement if it moved.
+    fn move_to(&mut self, pos: Point) -> Option<Vec2> {
+        let delta = pos - self.pos;
+        // Views can receive the same move twice.
+        if delta == Vec2::ZERO {
+            return None;
+        }
+        let now = Instant::now();
+        let seconds = now.duration_since(self.last_move).as_secs_f64();
+        if seconds > 0.0 {
+            // Keep some of the previous estimate to smooth out jittery positions.
+            self.velocity = delta / seconds * 0.8 + self.velocity * 0.2;
+        }
+        self.pos = pos;
+        self.last_move = now;
+        Some(delta)
+    }
+
+    fn translation(&self) -> Vec2 {
+        self.pos - self.start
+    }
+
+    fn release_velocity(&self) -> Vec2 {
+        if self.last_move.elapsed() <= MAX_REST {
+            self.velocity
+        } else {
+            Vec2::ZERO
+        }
+    }
+
+    fn pan_event(&self, delta: Vec2, velocity: Vec2) -> PanEvent {
+        PanEvent {
+            pos: self.pos,
+            delta,
+            translation: self.translation(),
+            velocity,
+        }
+    }
+}
+
+enum DragStep {
+    /// The pointer moved past the threshold and the view claimed it.
+    Start,
+    /// The claimed pointer moved by the given amount.
+    Move(Vec2),
+    /// The claimed pointer got an event that didn't move it.
+    Hold,
+    /// The claimed pointer was released or cancelled.
+    End { tracker: Tracker, cancelled: bool },
+}
+
+/// Follows the pointer pressed on a view, which the view claims once it moves past the drag
+/// threshold.
+#[derive(Default)]
+struct Drag {
+    tracker: Option<Tracker>,
+}
+
+impl Drag {
+    /// Handles an event of the view `id`. The view should stop the propagation of the event
+    /// when this returns a step.
+    fn event(&mut self, id: ViewId, event: &Event) -> Option<DragStep> {
+        let (contact, phase, pos) = contact_event(event)?;
+        match phase {
+            ContactPhase::Pressed => {
+                // Keep following a claimed pointer when another finger is pressed.
+                if self
+                    .tracker
+                    .as_ref()
+                    .map_or(true, |tracker| !tracker.claimed)
+                {
+                    unclaim(contact);
+                    self.tracker = Some(Tracker::new(contact, pos));
+                }
+                None
+            }
+            ContactPhase::Moved => {
+                let tracker = self
+                    .tracker
+                    .as_mut()
+                    .filter(|tracker| tracker.contact == contact)?;
+                let delta = tracker.move_to(pos);
+                if tracker.claimed {
+                    return Some(delta.map_or(DragStep::Hold, DragStep::Move));
+                }
+                if claimed_by_other(id, contact) {
+                    self.tracker = None;
+                    return None;
+                }
+                if tracker.translation().hypot() <= DRAG_THRESHOLD {
+                    return None;
+                }
+                tracker.claimed = true;
+                claim(id, contact);
+                Some(DragStep::Start)
+            }
+            ContactPhase::Released | ContactPhase::Cancelled => {
+                let tracker = self.tracker.take_if(|tracker| tracker.contact == contact)?;
+                if !tracker.claimed {
+                    return None;
+                }
+                unclaim(contact);
+                Some(DragStep::End {
+                    tracker,
+                    cancelled: phase == ContactPhase::Cancelled,
+                })
+            }
+        }
+    }
+}
+
+/// A pointer held on a view, until it moves or the long press is recognized.
+#[derive(Default)]
+struct LongPress {
+    tracker: Option<Tracker>,
+    timer: Option<TimerToken>,
+}
+
+impl LongPress {
+    fn cancel_timer(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.cancel();
+        }
+    }
+}
+
+/// Gives the pointer and touch events of `id` to `handler`.
+fn add_contact_listener(id: ViewId, handler: impl FnMut(&Event) -> EventPropagation + 'static) {
+    let handler = Rc::new(RefCell::new(handler));
+    for listener in [
+        EventListener::PointerDown,
+        EventListener::PointerMove,
+        EventListener::PointerUp,
+        EventListener::TouchStart,
+        EventListener::TouchMove,
+        EventListener::TouchEnd,
+        EventListener::TouchCancel,
+    ] {
+        let handler = handler.clone();
+        id.add_event_listener(
+            listener,
+            Box::new(move |event| (handler.borrow_mut())(event)),
+        );
+    }
+}
+
+pub(crate) fn add_long_press(id: ViewId, action: impl Fn(Point) + 'static) {
+    let state = Rc::new(RefCell::new(LongPress::default()));
+    let action: Rc<dyn Fn(Point)> = Rc::new(action);
+    add_contact_listener(id, move |event| {
+        let Some((contact, phase, pos)) = contact_event(event) else {
+            return EventPropagation::Continue;
+        };
+        let mut long_press = state.borrow_mut();
+        match phase {
+            ContactPhase::Pressed => {
+                if long_press
+                    .tracker
+                    .as_ref()
+                    .is_some_and(|tracker| tracker.claimed)
+                {
+                    return EventPropagation::Continue;
+                }
+                long_press.cancel_timer();
+                unclaim(contact);
+                long_press.tracker = Some(Tracker::new(contact, pos));
+                let state = state.clone();
+                let action = action.clone();
+                long_press.timer = Some(exec_after(LONG_PRESS_DELAY, move |token| {
+                    let mut long_press = state.borrow_mut();
+                    if long_press.timer != Some(token) {
+                        return;
+                    }
+                    long_press.timer = None;
+                    let Some(tracker) = long_press.tracker.as_mut() else {
+                        return;
+                    };
+                    if claimed_by_other(id, tracker.contact) {
+                        long_press.tracker = None;
+                        return;
+                    }
+                    tracker.claimed = true;
+                    claim(id, tracker.contact);
+                    let pos = tracker.pos;
+                    drop(long_press);
+                    action(pos);
+                }));
+                EventPropagation::Continue
+            }
+            ContactPhase::Moved => {
+                let Some(tracker) = long_press
+                    .tracker
+                    .as_mut()
+                    .filter(|tracker| tracker.contact == contact)
+                else {
+                    return EventPropagation::Continue;
+                };
+                if tracker.claimed {
+                    return EventPropagation::Stop;
+                }
+                tracker.move_to(pos);
+                if tracker.translation().hypot() > DRAG_THRESHOLD {
+                    long_press.cancel_timer();
+                    long_press.tracker = None;
+                }
+                EventPropagation::Continue
+            }
+            ContactPhase::Released | ContactPhase::Cancelled => {
+                let Some(tracker) = long_press
+                    .tracker
+                    .take_if(|tracker| tracker.contact == contact)
+                else {
+                    return EventPropagation::Continue;
+                };
+                long_press.cancel_timer();
+                if tracker.claimed {
+                    unclaim(contact);
+                    EventPropagation::Stop
+                } else {
+                    EventPropagation::Continue
+                }
+            }
+        }
+    });
+}
+
+pub(crate) fn add_pan(
+    id: ViewId,
+    start: impl Fn(&PanEvent) + 'static,
+    update: impl Fn(&PanEvent) + 'static,
+    end: impl Fn(&PanEvent) + 'static,
+) {
+    let mut drag = Drag::default();
+    add_contact_listener(id, move |event| {
+        let Some(step) = drag.event(id, event) else {
+            return EventPropagation::Continue;
+        };
+        match step {
+            DragStep::Start => {
+                let tracker = drag.tracker.as_ref().unwrap();
+                start(&tracker.pan_event(tracker.translation(), tracker.velocity));
+            }
+            DragStep::Move(delta) => {
+                let tracker = drag.tracker.as_ref().unwrap();
+                update(&tracker.pan_event(delta, tracker.velocity));
+            }
+            DragStep::Hold => {}
+            DragStep::End { tracker, .. } => {
+                end(&tracker.pan_event(Vec2::ZERO, tracker.release_velocity()));
+            }
+        }
+        EventPropagation::Stop
+    });
+}
+
+pub(crate) fn add_swipe(id: ViewId, action: impl Fn(SwipeDirection) + 'static) {
+    let mut drag = Drag::default();
+    add_contact_listener(id, move |event| {
+        let Some(step) = drag.event(id, event) else {
+            return EventPropagation::Continue;
+        };
+        if let DragStep::End {
+            tracker,
+            cancelled: false,
+        } = step
+        {
+            if let Some(direction) = SwipeDirection::from_velocity(tracker.release_velocity()) {
+                action(direction);
+            }
+        }
+        EventPropagation::Stop
+    });
+}
+
+pub(crate) fn add_pinch(id: ViewId, action: impl Fn(&PinchGesture) + 'static) {
+    let scale = Cell::new(1.0);
+    id.add_event_listener(
+        EventListener::PinchZoom,
+        Box::new(move |event| {
+            let Event::PinchZoom(pinch) = event else {
+                return EventPropagation::Continue;
+            };
+            if pinch.phase == GesturePhase::Started {
+                scale.set(1.0);
+            }
+            scale.set(scale.get() * (1.0 + pinch.delta));
+            action(&PinchGesture {
+                pos: pinch.pos,
+                scale: scale.get(),
+                delta: pinch.delta,
+                phase: pinch.phase,
+            });
+            EventPropagation::Stop
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+    use peniko::kurbo::Vec2;
+
+    use super::{PanEvent, SwipeDirection};
+    use crate::{
+        headless::test_window,
+        pointer::PointerButton,
+        views::{empty, scroll, v_stack, Decorators},
+    };
+
+    #[test]
+    fn swipe_direction_follows_the_fastest_axis() {
+        assert_eq!(SwipeDirection::from_velocity(Vec2::new(100.0, 50.0)), None);
+        assert_eq!(
+            SwipeDirection::from_velocity(Vec2::new(-800.0, 300.0)),
+            Some(SwipeDirection::Left)
+        );
+        assert_eq!(
+            SwipeDirection::from_velocity(Vec2::new(200.0, 900.0)),
+            Some(SwipeDirection::Down)
+        );
+    }
+
+    #[test]
+    fn pan_is_not_a_click() {
+        let clicks = create_rw_signal(0);
+        let panned = create_rw_signal(Vec2::ZERO);
+        let mut window = test_window((100.0, 100.0), move || {
+            let pan = move |event: &PanEvent| panned.update(|p| *p += event.delta);
+            empty()
+                .on_click_stop(move |_| clicks.update(|c| *c += 1))
+                .on_pan(pan, pan, |_| {})
+                .style(|s| s.size_full())
+        });
+
+        window.pointer_move((10.0, 10.0));
+        window.pointer_down(PointerButton::Primary);
+        window.pointer_move((60.0, 10.0));
+        window.pointer_up(PointerButton::Primary);
+        assert_eq!(panned.get_untracked(), Vec2::new(50.0, 0.0));
+        assert_eq!(clicks.get_untracked(), 0);
+
+        // A press that doesn't move past the drag threshold is still a click.
+        window.click((60.0, 10.0));
+        assert_eq!(clicks.get_untracked(), 1);
+    }
+
+    #[test]
+    fn pan_inside_scroll_claims_the_finger() {
+        let offset = create_rw_signal(0.0);
+        let panned = create_rw_signal(Vec2::ZERO);
+        let ended = create_rw_signal(false);
+        let mut window = test_window((100.0, 100.0), move || {
+            let pan = move |event: &PanEvent| panned.update(|p| *p += event.delta);
+            scroll(v_stack((
+                empty()
+                    .on_pan(pan, pan, move |_| ended.set(true))
+                    .style(|s| s.size(100.0, 50.0)),
+                empty().style(|s| s.size(100.0, 350.0)),
+            )))
+            .on_scroll(move |viewport| offset.set(viewport.y0))
+            .style(|s| s.size(100.0, 100.0))
+        });
+
+        // The pan sees the finger move first, so it claims the finger instead of the scroll view.
+        window.touch_start(1, (50.0, 25.0));
+        window.touch_move(1, (50.0, 5.0));
+        window.touch_move(1, (50.0, 80.0));
+        window.touch_end(1, (50.0, 80.0));
+        assert_eq!(panned.get_untracked(), Vec2::new(0.0, 55.0));
+        assert!(ended.get_untracked());
+        assert_eq!(offset.get_untracked(), 0.0);
+
+        window.touch_start(2, (50.0, 90.0));
+        window.touch_move(2, (50.0, 60.0));
+        window.touch_end(2, (50.0, 60.0));
+        assert_eq!(offset.get_untracked(), 30.0);
+        assert_eq!(panned.get_untracked(), Vec2::new(0.0, 55.0));
+    }
+}
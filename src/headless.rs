@@ -0,0 +1,527 @@
+//! Rendering views without a window.
+//!
+//! A [`HeadlessWindow`] owns the same state as a real window (views, styles, layout)
+//! but paints with the CPU renderer into an offscreen pixmap. It needs no event loop,
+//! display server or GPU, which makes it usable for snapshot tests on CI machines.
+//!
+//! It can also drive the view tree with synthetic input, for integration tests that
+//! check signals, focus, hover and active state without a real window.
+//!
+//! ```rust,no_run
+//! use floem::headless::render_view;
+//! use floem::views::{text, Decorators};
+//!
+//! let image = render_view(|| text("Hello").style(|s| s.padding(10.0)), (200.0, 100.0), 2.0);
+//! assert_eq!(image.width(), 400);
+//! ```
+
+use std::path::PathBuf;
+
+use floem_renderer::tiny_skia::Pixmap;
+use floem_winit::event::{ElementState, Ime, MouseButton, TouchPhase};
+use image::DynamicImage;
+use peniko::kurbo::{Point, Size, Vec2};
+
+use crate::{
+    app_state::AppState,
+    event::Event,
+    id::ViewId,
+    keyboard::{Key, KeyInfo, Modifiers, NamedKey},
+    pointer::{PointerButton, PointerWheelEvent},
+    renderer::Renderer,
+    theme::WindowTheme,
+    view::IntoView,
+    window_handle::WindowHandle,
+};
+
+/// Configures a [`HeadlessWindow`].
+#[derive(Debug, Clone)]
+pub struct HeadlessConfig {
+    pub size: Size,
+    pub scale: f64,
+    pub apply_default_theme: bool,
+    pub theme: Option<WindowTheme>,
+    pub font_embolden: f32,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            size: Size::new(800.0, 600.0),
+            scale: 1.0,
+            apply_default_theme: true,
+            theme: None,
+            font_embolden: 0.,
+        }
+    }
+}
+
+impl HeadlessConfig {
+    /// Sets the logical size of the window.
+    ///
+    /// The default is `800x600`.
+    #[inline]
+    pub fn size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+
+    /// Sets the scale factor. The rendered image is `size * scale` pixels.
+    ///
+    /// The default is `1.0`.
+    #[inline]
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// If set to true, the stylesheet for Floem's default theme will be applied.
+    ///
+    /// The default is `true`.
+    #[inline]
+    pub fn apply_default_theme(mut self, apply_default_theme: bool) -> Self {
+        self.apply_default_theme = apply_default_theme;
+        self
+    }
+
+    /// Sets the theme of the window.
+    ///
+    /// This takes precedence over [`apply_default_theme`](Self::apply_default_theme).
+    #[inline]
+    pub fn theme(mut self, theme: impl Into<WindowTheme>) -> Self {
+        self.theme = Some(theme.into());
+        self
+    }
+
+    /// Sets the amount by which fonts are emboldened.
+    ///
+    /// The default is `0.0`.
+    #[inline]
+    pub fn font_embolden(mut self, font_embolden: f32) -> Self {
+        self.font_embolden = font_embolden;
+        self
+    }
+}
+
+/// A window without a platform window, which renders its view tree to an image.
+pub struct HeadlessWindow {
+    pub(crate) handle: WindowHandle,
+    size: Size,
+    scale: f64,
+}
+
+impl HeadlessWindow {
+    pub fn new<V: IntoView + 'static>(
+        app_view: impl FnOnce() -> V + 'static,
+        config: HeadlessConfig,
+    ) -> Self {
+        let handle = WindowHandle::new_headless(
+            move |_| app_view().into_any(),
+            config.size,
+            config.scale,
+            config
+                .theme
+                .or_else(|| config.apply_default_theme.then(WindowTheme::default)),
+            config.font_embolden,
+        );
+        Self {
+            handle,
+            size: config.size,
+            scale: config.scale,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Resizes the window, running style and layout for the new size.
+    pub fn resize(&mut self, size: impl Into<Size>) {
+        self.size = size.into();
+        self.handle.size(self.size);
+    }
+
+    /// Changes the scale factor, which also changes the size of the rendered image.
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale;
+        self.handle.scale(scale);
+        self.handle.size(self.size);
+    }
+
+    /// Processes pending updates, then runs style, layout and paint and returns the frame.
+    pub fn render(&mut self) -> DynamicImage {
+        self.handle
+            .render_frame()
+            .expect("headless renderer always produces an image")
+    }
+
+    /// Like [`HeadlessWindow::render`], but returns the premultiplied pixmap the frame was painted into.
+    pub fn render_pixmap(&mut self) -> Pixmap {
+        self.handle.render_frame();
+        match self.handle.paint_state.renderer() {
+            Renderer::TinySkia(renderer) => renderer.pixmap().clone(),
+            _ => unreachable!("headless windows always use the tiny-skia renderer"),
+        }
+    }
+}
+
+/// Synthetic input.
+///
+/// Positions are logical coordinates relative to the window. Each call first brings style and
+/// layout up to date, so hit testing sees the current view tree, and processes the resulting
+/// updates afterwards.
+impl HeadlessWindow {
+    /// The id of the view returned by the view function.
+    pub fn root_view(&self) -> ViewId {
+        self.handle.main_view()
+    }
+
+    pub fn app_state(&self) -> &AppState {
+        self.handle.app_state()
+    }
+
+    /// The currently focused view.
+    pub fn focus(&self) -> Option<ViewId> {
+        self.handle.app_state().focus
+    }
+
+    /// The view receiving all pointer events, for example while dragging a slider.
+    pub fn active(&self) -> Option<ViewId> {
+        self.handle.app_state().active
+    }
+
+    pub fn is_focused(&self, id: ViewId) -> bool {
+        self.handle.app_state().is_focused(&id)
+    }
+
+    pub fn is_hovered(&self, id: ViewId) -> bool {
+        self.handle.app_state().is_hovered(&id)
+    }
+
+    pub fn is_active(&self, id: ViewId) -> bool {
+        self.handle.app_state().is_active(&id)
+    }
+
+    pub fn cursor_position(&self) -> Point {
+        self.handle.cursor_position
+    }
+
+    /// Runs the update, style and layout passes without painting.
+    pub fn update(&mut self) {
+        self.handle.process_update_no_paint();
+    }
+
+    /// Dispatches an arbitrary event, as if it came from the platform.
+    pub fn event(&mut self, event: Event) {
+        self.update();
+        self.handle.event(event);
+    }
+
+    /// Sets the modifiers attached to subsequent pointer and key events, sending
+    /// `ModifiersChanged` if they changed.
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.update();
+        self.handle.set_modifiers(modifiers);
+    }
+
+    pub fn pointer_move(&mut self, pos: impl Into<Point>) {
+        self.update();
+        self.handle.pointer_move(pos.into());
+    }
+
+    /// Presses `button` at the current cursor position.
+    /// Repeated presses at the same position count as double and triple clicks.
+    pub fn pointer_down(&mut self, button: PointerButton) {
+        self.update();
+        self.handle
+            .mouse_input(to_mouse_button(button), ElementState::Pressed);
+    }
+
+    /// Releases `button` at the current cursor position.
+    pub fn pointer_up(&mut self, button: PointerButton) {
+        self.update();
+        self.handle
+            .mouse_input(to_mouse_button(button), ElementState::Released);
+    }
+
+    /// Moves the pointer to `pos` and clicks the primary button.
+    pub fn click(&mut self, pos: impl Into<Point>) {
+        self.pointer_move(pos);
+        self.pointer_down(PointerButton::Primary);
+        self.pointer_up(PointerButton::Primary);
+    }
+
+    /// Moves the pointer out of the window.
+    pub fn pointer_leave(&mut self) {
+        self.update();
+        self.handle.pointer_leave();
+    }
+
+    /// Scrolls at the current cursor position. `delta` is in logical pixels,
+    /// positive values scroll the content up and to the left.
+    pub fn wheel(&mut self, delta: impl Into<Vec2>) {
+        let event = PointerWheelEvent {
+            pos: self.handle.cursor_position,
+            delta: delta.into(),
+            modifiers: self.handle.modifiers,
+        };
+        self.event(Event::PointerWheel(event));
+    }
+
+    /// Puts the finger `id` on the screen at `pos`. The same `id` moves and lifts it.
+    pub fn touch_start(&mut self, id: u64, pos: impl Into<Point>) {
+        self.update();
+        self.handle.touch(id, TouchPhase::Started, pos.into(), None);
+    }
+
+    pub fn touch_move(&mut self, id: u64, pos: impl Into<Point>) {
+        self.update();
+        self.handle.touch(id, TouchPhase::Moved, pos.into(), None);
+    }
+
+    pub fn touch_end(&mut self, id: u64, pos: impl Into<Point>) {
+        self.update();
+        self.handle.touch(id, TouchPhase::Ended, pos.into(), None);
+    }
+
+    /// Touches the surface with a pen at `pos`, which sends a `PointerDown` of the primary
+    /// button with the pen's pressure and its tilt in degrees from upright.
+    pub fn pen_down(&mut self, pos: impl Into<Point>, pressure: f64, tilt: f64) {
+        self.pen(TouchPhase::Started, pos.into(), pressure, tilt);
+    }
+
+    pub fn pen_move(&mut self, pos: impl Into<Point>, pressure: f64, tilt: f64) {
+        self.pen(TouchPhase::Moved, pos.into(), pressure, tilt);
+    }
+
+    pub fn pen_up(&mut self, pos: impl Into<Point>, tilt: f64) {
+        self.pen(TouchPhase::Ended, pos.into(), 0.0, tilt);
+    }
+
+    fn pen(&mut self, phase: TouchPhase, pos: Point, pressure: f64, tilt: f64) {
+        self.update();
+        self.handle.pen(phase, pos, pressure, Some(tilt));
+    }
+
+    /// Drags `paths` from another application over the window, at the current cursor
+    /// position. Moving the pointer then moves the files, until they are dropped or cancelled.
+    ///
+    /// If the pointer hasn't moved in the window since it last left it, the files only reach
+    /// views once it moves, like on platforms that don't report the cursor during a drag.
+    pub fn file_hover<P: Into<PathBuf>>(&mut self, paths: impl IntoIterator<Item = P>) {
+        self.update();
+        for path in paths {
+            self.handle.hovered_file(path.into());
+        }
+        self.handle.flush_file_drag();
+    }
+
+    /// Drags the hovered files back out of the window.
+    pub fn file_hover_cancelled(&mut self) {
+        self.update();
+        self.handle.hovered_file_cancelled();
+    }
+
+    /// Drops `paths` from another application at the current cursor position.
+    pub fn file_drop<P: Into<PathBuf>>(&mut self, paths: impl IntoIterator<Item = P>) {
+        self.update();
+        for path in paths {
+            self.handle.dropped_file(path.into());
+        }
+        self.handle.flush_file_drag();
+    }
+
+    /// Presses `key` with `modifiers` held, which sends it to the focused view, or to the root
+    /// view if nothing is focused. The key has the text and physical key it has on a US
+    /// keyboard layout.
+    pub fn key_down(&mut self, key: Key, modifiers: Modifiers) {
+        self.key(key, ElementState::Pressed, modifiers);
+    }
+
+    pub fn key_up(&mut self, key: Key, modifiers: Modifiers) {
+        self.key(key, ElementState::Released, modifiers);
+    }
+
+    /// Presses and releases `key` without modifiers.
+    pub fn key_press(&mut self, key: Key) {
+        self.key_down(key.clone(), Modifiers::empty());
+        self.key_up(key, Modifiers::empty());
+    }
+
+    /// Types `text` into the focused view, one character key at a time.
+    pub fn type_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            let key = match ch {
+                ' ' => Key::Named(NamedKey::Space),
+                ch => Key::Character(ch.to_string().into()),
+            };
+            self.key_press(key);
+        }
+    }
+
+    fn key(&mut self, key: Key, state: ElementState, modifiers: Modifiers) {
+        self.set_modifiers(modifiers);
+        self.update();
+        self.handle.key_event(KeyInfo::new(key, state));
+    }
+
+    pub fn ime_enabled(&mut self) {
+        self.update();
+        self.handle.ime(Ime::Enabled);
+    }
+
+    pub fn ime_preedit(&mut self, text: impl Into<String>, cursor: Option<(usize, usize)>) {
+        self.update();
+        self.handle.ime(Ime::Preedit(text.into(), cursor));
+    }
+
+    pub fn ime_commit(&mut self, text: impl Into<String>) {
+        self.update();
+        self.handle.ime(Ime::Commit(text.into()));
+    }
+
+    pub fn ime_disabled(&mut self) {
+        self.update();
+        self.handle.ime(Ime::Disabled);
+    }
+
+    /// Simulates the OS switching between its light and dark themes.
+    pub fn os_theme_changed(&mut self, theme: crate::window::Theme) {
+        self.update();
+        self.handle.os_theme_changed(theme);
+    }
+}
+
+fn to_mouse_button(button: PointerButton) -> MouseButton {
+    match button {
+        PointerButton::Primary => MouseButton::Left,
+        PointerButton::Secondary => MouseButton::Right,
+        PointerButton::Auxiliary => MouseButton::Middle,
+        PointerButton::X1 => MouseButton::Back,
+        PointerButton::X2 => MouseButton::Forward,
+        PointerButton::None => MouseButton::Other(0),
+    }
+}
+
+impl Drop for HeadlessWindow {
+    fn drop(&mut self) {
+        self.handle.destroy();
+    }
+}
+
+/// Renders a view once at the given logical size and scale factor.
+pub fn render_view<V: IntoView + 'static>(
+    app_view: impl FnOnce() -> V + 'static,
+    size: impl Into<Size>,
+    scale: f64,
+) -> DynamicImage {
+    let config = HeadlessConfig::default().size(size).scale(scale);
+    HeadlessWindow::new(app_view, config).render()
+}
+
+/// A window of the given logical size showing `app_view`, for the tests of this crate.
+#[cfg(test)]
+pub(crate) fn test_window<V: IntoView + 'static>(
+    size: impl Into<Size>,
+    app_view: impl FnOnce() -> V + 'static,
+) -> HeadlessWindow {
+    HeadlessWindow::new(app_view, HeadlessConfig::default().size(size))
+}
+
+/// Like [`test_window`], but `app_view` also returns values the test needs, such as the ids
+/// of views, which are returned with the window.
+#[cfg(test)]
+pub(crate) fn test_window_with<V: IntoView + 'static, T: 'static>(
+    size: impl Into<Size>,
+    app_view: impl FnOnce() -> (V, T) + 'static,
+) -> (HeadlessWindow, T) {
+    let values = std::rc::Rc::new(std::cell::Cell::new(None));
+    let window = test_window(size, {
+        let values = values.clone();
+        move || {
+            let (view, value) = app_view();
+            values.set(Some(value));
+            view
+        }
+    });
+    let value = values
+        .take()
+        .expect("the view function runs when the window is created");
+    (window, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+    use peniko::Color;
+
+    use super::{render_view, test_window};
+    use crate::{
+        event::{Event, EventListener},
+        keyboard::{Key, Modifiers},
+        views::{empty, Decorators},
+    };
+
+    #[test]
+    fn render_view_paints_at_scale() {
+        let image = render_view(
+            || empty().style(|s| s.size_full().background(Color::RED)),
+            (20.0, 10.0),
+            2.0,
+        );
+
+        assert_eq!((image.width(), image.height()), (40, 20));
+        assert_eq!(image.to_rgba8().get_pixel(30, 15).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn click_updates_signals_and_focus() {
+        let clicks = create_rw_signal(0);
+        let mut window = test_window((100.0, 100.0), move || {
+            empty()
+                .keyboard_navigatable()
+                .on_click_stop(move |_| clicks.update(|c| *c += 1))
+                .style(|s| s.size(50.0, 50.0))
+        });
+        let id = window.root_view();
+
+        window.pointer_move((25.0, 25.0));
+        assert!(window.is_hovered(id));
+
+        window.click((25.0, 25.0));
+        assert_eq!(clicks.get_untracked(), 1);
+        assert_eq!(window.focus(), Some(id));
+
+        window.pointer_move((75.0, 75.0));
+        assert!(!window.is_hovered(id));
+        window.pointer_leave();
+    }
+
+    #[test]
+    fn keys_go_to_the_focused_view_with_their_modifiers() {
+        let keys = create_rw_signal(Vec::new());
+        let mut window = test_window((100.0, 100.0), move || {
+            empty()
+                .keyboard_navigatable()
+                .on_event_stop(EventListener::KeyDown, move |event| {
+                    if let Event::KeyDown(event) = event {
+                        let key = (event.key.logical_key.clone(), event.modifiers);
+                        keys.update(|keys| keys.push(key));
+                    }
+                })
+                .style(|s| s.size(50.0, 50.0))
+        });
+
+        window.click((25.0, 25.0));
+        window.key_down(Key::Character("s".into()), Modifiers::CONTROL);
+        window.key_up(Key::Character("s".into()), Modifiers::empty());
+        assert_eq!(
+            keys.get_untracked(),
+            vec![(Key::Character("s".into()), Modifiers::CONTROL)]
+        );
+    }
+}
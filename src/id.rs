@@ -14,9 +14,11 @@ use taffy::{Display, Layout, NodeId, TaffyTree};
 use crate::{
     animate::{AnimStateCommand, Animation},
     context::{EventCallback, ResizeCallback},
+    drag::{DragPayload, DropTarget},
     event::{EventListener, EventPropagation},
     menu::Menu,
     style::{DisplayProp, Style, StyleClassRef, StyleSelector},
+    touch::TouchId,
     unit::PxPct,
     update::{UpdateMessage, CENTRAL_DEFERRED_UPDATE_MESSAGES, CENTRAL_UPDATE_MESSAGES},
     view::{IntoView, View},
@@ -342,6 +344,16 @@ impl ViewId {
         self.state().borrow_mut().popout_menu = Some(Rc::new(menu));
     }
 
+    /// Set the payload of the drags out of this view.
+    pub(crate) fn update_drag_payload(&self, payload: Rc<dyn Fn() -> DragPayload>) {
+        self.state().borrow_mut().drag_payload = Some(payload);
+    }
+
+    /// Make this view a drop target.
+    pub(crate) fn update_drop_target(&self, target: DropTarget) {
+        self.state().borrow_mut().drop_target = Some(target);
+    }
+
     /// Request that this view receive the active state (mark that this element is currently being interacted with)
     ///
     /// When an View has Active, it will receive events such as mouse events, even if the mouse is not directly over this view.
@@ -356,6 +368,21 @@ impl ViewId {
         self.add_update_message(UpdateMessage::ClearActive(*self));
     }
 
+    /// Request that releasing the pointer pressed on this View doesn't click it or any other
+    /// View, because a gesture took the pointer over.
+    pub(crate) fn cancel_click(&self) {
+        self.add_update_message(UpdateMessage::CancelClick(*self));
+    }
+
+    /// Request that the rest of the events of a finger on the touchscreen are sent to this view,
+    /// even outside of it.
+    ///
+    /// The view that was receiving the events of the finger gets a
+    /// [`TouchCancel`](crate::event::Event::TouchCancel).
+    pub fn capture_touch(&self, touch: TouchId) {
+        self.add_update_message(UpdateMessage::CaptureTouch { id: *self, touch });
+    }
+
     /// Send a message to the application to open the Inspector for this Window
     pub fn inspect(&self) {
         self.add_update_message(UpdateMessage::Inspect);
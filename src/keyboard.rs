@@ -1,16 +1,167 @@
 use bitflags::bitflags;
+pub use floem_winit::event::ElementState;
 pub use floem_winit::keyboard::{
     Key, KeyCode, KeyLocation, ModifiersState, NamedKey, NativeKey, PhysicalKey,
 };
+use floem_winit::keyboard::{NativeKeyCode, SmolStr};
 #[cfg(not(target_arch = "wasm32"))]
 pub use floem_winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct KeyEvent {
-    pub key: floem_winit::event::KeyEvent,
+    pub key: KeyInfo,
     pub modifiers: Modifiers,
 }
 
+/// The key pressed or released in a [`KeyEvent`].
+///
+/// It has the fields of winit's key event, which only winit can create, so that tests can make
+/// up key events, for example with [`HeadlessWindow::key_down`](crate::headless::HeadlessWindow::key_down).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct KeyInfo {
+    /// The key's position on the keyboard, whatever the keyboard layout.
+    pub physical_key: PhysicalKey,
+    /// The key in the current keyboard layout, with the modifiers applied.
+    pub logical_key: Key,
+    /// The text the key produces. Only set when the key is pressed.
+    pub text: Option<SmolStr>,
+    pub location: KeyLocation,
+    pub state: ElementState,
+    /// Whether this press repeats because the key is held down.
+    pub repeat: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    text_with_all_modifiers: Option<SmolStr>,
+    #[cfg(not(target_arch = "wasm32"))]
+    key_without_modifiers: Key,
+}
+
+impl KeyInfo {
+    /// A press or release of `logical_key`, with the text and physical key it has on a US
+    /// keyboard layout.
+    pub fn new(logical_key: Key, state: ElementState) -> Self {
+        let text = if state.is_pressed() {
+            logical_key.to_text().map(SmolStr::new)
+        } else {
+            None
+        };
+        Self {
+            physical_key: us_physical_key(&logical_key),
+            #[cfg(not(target_arch = "wasm32"))]
+            text_with_all_modifiers: text.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            key_without_modifiers: match &logical_key {
+                Key::Character(ch) => Key::Character(ch.to_lowercase().into()),
+                key => key.clone(),
+            },
+            text,
+            logical_key,
+            location: KeyLocation::Standard,
+            state,
+            repeat: false,
+        }
+    }
+}
+
+impl From<floem_winit::event::KeyEvent> for KeyInfo {
+    fn from(event: floem_winit::event::KeyEvent) -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            text_with_all_modifiers: event.text_with_all_modifiers().map(SmolStr::new),
+            #[cfg(not(target_arch = "wasm32"))]
+            key_without_modifiers: event.key_without_modifiers(),
+            physical_key: event.physical_key,
+            logical_key: event.logical_key,
+            text: event.text,
+            location: event.location,
+            state: event.state,
+            repeat: event.repeat,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl KeyEventExtModifierSupplement for KeyInfo {
+    fn text_with_all_modifiers(&self) -> Option<&str> {
+        self.text_with_all_modifiers.as_deref()
+    }
+
+    fn key_without_modifiers(&self) -> Key {
+        self.key_without_modifiers.clone()
+    }
+}
+
+/// The physical key producing `key` on a US keyboard layout.
+fn us_physical_key(key: &Key) -> PhysicalKey {
+    let code = match key {
+        Key::Named(NamedKey::Enter) => KeyCode::Enter,
+        Key::Named(NamedKey::Tab) => KeyCode::Tab,
+        Key::Named(NamedKey::Space) => KeyCode::Space,
+        Key::Named(NamedKey::Backspace) => KeyCode::Backspace,
+        Key::Named(NamedKey::Delete) => KeyCode::Delete,
+        Key::Named(NamedKey::Escape) => KeyCode::Escape,
+        Key::Named(NamedKey::ArrowUp) => KeyCode::ArrowUp,
+        Key::Named(NamedKey::ArrowDown) => KeyCode::ArrowDown,
+        Key::Named(NamedKey::ArrowLeft) => KeyCode::ArrowLeft,
+        Key::Named(NamedKey::ArrowRight) => KeyCode::ArrowRight,
+        Key::Named(NamedKey::Home) => KeyCode::Home,
+        Key::Named(NamedKey::End) => KeyCode::End,
+        Key::Named(NamedKey::PageUp) => KeyCode::PageUp,
+        Key::Named(NamedKey::PageDown) => KeyCode::PageDown,
+        Key::Named(NamedKey::Shift) => KeyCode::ShiftLeft,
+        Key::Named(NamedKey::Control) => KeyCode::ControlLeft,
+        Key::Named(NamedKey::Alt) => KeyCode::AltLeft,
+        Key::Named(NamedKey::Meta) => KeyCode::SuperLeft,
+        Key::Character(ch) => match ch.to_lowercase().as_str() {
+            "a" => KeyCode::KeyA,
+            "b" => KeyCode::KeyB,
+            "c" => KeyCode::KeyC,
+            "d" => KeyCode::KeyD,
+            "e" => KeyCode::KeyE,
+            "f" => KeyCode::KeyF,
+            "g" => KeyCode::KeyG,
+            "h" => KeyCode::KeyH,
+            "i" => KeyCode::KeyI,
+            "j" => KeyCode::KeyJ,
+            "k" => KeyCode::KeyK,
+            "l" => KeyCode::KeyL,
+            "m" => KeyCode::KeyM,
+            "n" => KeyCode::KeyN,
+            "o" => KeyCode::KeyO,
+            "p" => KeyCode::KeyP,
+            "q" => KeyCode::KeyQ,
+            "r" => KeyCode::KeyR,
+            "s" => KeyCode::KeyS,
+            "t" => KeyCode::KeyT,
+            "u" => KeyCode::KeyU,
+            "v" => KeyCode::KeyV,
+            "w" => KeyCode::KeyW,
+            "x" => KeyCode::KeyX,
+            "y" => KeyCode::KeyY,
+            "z" => KeyCode::KeyZ,
+            "0" | ")" => KeyCode::Digit0,
+            "1" | "!" => KeyCode::Digit1,
+            "2" | "@" => KeyCode::Digit2,
+            "3" | "#" => KeyCode::Digit3,
+            "4" | "$" => KeyCode::Digit4,
+            "5" | "%" => KeyCode::Digit5,
+            "6" | "^" => KeyCode::Digit6,
+            "7" | "&" => KeyCode::Digit7,
+            "8" | "*" => KeyCode::Digit8,
+            "9" | "(" => KeyCode::Digit9,
+            "-" | "_" => KeyCode::Minus,
+            "=" | "+" => KeyCode::Equal,
+            "," | "<" => KeyCode::Comma,
+            "." | ">" => KeyCode::Period,
+            "/" | "?" => KeyCode::Slash,
+            ";" | ":" => KeyCode::Semicolon,
+            "'" | "\"" => KeyCode::Quote,
+            _ => return PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+        },
+        _ => return PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+    };
+    PhysicalKey::Code(code)
+}
+
 bitflags! {
     /// Represents the current state of the keyboard modifiers
     ///
@@ -183,13 +183,18 @@ mod app_handle;
 pub(crate) mod app_state;
 mod clipboard;
 pub mod context;
+pub mod drag;
 pub mod dropped_file;
 pub mod easing;
+#[cfg(feature = "engine")]
+pub mod engine;
 pub mod event;
 pub mod ext_event;
 pub mod file;
 #[cfg(any(feature = "rfd-async-std", feature = "rfd-tokio"))]
 pub mod file_action;
+pub mod gesture;
+pub mod headless;
 pub mod id;
 mod inspector;
 pub mod keyboard;
@@ -201,7 +206,10 @@ mod renderer;
 pub mod responsive;
 mod screen_layout;
 pub mod style;
-pub(crate) mod theme;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod theme;
+pub mod touch;
 pub mod unit;
 mod update;
 pub(crate) mod view;
@@ -218,16 +226,18 @@ pub use app::{launch, quit_app, AppEvent, Application};
 pub use app_state::AppState;
 pub use clipboard::{Clipboard, ClipboardError};
 pub use floem_reactive as reactive;
+pub use floem_renderer::gpu_resources::DeviceConfig;
 pub use floem_renderer::text;
 use floem_renderer::Renderer;
 pub use id::ViewId;
 pub use peniko;
 pub use peniko::kurbo;
+pub use renderer::{RendererBackend, RendererError, RendererInfo};
 pub use screen_layout::ScreenLayout;
 pub use taffy;
 pub use view::{recursively_layout_view, AnyView, IntoView, View};
 pub use window::{close_window, new_window};
-pub use window_handle::{CustomRenderCallback, EngineHandle, GpuHelper, WindowHandle};
+pub use window_handle::WindowHandle;
 pub use window_id::{Urgency, WindowIdExt};
 
 pub mod common;
@@ -55,16 +55,96 @@ impl PointerButton {
     }
 }
 
+/// The device a pointer event comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PointerType {
+    #[default]
+    Mouse,
+    /// A finger on a touchscreen. Fingers are delivered as [touch events](crate::touch), so
+    /// only pointer events created by the application have this type.
+    Touch,
+    /// A pen on a touchscreen or a tablet. Pens are only told apart from fingers on iOS,
+    /// where their touches report an altitude.
+    Pen,
+    /// The eraser end of a pen. No platform reports it yet, so only pointer events created by
+    /// the application have this type.
+    Eraser,
+}
+
+impl PointerType {
+    /// Whether the pointer is the tip or the eraser of a pen.
+    pub fn is_pen(self) -> bool {
+        matches!(self, PointerType::Pen | PointerType::Eraser)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PointerInputEvent {
     pub pos: Point,
     pub button: PointerButton,
     pub modifiers: Modifiers,
     pub count: u8,
+    pub pointer_type: PointerType,
+    /// The pressure of the pointer from `0.0` to `1.0`. Pointers that don't sense pressure,
+    /// such as a mouse, report `0.5` while a button is pressed and `0.0` otherwise.
+    pub pressure: f64,
+    /// The angle between a pen and the normal of the surface in degrees, from `0.0` for a pen
+    /// held upright to `90.0` for a pen lying flat. `None` if the pointer or the platform
+    /// doesn't report it.
+    pub tilt: Option<f64>,
+    /// The clockwise rotation of a pen around its own axis in degrees, from `0.0` to `360.0`.
+    /// `None` if the pointer or the platform doesn't report it, which no platform does yet.
+    pub twist: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct PointerMoveEvent {
     pub pos: Point,
     pub modifiers: Modifiers,
+    pub pointer_type: PointerType,
+    /// See [`PointerInputEvent::pressure`].
+    pub pressure: f64,
+    /// See [`PointerInputEvent::tilt`].
+    pub tilt: Option<f64>,
+    /// See [`PointerInputEvent::twist`].
+    pub twist: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+
+    use super::PointerType;
+    use crate::{
+        event::{Event, EventListener},
+        headless::test_window,
+        views::{empty, Decorators},
+    };
+
+    #[test]
+    fn pen_strokes_carry_pressure_and_tilt() {
+        let strokes = create_rw_signal(Vec::new());
+        let mut window = test_window((100.0, 100.0), move || {
+            empty()
+                .on_event_stop(EventListener::PointerMove, move |event| {
+                    if let Event::PointerMove(event) = event {
+                        if event.pointer_type == PointerType::Pen {
+                            let point = (event.pressure, event.tilt, event.twist);
+                            strokes.update(|s| s.push(point));
+                        }
+                    }
+                })
+                .style(|s| s.size_full())
+        });
+
+        window.pointer_move((10.0, 10.0));
+        window.pen_down((20.0, 20.0), 0.2, 30.0);
+        window.pen_move((30.0, 20.0), 0.4, 30.0);
+        window.pen_move((40.0, 20.0), 0.9, 45.0);
+        window.pen_up((40.0, 20.0), 45.0);
+        assert_eq!(
+            strokes.get_untracked(),
+            vec![(0.4, Some(30.0), None), (0.9, Some(45.0), None)]
+        );
+    }
 }
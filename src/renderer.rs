@@ -50,13 +50,74 @@
 use std::sync::Arc;
 
 use crate::text::TextLayout;
-use floem_renderer::gpu_resources::{self, GpuResources};
+use floem_renderer::config::{BackendPreference, FrameFormat, RendererConfig, RendererConfigError};
+use floem_renderer::gpu_resources::{self, GpuResourceError, GpuResources};
 use floem_renderer::Img;
 use floem_tiny_skia_renderer::TinySkiaRenderer;
 use floem_vger_renderer::VgerRenderer;
 use image::DynamicImage;
 use peniko::kurbo::{self, Affine, Rect, Shape, Size};
-use peniko::BrushRef;
+use peniko::{BlendMode, BrushRef};
+
+/// The renderer a window is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RendererBackend {
+    /// vger, on the GPU.
+    Vger,
+    /// tiny-skia, on the CPU.
+    TinySkia,
+}
+
+/// The renderer a window is drawn with and the GPU adapter it uses, see
+/// [`WindowIdExt::renderer_info`](crate::WindowIdExt::renderer_info).
+#[derive(Debug, Clone)]
+pub struct RendererInfo {
+    pub backend: RendererBackend,
+    /// The name, type and graphics API of the adapter, if the renderer uses the GPU.
+    pub adapter: Option<wgpu::AdapterInfo>,
+}
+
+/// Possible errors when creating the renderer of a window.
+#[derive(Debug)]
+pub enum RendererError {
+    /// The [`RendererConfig`] isn't supported by the surface of the window.
+    Config(RendererConfigError),
+    /// The GPU adapter doesn't support these features required by the
+    /// [`DeviceConfig`](crate::DeviceConfig) of the application.
+    MissingFeatures(wgpu::Features),
+    /// None of the renderers allowed by the [`BackendPreference`] could be created.
+    /// Each error is `None` if that renderer wasn't tried.
+    Unavailable {
+        vger: Option<Box<dyn std::error::Error>>,
+        tiny_skia: Option<Box<dyn std::error::Error>>,
+    },
+}
+
+impl std::fmt::Display for RendererError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RendererError::Config(err) => write!(f, "Invalid renderer config: {err}"),
+            RendererError::MissingFeatures(features) => {
+                write!(
+                    f,
+                    "The GPU adapter doesn't support the features {features:?}"
+                )
+            }
+            RendererError::Unavailable { vger, tiny_skia } => {
+                write!(f, "No renderer could be created")?;
+                if let Some(err) = vger {
+                    write!(f, "\nFailed to create VgerRenderer: {err}")?;
+                }
+                if let Some(err) = tiny_skia {
+                    write!(f, "\nFailed to create TinySkiaRenderer: {err}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RendererError {}
 
 #[allow(clippy::large_enum_variant)]
 pub enum Renderer<W> {
@@ -73,11 +134,12 @@ pub enum Renderer<W> {
 impl<W: wgpu::WindowHandle> Renderer<W> {
     pub fn new(
         window: W,
-        gpu_resources: std::sync::Arc<GpuResources>,
+        gpu_resources: Result<Arc<GpuResources>, GpuResourceError>,
         scale: f64,
         size: Size,
         font_embolden: f32,
-    ) -> Self
+        renderer_config: RendererConfig,
+    ) -> Result<Self, RendererError>
     where
         W: Clone + 'static,
     {
@@ -87,39 +149,76 @@ impl<W: wgpu::WindowHandle> Renderer<W> {
             .ok()
             .map(|val| val.as_str() == "1")
             .unwrap_or(false);
+        let backend = if force_tiny_skia {
+            BackendPreference::TinySkia
+        } else {
+            renderer_config.backend
+        };
 
-        let gpu_resources = Arc::clone(&gpu_resources);
-
-        let vger_err = if !force_tiny_skia {
-            match VgerRenderer::new(
-                gpu_resources,
-                size.width as u32,
-                size.height as u32,
-                scale,
-                font_embolden,
-            ) {
-                Ok(vger) => return Self::Vger(vger),
-                Err(err) => Some(err),
-            }
+        let vger_err = if backend != BackendPreference::TinySkia {
+            let err: Box<dyn std::error::Error> = match gpu_resources {
+                Ok(gpu_resources) => match VgerRenderer::new(
+                    gpu_resources,
+                    size.width as u32,
+                    size.height as u32,
+                    scale,
+                    font_embolden,
+                    renderer_config,
+                ) {
+                    Ok(vger) => return Ok(Self::Vger(vger)),
+                    // A configuration the surface doesn't support is a mistake of the
+                    // application, so it isn't hidden by falling back to the CPU renderer.
+                    Err(err) => match err.downcast::<RendererConfigError>() {
+                        Ok(err) => return Err(RendererError::Config(err)),
+                        Err(err) => err.into(),
+                    },
+                },
+                // Like an unsupported configuration, features the application requires aren't
+                // hidden by falling back to the CPU renderer, which has no device to use them.
+                Err(GpuResourceError::MissingFeatures(features)) => {
+                    return Err(RendererError::MissingFeatures(features))
+                }
+                Err(err) => Box::new(err),
+            };
+            Some(err)
         } else {
             None
         };
 
-        let tiny_skia_err = match TinySkiaRenderer::new(
+        if backend == BackendPreference::VgerOnly {
+            return Err(RendererError::Unavailable {
+                vger: vger_err,
+                tiny_skia: None,
+            });
+        }
+
+        match TinySkiaRenderer::new(
             window,
             size.width as u32,
             size.height as u32,
             scale,
             font_embolden,
         ) {
-            Ok(tiny_skia) => return Self::TinySkia(tiny_skia),
-            Err(err) => err,
-        };
+            Ok(tiny_skia) => Ok(Self::TinySkia(tiny_skia)),
+            Err(err) => Err(RendererError::Unavailable {
+                vger: vger_err,
+                tiny_skia: Some(err.into()),
+            }),
+        }
+    }
 
-        if !force_tiny_skia {
-            panic!("Failed to create VgerRenderer: {}\nFailed to create TinySkiaRenderer: {tiny_skia_err}", vger_err.unwrap());
-        } else {
-            panic!("Failed to create TinySkiaRenderer: {tiny_skia_err}");
+    /// Creates a CPU renderer that paints into an offscreen pixmap instead of a window surface.
+    pub fn new_headless(scale: f64, size: Size, font_embolden: f32) -> Self {
+        let size = Size::new(size.width.max(1.0), size.height.max(1.0));
+
+        match TinySkiaRenderer::new_headless(
+            size.width as u32,
+            size.height as u32,
+            scale,
+            font_embolden,
+        ) {
+            Ok(tiny_skia) => Self::TinySkia(tiny_skia),
+            Err(err) => panic!("Failed to create headless TinySkiaRenderer: {err}"),
         }
     }
 
@@ -159,6 +258,54 @@ impl<W: wgpu::WindowHandle> Renderer<W> {
             Renderer::Uninitialized { size, .. } => *size,
         }
     }
+
+    /// The renderer in use and its adapter, if it is initialized.
+    pub fn info(&self) -> Option<RendererInfo> {
+        match self {
+            Renderer::Vger(r) => Some(RendererInfo {
+                backend: RendererBackend::Vger,
+                adapter: Some(r.gpu_resources().adapter.get_info()),
+            }),
+            Renderer::TinySkia(_) => Some(RendererInfo {
+                backend: RendererBackend::TinySkia,
+                adapter: None,
+            }),
+            Renderer::Uninitialized { .. } => None,
+        }
+    }
+
+    /// The format of the frames the renderer draws, if it renders with wgpu.
+    pub fn frame_format(&self) -> Option<FrameFormat> {
+        match self {
+            Renderer::Vger(r) => Some(r.frame_format()),
+            Renderer::TinySkia(_) | Renderer::Uninitialized { .. } => None,
+        }
+    }
+
+    /// The present mode of the surface, if the renderer renders with wgpu.
+    pub fn present_mode(&self) -> Option<wgpu::PresentMode> {
+        match self {
+            Renderer::Vger(r) => Some(r.present_mode()),
+            Renderer::TinySkia(_) | Renderer::Uninitialized { .. } => None,
+        }
+    }
+
+    /// The depth/stencil texture of the frame, if the renderer renders with wgpu and was
+    /// configured with one.
+    pub fn depth_stencil_view(&self) -> Option<&Arc<wgpu::TextureView>> {
+        match self {
+            Renderer::Vger(r) => r.depth_stencil_view.as_ref(),
+            Renderer::TinySkia(_) | Renderer::Uninitialized { .. } => None,
+        }
+    }
+
+    /// The GPU resources of the renderer, if it renders with wgpu.
+    pub fn gpu_resources(&self) -> Option<&Arc<GpuResources>> {
+        match self {
+            Renderer::Vger(r) => Some(r.gpu_resources()),
+            Renderer::TinySkia(_) | Renderer::Uninitialized { .. } => None,
+        }
+    }
 }
 
 impl<W: wgpu::WindowHandle> floem_renderer::Renderer for Renderer<W> {
@@ -198,6 +345,30 @@ impl<W: wgpu::WindowHandle> floem_renderer::Renderer for Renderer<W> {
         }
     }
 
+    fn push_layer(&mut self, alpha: f32, blend_mode: impl Into<BlendMode>, clip: &impl Shape) {
+        match self {
+            Renderer::Vger(v) => {
+                v.push_layer(alpha, blend_mode, clip);
+            }
+            Renderer::TinySkia(v) => {
+                v.push_layer(alpha, blend_mode, clip);
+            }
+            Renderer::Uninitialized { .. } => {}
+        }
+    }
+
+    fn pop_layer(&mut self) {
+        match self {
+            Renderer::Vger(v) => {
+                v.pop_layer();
+            }
+            Renderer::TinySkia(v) => {
+                v.pop_layer();
+            }
+            Renderer::Uninitialized { .. } => {}
+        }
+    }
+
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
         match self {
             Renderer::Vger(v) => {
@@ -227,6 +398,24 @@ impl<W: wgpu::WindowHandle> floem_renderer::Renderer for Renderer<W> {
         }
     }
 
+    fn fill_img(
+        &mut self,
+        path: &impl peniko::kurbo::Shape,
+        img: &peniko::Image,
+        rect: Rect,
+        repeat: bool,
+    ) {
+        match self {
+            Renderer::Vger(v) => {
+                v.fill_img(path, img, rect, repeat);
+            }
+            Renderer::TinySkia(v) => {
+                v.fill_img(path, img, rect, repeat);
+            }
+            Renderer::Uninitialized { .. } => {}
+        }
+    }
+
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<kurbo::Point>) {
         match self {
             Renderer::Vger(v) => {
@@ -252,6 +441,18 @@ impl<W: wgpu::WindowHandle> floem_renderer::Renderer for Renderer<W> {
         }
     }
 
+    fn draw_texture(&mut self, texture: &Arc<wgpu::TextureView>, rect: Rect) {
+        match self {
+            Renderer::Vger(v) => {
+                v.draw_texture(texture, rect);
+            }
+            Renderer::TinySkia(v) => {
+                v.draw_texture(texture, rect);
+            }
+            Renderer::Uninitialized { .. } => {}
+        }
+    }
+
     fn draw_svg<'b>(
         &mut self,
         svg: floem_renderer::Svg<'b>,
@@ -314,3 +515,49 @@ impl<W: wgpu::WindowHandle> floem_renderer::Renderer for Renderer<W> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use peniko::kurbo::Size;
+    use raw_window_handle::{
+        DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+    };
+
+    use super::{Renderer, RendererError};
+    use floem_renderer::{config::RendererConfig, gpu_resources::GpuResourceError};
+
+    /// A window without a surface, which no renderer can draw to.
+    #[derive(Clone)]
+    struct NoWindow;
+
+    impl HasWindowHandle for NoWindow {
+        fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+            Err(HandleError::NotSupported)
+        }
+    }
+
+    impl HasDisplayHandle for NoWindow {
+        fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+            Err(HandleError::NotSupported)
+        }
+    }
+
+    #[test]
+    fn missing_features_do_not_fall_back_to_tiny_skia() {
+        let result = Renderer::new(
+            NoWindow,
+            Err(GpuResourceError::MissingFeatures(
+                wgpu::Features::TEXTURE_FORMAT_NV12,
+            )),
+            1.0,
+            Size::new(10.0, 10.0),
+            0.0,
+            RendererConfig::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(RendererError::MissingFeatures(features))
+                if features == wgpu::Features::TEXTURE_FORMAT_NV12
+        ));
+    }
+}
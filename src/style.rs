@@ -3,7 +3,7 @@
 use floem_reactive::create_updater;
 use floem_renderer::text::{LineHeightValue, Weight};
 use im_rc::hashmap::Entry;
-use peniko::kurbo::Point;
+use peniko::kurbo::{self, Point};
 use peniko::{Brush, Color, ColorStop, ColorStops, Gradient, GradientKind};
 use rustc_hash::FxHasher;
 use std::any::{type_name, Any};
@@ -109,6 +109,8 @@ impl StylePropValue for Weight {
 }
 impl StylePropValue for crate::text::Style {}
 impl StylePropValue for TextOverflow {}
+impl StylePropValue for BackgroundRepeat {}
+impl StylePropValue for BackgroundSize {}
 impl StylePropValue for LineHeightValue {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
         match (self, other) {
@@ -1168,6 +1170,12 @@ impl Style {
                 self.apply_mut(map);
             }
         }
+        if interact_state.is_drag_over {
+            if let Some(mut map) = self.get_nested_map(StyleSelector::DragOver.to_key()) {
+                map.apply_interact_state(interact_state, screen_size_bp);
+                self.apply_mut(map);
+            }
+        }
 
         let focused_keyboard =
             interact_state.using_keyboard_navigation && interact_state.is_focused;
@@ -1314,6 +1322,7 @@ pub enum StyleSelector {
     Active,
     Dragging,
     Selected,
+    DragOver,
 }
 
 style_key_selector!(hover, StyleSelectors::new().set(StyleSelector::Hover, true));
@@ -1338,6 +1347,10 @@ style_key_selector!(
     selected,
     StyleSelectors::new().set(StyleSelector::Selected, true)
 );
+style_key_selector!(
+    drag_over,
+    StyleSelectors::new().set(StyleSelector::DragOver, true)
+);
 
 impl StyleSelector {
     fn to_key(self) -> StyleKey {
@@ -1349,6 +1362,7 @@ impl StyleSelector {
             StyleSelector::Active => active(),
             StyleSelector::Dragging => dragging(),
             StyleSelector::Selected => selected(),
+            StyleSelector::DragOver => drag_over(),
         }
     }
 }
@@ -1399,6 +1413,44 @@ pub enum TextOverflow {
     Ellipsis,
 }
 
+/// Whether an image [`Background`] is drawn once or tiled over the view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundRepeat {
+    Repeat,
+    NoRepeat,
+}
+
+/// How an image [`Background`] is sized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundSize {
+    /// The image is drawn at its own size, one pixel per logical pixel, from the top left corner.
+    Auto,
+    /// The image is scaled to cover the whole view, keeping its aspect ratio, and centered.
+    Cover,
+    /// The image is scaled to fit inside the view, keeping its aspect ratio, and centered.
+    Contain,
+}
+
+impl BackgroundSize {
+    /// Returns where one copy of an image of `image` size is drawn in a view of `view` size.
+    pub(crate) fn image_rect(self, image: kurbo::Size, view: kurbo::Size) -> kurbo::Rect {
+        if image.is_empty() {
+            return kurbo::Rect::ZERO;
+        }
+        let scale = match self {
+            BackgroundSize::Auto => return image.to_rect(),
+            BackgroundSize::Cover => (view.width / image.width).max(view.height / image.height),
+            BackgroundSize::Contain => (view.width / image.width).min(view.height / image.height),
+        };
+        let size = image * scale;
+        let origin = Point::new(
+            (view.width - size.width) / 2.0,
+            (view.height - size.height) / 2.0,
+        );
+        kurbo::Rect::from_origin_size(origin, size)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CursorStyle {
     Default,
@@ -1587,7 +1639,10 @@ define_builtin_props!(
     Cursor cursor nocb: Option<CursorStyle> {} = None,
     TextColor color nocb: Option<Color> { inherited } = None,
     Background background nocb: Option<Brush> {} = None,
+    BackgroundRepeatProp background_repeat: BackgroundRepeat {} = BackgroundRepeat::Repeat,
+    BackgroundSizeProp background_size: BackgroundSize {} = BackgroundSize::Auto,
     Foreground foreground nocb: Option<Brush> {} = None,
+    Opacity opacity: f32 {} = 1.0,
     BoxShadowProp box_shadow nocb: Option<BoxShadow> {} = None,
     FontSize font_size nocb: Option<f32> { inherited } = None,
     FontFamily font_family nocb: Option<String> { inherited } = None,
@@ -1605,7 +1660,12 @@ define_builtin_props!(
     ScaleY scale_y: PxPct {} = PxPct::Pct(100.),
     TranslateX translate_x: PxPct {} = PxPct::Px(0.),
     TranslateY translate_y: PxPct {} = PxPct::Px(0.),
-    // Rotation rotate: Px {} = Px(0.),
+    // Rotation and skew angles are in radians.
+    Rotation rotate: f64 {} = 0.,
+    SkewX skew_x: f64 {} = 0.,
+    SkewY skew_y: f64 {} = 0.,
+    TransformOriginX transform_origin_x: PxPct {} = PxPct::Pct(50.),
+    TransformOriginY transform_origin_y: PxPct {} = PxPct::Pct(50.),
 );
 
 prop_extractor! {
@@ -1661,10 +1721,45 @@ prop_extractor! {
         pub translate_x: TranslateX,
         pub translate_y: TranslateY,
 
-        // pub rotation: Rotation,
+        pub rotation: Rotation,
+        pub skew_x: SkewX,
+        pub skew_y: SkewY,
+        pub transform_origin_x: TransformOriginX,
+        pub transform_origin_y: TransformOriginY,
     }
 }
 impl LayoutProps {
+    /// The transform applied by the translate, scale, rotate and skew props to a view of `size`,
+    /// in the coordinates of the view's layout location.
+    pub(crate) fn transform(&self, size: kurbo::Size) -> kurbo::Affine {
+        let resolve = |value: PxPct, length: f64| match value {
+            PxPct::Px(px) => px,
+            PxPct::Pct(pct) => length * (pct / 100.),
+        };
+        let translate = kurbo::Vec2::new(
+            resolve(self.translate_x(), size.width),
+            resolve(self.translate_y(), size.height),
+        );
+        let scale_x = match self.scale_x() {
+            PxPct::Px(px) => px / size.width,
+            PxPct::Pct(pct) => pct / 100.,
+        };
+        let scale_y = match self.scale_y() {
+            PxPct::Px(px) => px / size.height,
+            PxPct::Pct(pct) => pct / 100.,
+        };
+        let origin = kurbo::Vec2::new(
+            resolve(self.transform_origin_x(), size.width),
+            resolve(self.transform_origin_y(), size.height),
+        );
+
+        kurbo::Affine::translate(translate + origin)
+            * kurbo::Affine::rotate(self.rotation())
+            * kurbo::Affine::skew(self.skew_x().tan(), self.skew_y().tan())
+            * kurbo::Affine::scale_non_uniform(scale_x, scale_y)
+            * kurbo::Affine::translate(-origin)
+    }
+
     pub fn to_style(&self) -> Style {
         Style::new()
             .width(self.width())
@@ -1769,6 +1864,11 @@ impl Style {
         self.selector(StyleSelector::Active, style)
     }
 
+    /// The style to apply while a payload this view accepts as a drop target is dragged over it.
+    pub fn drag_over(self, style: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(StyleSelector::DragOver, style)
+    }
+
     pub fn responsive(mut self, size: ScreenSize, style: impl FnOnce(Style) -> Style) -> Self {
         let over = style(Style::default());
         for breakpoint in size.breakpoints() {
@@ -2061,6 +2161,10 @@ impl Style {
         self.set_style_value(TextColor, color.into().map(Some))
     }
 
+    /// Sets the background to a color, a gradient or an image.
+    ///
+    /// Image backgrounds are placed according to [`BackgroundSizeProp`] and tiled according to
+    /// [`BackgroundRepeatProp`].
     pub fn background(self, color: impl Into<Brush>) -> Self {
         let brush = StyleValue::Val(Some(color.into()));
         self.set_style_value(Background, brush)
@@ -2196,6 +2300,17 @@ impl Style {
         self.scale_x(val).scale_y(val)
     }
 
+    /// Skews the view along both axes. The angles are in radians.
+    pub fn skew(self, x: f64, y: f64) -> Self {
+        self.skew_x(x).skew_y(y)
+    }
+
+    /// Sets the point that scale, rotation and skew are relative to.
+    /// The default is the center of the view.
+    pub fn transform_origin(self, x: impl Into<PxPct>, y: impl Into<PxPct>) -> Self {
+        self.transform_origin_x(x).transform_origin_y(y)
+    }
+
     /// Allow the application of a function if the option exists.
     /// This is useful for chaining together a bunch of optional style changes.
     /// ```rust
@@ -2350,10 +2465,17 @@ pub trait CustomStylable<S: Default + Into<Style> + 'static>:
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+    use peniko::{Blob, Color, Format, Image};
+
     use super::{Style, StyleValue};
     use crate::{
+        headless::{render_view, test_window},
         style::{PaddingBottom, PaddingLeft},
         unit::PxPct,
+        views::{container, empty, Decorators},
     };
 
     #[test]
@@ -2429,4 +2551,63 @@ mod tests {
             StyleValue::Val(PxPct::Px(100.0))
         );
     }
+
+    #[test]
+    fn image_background_repeats() {
+        let pixels = vec![255, 0, 0, 255, 0, 0, 255, 255];
+        let image = Image::new(Blob::new(Arc::new(pixels)), Format::Rgba8, 2, 1);
+        let image = render_view(
+            move || empty().style(move |s| s.size_full().background(image.clone())),
+            (4.0, 2.0),
+            1.0,
+        )
+        .to_rgba8();
+
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(2, 1).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(3, 1).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn rotated_view_is_hit_tested_in_its_own_coordinates() {
+        let clicks = create_rw_signal(0);
+        let mut window = test_window((100.0, 100.0), move || {
+            empty()
+                .on_click_stop(move |_| clicks.update(|c| *c += 1))
+                .style(|s| s.size(80.0, 20.0).rotate(std::f64::consts::FRAC_PI_2))
+        });
+
+        // Inside the layout rect, but outside the rotated view.
+        window.click((70.0, 10.0));
+        assert_eq!(clicks.get_untracked(), 0);
+
+        // Outside the layout rect, but inside the rotated view.
+        window.click((40.0, 40.0));
+        assert_eq!(clicks.get_untracked(), 1);
+    }
+
+    #[test]
+    fn opacity_composites_subtree() {
+        let image = render_view(
+            || {
+                container(empty().style(|s| s.size_full().background(Color::RED))).style(|s| {
+                    s.size_full()
+                        .padding(5.0)
+                        .background(Color::BLUE)
+                        .opacity(0.5)
+                })
+            },
+            (20.0, 20.0),
+            1.0,
+        )
+        .to_rgba8();
+
+        // Blue at half opacity over the light theme's (248, 248, 248) background.
+        let [r, g, b, _] = image.get_pixel(1, 1).0;
+        assert!(r.abs_diff(124) <= 1 && g.abs_diff(124) <= 1 && b.abs_diff(252) <= 1);
+        // The red child covers the blue parent inside the layer, so no blue shows through.
+        let [r, g, b, _] = image.get_pixel(10, 10).0;
+        assert!(r.abs_diff(252) <= 1 && g.abs_diff(124) <= 1 && b.abs_diff(124) <= 1);
+    }
 }
@@ -0,0 +1,301 @@
+//! Golden-image tests for views.
+//!
+//! Views are rendered with a [`HeadlessWindow`], so these tests run on the CPU and need no
+//! display server. A rendered frame is compared with a stored PNG; if more pixels than allowed
+//! differ by more than the per-channel tolerance, the test fails and a diff image is written
+//! next to the golden file.
+//!
+//! Set the `FLOEM_UPDATE_GOLDENS=1` environment variable to write the rendered frames as the
+//! new golden files instead of comparing them.
+//!
+//! ```rust,no_run
+//! use floem::testing::assert_golden;
+//! use floem::views::{text, Decorators};
+//!
+//! assert_golden("tests/goldens/label.png", || text("Hello").style(|s| s.padding(10.0)), (200.0, 50.0));
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use peniko::kurbo::Size;
+
+use crate::{
+    headless::{HeadlessConfig, HeadlessWindow},
+    view::IntoView,
+};
+
+/// Setting this environment variable to `1` overwrites golden files with the rendered frames.
+pub const UPDATE_GOLDENS_ENV: &str = "FLOEM_UPDATE_GOLDENS";
+
+/// How strictly a rendered frame has to match its golden image.
+#[derive(Debug, Clone, Copy)]
+pub struct GoldenOptions {
+    /// The largest difference allowed in any color channel before a pixel counts as mismatched.
+    pub tolerance: u8,
+    /// The number of mismatched pixels allowed before the comparison fails.
+    pub max_mismatched_pixels: usize,
+}
+
+impl Default for GoldenOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 2,
+            max_mismatched_pixels: 0,
+        }
+    }
+}
+
+impl GoldenOptions {
+    /// Sets the per-channel tolerance.
+    ///
+    /// The default is `2`.
+    #[inline]
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the number of mismatched pixels allowed.
+    ///
+    /// The default is `0`.
+    #[inline]
+    pub fn max_mismatched_pixels(mut self, max_mismatched_pixels: usize) -> Self {
+        self.max_mismatched_pixels = max_mismatched_pixels;
+        self
+    }
+}
+
+/// Possible errors when checking a frame against a golden image.
+#[derive(Debug)]
+pub enum GoldenError {
+    /// There is no golden image at this path. The rendered frame was written next to it.
+    Missing {
+        golden: PathBuf,
+        actual: PathBuf,
+    },
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+        actual_path: PathBuf,
+    },
+    Mismatch {
+        mismatched_pixels: usize,
+        diff_path: PathBuf,
+        actual_path: PathBuf,
+    },
+    Image(image::ImageError),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenError::Missing { golden, actual } => write!(
+                f,
+                "Golden image {} does not exist, the rendered frame was written to {}. Run with {UPDATE_GOLDENS_ENV}=1 to accept it",
+                golden.display(),
+                actual.display()
+            ),
+            GoldenError::SizeMismatch {
+                expected,
+                actual,
+                actual_path,
+            } => write!(
+                f,
+                "Golden image is {}x{} but the rendered frame is {}x{}, see {}",
+                expected.0,
+                expected.1,
+                actual.0,
+                actual.1,
+                actual_path.display()
+            ),
+            GoldenError::Mismatch {
+                mismatched_pixels,
+                diff_path,
+                actual_path,
+            } => write!(
+                f,
+                "{mismatched_pixels} pixels differ from the golden image, see {} and {}",
+                diff_path.display(),
+                actual_path.display()
+            ),
+            GoldenError::Image(err) => write!(f, "Image error: {}", err),
+            GoldenError::Io(err) => write!(f, "IO error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+impl From<image::ImageError> for GoldenError {
+    fn from(err: image::ImageError) -> Self {
+        GoldenError::Image(err)
+    }
+}
+
+impl From<std::io::Error> for GoldenError {
+    fn from(err: std::io::Error) -> Self {
+        GoldenError::Io(err)
+    }
+}
+
+/// The result of comparing two images of the same size.
+pub struct ImageDiff {
+    pub mismatched_pixels: usize,
+    /// The actual image faded out, with mismatched pixels drawn in red.
+    pub image: RgbaImage,
+}
+
+/// Compares two images of the same size pixel by pixel.
+pub fn diff_images(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> ImageDiff {
+    let mut mismatched_pixels = 0;
+    let image = RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let a = actual.get_pixel(x, y);
+        let e = expected.get_pixel(x, y);
+        let matches =
+            a.0.iter()
+                .zip(e.0.iter())
+                .all(|(a, e)| a.abs_diff(*e) <= tolerance);
+        if matches {
+            let luma = (a.0[0] as u32 * 3 + a.0[1] as u32 * 6 + a.0[2] as u32) / 10;
+            let faded = 191 + (luma / 4) as u8;
+            Rgba([faded, faded, faded, 255])
+        } else {
+            mismatched_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        }
+    });
+    ImageDiff {
+        mismatched_pixels,
+        image,
+    }
+}
+
+fn sibling_path(golden: &Path, suffix: &str) -> PathBuf {
+    let stem = golden
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    golden.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+fn should_update_goldens() -> bool {
+    std::env::var(UPDATE_GOLDENS_ENV)
+        .ok()
+        .map(|val| val.as_str() == "1")
+        .unwrap_or(false)
+}
+
+/// Checks a rendered frame against the golden PNG at `golden`.
+///
+/// On failure the frame is written to `<name>.actual.png` and, if the sizes match,
+/// a diff to `<name>.diff.png`. With `FLOEM_UPDATE_GOLDENS=1` the golden is overwritten instead.
+pub fn check_golden(
+    image: &DynamicImage,
+    golden: impl AsRef<Path>,
+    options: GoldenOptions,
+) -> Result<(), GoldenError> {
+    let golden = golden.as_ref();
+    let actual = image.to_rgba8();
+    let actual_path = sibling_path(golden, "actual");
+    let diff_path = sibling_path(golden, "diff");
+
+    if should_update_goldens() {
+        if let Some(parent) = golden.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        actual.save(golden)?;
+        let _ = std::fs::remove_file(&actual_path);
+        let _ = std::fs::remove_file(&diff_path);
+        return Ok(());
+    }
+
+    if !golden.exists() {
+        if let Some(parent) = golden.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        actual.save(&actual_path)?;
+        return Err(GoldenError::Missing {
+            golden: golden.to_path_buf(),
+            actual: actual_path,
+        });
+    }
+
+    let expected = image::open(golden)?.to_rgba8();
+    if expected.dimensions() != actual.dimensions() {
+        actual.save(&actual_path)?;
+        return Err(GoldenError::SizeMismatch {
+            expected: expected.dimensions(),
+            actual: actual.dimensions(),
+            actual_path,
+        });
+    }
+
+    let diff = diff_images(&expected, &actual, options.tolerance);
+    if diff.mismatched_pixels > options.max_mismatched_pixels {
+        actual.save(&actual_path)?;
+        diff.image.save(&diff_path)?;
+        return Err(GoldenError::Mismatch {
+            mismatched_pixels: diff.mismatched_pixels,
+            diff_path,
+            actual_path,
+        });
+    }
+
+    let _ = std::fs::remove_file(&actual_path);
+    let _ = std::fs::remove_file(&diff_path);
+    Ok(())
+}
+
+/// Renders a view at `size` with a scale factor of 1 and asserts that it matches the golden PNG.
+///
+/// # Panics
+///
+/// Panics with the [`GoldenError`] message if the frame doesn't match.
+#[track_caller]
+pub fn assert_golden<V: IntoView + 'static>(
+    golden: impl AsRef<Path>,
+    app_view: impl FnOnce() -> V + 'static,
+    size: impl Into<Size>,
+) {
+    let config = HeadlessConfig::default().size(size);
+    let mut window = HeadlessWindow::new(app_view, config);
+    assert_window_golden(&mut window, golden, GoldenOptions::default());
+}
+
+/// Renders the current frame of `window` and asserts that it matches the golden PNG.
+///
+/// # Panics
+///
+/// Panics with the [`GoldenError`] message if the frame doesn't match.
+#[track_caller]
+pub fn assert_window_golden(
+    window: &mut HeadlessWindow,
+    golden: impl AsRef<Path>,
+    options: GoldenOptions,
+) {
+    let image = window.render();
+    if let Err(err) = check_golden(&image, golden, options) {
+        panic!("{err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::diff_images;
+
+    #[test]
+    fn diff_respects_tolerance() {
+        let expected = RgbaImage::from_pixel(4, 4, Rgba([100, 100, 100, 255]));
+        let mut actual = expected.clone();
+        actual.put_pixel(0, 0, Rgba([102, 100, 100, 255]));
+        actual.put_pixel(1, 0, Rgba([110, 100, 100, 255]));
+
+        assert_eq!(diff_images(&expected, &actual, 2).mismatched_pixels, 1);
+        assert_eq!(diff_images(&expected, &actual, 10).mismatched_pixels, 0);
+        assert_eq!(diff_images(&expected, &actual, 0).mismatched_pixels, 2);
+    }
+}
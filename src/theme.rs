@@ -1,5 +1,29 @@
+//! Themes for Floem's built-in views.
+//!
+//! A [`Theme`] is a set of design tokens ([`Palette`], [`Spacing`], [`Radii`] and
+//! [`Typography`]) from which the styles of the built-in view classes
+//! (`ButtonClass`, `TextInputClass`, `SliderClass`, ...) are generated.
+//! Individual classes can be restyled on top of the generated styles with [`Theme::class`].
+//!
+//! A theme is set per window with [`WindowConfig::theme`](crate::window::WindowConfig::theme),
+//! and can be replaced at runtime with [`set_window_theme`](crate::action::set_window_theme).
+//!
+//! ```rust
+//! use floem::peniko::Color;
+//! use floem::theme::{Theme, WindowTheme};
+//! use floem::views::ButtonClass;
+//!
+//! let theme = Theme::light()
+//!     .palette(Theme::light().palette.accent(Color::rgb8(30, 120, 220)))
+//!     .class(ButtonClass, |s| s.font_bold());
+//!
+//! // Follow the OS between the built-in light and dark themes.
+//! let system = WindowTheme::system();
+//! ```
+
 use crate::{
-    style::{Background, CursorStyle, Foreground, Style, Transition},
+    common,
+    style::{Background, CursorStyle, Foreground, Style, StyleClass, Transition},
     unit::{DurationUnitExt, UnitExt},
     views::{
         dropdown::{self},
@@ -12,292 +36,695 @@ use crate::{
     },
 };
 use peniko::{Brush, Color};
-use std::rc::Rc;
 use taffy::style::AlignItems;
 
-pub(crate) struct Theme {
-    pub(crate) background: Color,
-    pub(crate) style: Rc<Style>,
+/// The colors of a [`Theme`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    /// The background of the window.
+    pub background: Color,
+    /// The background of inputs, checkboxes and radio buttons.
+    pub surface: Color,
+    /// The background of buttons and toggle buttons.
+    pub control: Color,
+    /// The background of tooltips and popups such as the dropdown list.
+    pub popup: Color,
+    /// The default text color.
+    pub text: Color,
+    /// The text color of buttons.
+    pub control_text: Color,
+    /// The text color of tooltips.
+    pub tooltip_text: Color,
+    /// The text color of placeholders in text inputs.
+    pub placeholder: Color,
+    /// The text color of disabled views.
+    pub muted_text: Color,
+    /// The text color of active buttons.
+    pub on_active: Color,
+    /// The border color of controls.
+    pub border: Color,
+    /// The border color of focused controls.
+    pub focus: Color,
+    /// The color of the focus outline.
+    pub focus_ring: Color,
+    pub hover: Color,
+    pub focus_hover: Color,
+    pub active: Color,
+    /// The hover background of text inputs.
+    pub input_hover: Color,
+    /// The hover background of focused text inputs.
+    pub input_focus_hover: Color,
+    /// The background of selected items in a focused list.
+    pub selected: Color,
+    pub selected_hover: Color,
+    /// The background of selected items in an unfocused list.
+    pub selected_unfocused: Color,
+    pub selected_unfocused_hover: Color,
+    /// The background of disabled controls.
+    pub disabled: Color,
+    /// The border color of disabled controls.
+    pub disabled_border: Color,
+    /// The color of filled-in parts, such as the slider's accent bar.
+    pub accent: Color,
+    /// The color of handles, such as the slider and toggle button handles.
+    pub handle: Color,
+    /// The background of primary buttons.
+    pub primary: Color,
+    /// The text color of primary and danger buttons.
+    pub on_primary: Color,
+    /// The background of danger buttons and the text color of error alerts.
+    pub danger: Color,
+    pub danger_surface: Color,
+    pub success: Color,
+    pub success_surface: Color,
+    pub info: Color,
+    pub info_surface: Color,
+    pub warning: Color,
+    pub warning_surface: Color,
+    pub scrollbar: Color,
+    pub scrollbar_hover: Color,
+    pub scrollbar_active: Color,
+    pub scrollbar_track_hover: Color,
+    pub shadow: Color,
 }
 
-pub(crate) fn default_theme() -> Theme {
-    let border = Color::rgb8(140, 140, 140);
-
-    let padding = 5.0;
-    let border_radius = 5.0;
-
-    let hover_bg_color = Color::rgba8(228, 237, 216, 160);
-    let focus_hover_bg_color = Color::rgb8(234, 230, 236);
-    let active_bg_color = Color::rgb8(160, 160, 160);
-
-    let selected_bg_color = Color::rgb8(213, 208, 216);
-    let selected_hover_bg_color = Color::rgb8(186, 180, 216);
-
-    let selected_unfocused_bg_color = Color::rgb8(212, 212, 212);
-    let selected_unfocused_hover_bg_color = Color::rgb8(197, 197, 197);
-
-    let light_hover_bg_color = Color::rgb8(250, 252, 248);
-    let light_focus_hover_bg_color = Color::rgb8(250, 249, 251);
-
-    let focus_applied_style = Style::new().border_color(Color::rgb8(114, 74, 140));
-
-    let focus_visible_applied_style = Style::new().outline(3.0);
-
-    let focus_style = Style::new()
-        .outline_color(Color::rgba8(213, 208, 216, 150))
-        .focus(|_| focus_applied_style.clone())
-        .focus_visible(|_| focus_visible_applied_style.clone());
-
-    let border_style = Style::new()
-        .disabled(|s| s.border_color(Color::rgb8(131, 145, 123).with_alpha_factor(0.3)))
-        .border(1.0)
-        .border_color(border)
-        .padding(padding)
-        .border_radius(border_radius)
-        .apply(focus_style.clone());
-
-    let button_style = Style::new()
-        .apply(LabelCustomStyle::new().selectable(false).style())
-        .class(LabelClass, |s| {
-            s.apply(LabelCustomStyle::new().selectable(false).style())
-        })
-        .background(Color::rgb8(240, 240, 240))
-        .disabled(|s| {
-            s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
-                .border_color(Color::rgb8(131, 145, 123).with_alpha_factor(0.3))
-                .color(Color::GRAY)
-        })
-        .active(|s| {
-            s.background(active_bg_color)
-                .color(Color::WHITE.with_alpha_factor(0.9))
-        })
-        .transition(Background, Transition::linear(400.millis()))
-        .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
-        .hover(|s| s.background(hover_bg_color))
-        .padding(padding)
-        .justify_center()
-        .items_center()
-        .apply(focus_style.clone())
-        .apply(border_style.clone())
-        .color(Color::rgb8(40, 40, 40));
-
-    let checkbox_style = Style::new()
-        .width(20.)
-        .height(20.)
-        .background(Color::WHITE)
-        .active(|s| s.background(active_bg_color))
-        .transition(Background, Transition::linear(400.millis()))
-        .hover(|s| s.background(hover_bg_color))
-        .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
-        .apply(border_style.clone())
-        .apply(focus_style.clone())
-        .disabled(|s| {
-            s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
-                .color(Color::GRAY)
-        });
+impl Palette {
+    /// The palette of [`Theme::light`].
+    pub fn light() -> Self {
+        Self {
+            background: Color::rgb8(248, 248, 248),
+            surface: Color::WHITE,
+            control: Color::rgb8(240, 240, 240),
+            popup: Color::WHITE_SMOKE,
+            text: Color::BLACK,
+            control_text: Color::rgb8(40, 40, 40),
+            tooltip_text: Color::rgb8(80, 80, 80),
+            placeholder: Color::rgba8(158, 158, 158, 30),
+            muted_text: Color::GRAY,
+            on_active: Color::WHITE.with_alpha_factor(0.9),
+            border: Color::rgb8(140, 140, 140),
+            focus: Color::rgb8(114, 74, 140),
+            focus_ring: Color::rgba8(213, 208, 216, 150),
+            hover: Color::rgba8(228, 237, 216, 160),
+            focus_hover: Color::rgb8(234, 230, 236),
+            active: Color::rgb8(160, 160, 160),
+            input_hover: Color::rgb8(250, 252, 248),
+            input_focus_hover: Color::rgb8(250, 249, 251),
+            selected: Color::rgb8(213, 208, 216),
+            selected_hover: Color::rgb8(186, 180, 216),
+            selected_unfocused: Color::rgb8(212, 212, 212),
+            selected_unfocused_hover: Color::rgb8(197, 197, 197),
+            disabled: Color::rgb8(180, 188, 175).with_alpha_factor(0.3),
+            disabled_border: Color::rgb8(131, 145, 123).with_alpha_factor(0.3),
+            accent: Color::GREEN,
+            handle: Color::DARK_GRAY,
+            primary: Color::rgb8(37, 99, 235),
+            on_primary: Color::WHITE,
+            danger: Color::rgb8(220, 38, 38),
+            danger_surface: Color::rgb8(254, 242, 242),
+            success: Color::rgb8(22, 163, 74),
+            success_surface: Color::rgb8(240, 253, 244),
+            info: Color::rgb8(37, 99, 235),
+            info_surface: Color::rgb8(167, 209, 242),
+            warning: Color::rgb8(202, 138, 4),
+            warning_surface: Color::rgb8(254, 252, 232),
+            scrollbar: Color::rgba8(166, 166, 166, 140),
+            scrollbar_hover: Color::rgb8(184, 184, 184),
+            scrollbar_active: Color::rgb8(166, 166, 166),
+            scrollbar_track_hover: Color::rgba8(166, 166, 166, 30),
+            shadow: Color::BLACK,
+        }
+    }
+
+    /// The palette of [`Theme::dark`].
+    pub fn dark() -> Self {
+        Self {
+            background: Color::rgb8(32, 32, 34),
+            surface: Color::rgb8(44, 44, 47),
+            control: Color::rgb8(56, 56, 60),
+            popup: Color::rgb8(48, 48, 52),
+            text: Color::rgb8(232, 232, 232),
+            control_text: Color::rgb8(220, 220, 220),
+            tooltip_text: Color::rgb8(200, 200, 200),
+            placeholder: Color::rgba8(158, 158, 158, 60),
+            muted_text: Color::GRAY,
+            on_active: Color::WHITE.with_alpha_factor(0.9),
+            border: Color::rgb8(96, 96, 100),
+            focus: Color::rgb8(160, 128, 200),
+            focus_ring: Color::rgba8(120, 96, 150, 150),
+            hover: Color::rgba8(84, 96, 72, 160),
+            focus_hover: Color::rgb8(72, 66, 82),
+            active: Color::rgb8(100, 100, 104),
+            input_hover: Color::rgb8(50, 52, 48),
+            input_focus_hover: Color::rgb8(50, 48, 54),
+            selected: Color::rgb8(74, 64, 92),
+            selected_hover: Color::rgb8(88, 76, 124),
+            selected_unfocused: Color::rgb8(64, 64, 64),
+            selected_unfocused_hover: Color::rgb8(76, 76, 76),
+            disabled: Color::rgb8(90, 96, 86).with_alpha_factor(0.3),
+            disabled_border: Color::rgb8(110, 120, 104).with_alpha_factor(0.3),
+            accent: Color::rgb8(86, 180, 96),
+            handle: Color::rgb8(176, 176, 176),
+            primary: Color::rgb8(59, 130, 246),
+            on_primary: Color::WHITE,
+            danger: Color::rgb8(239, 68, 68),
+            danger_surface: Color::rgb8(69, 26, 26),
+            success: Color::rgb8(74, 222, 128),
+            success_surface: Color::rgb8(20, 46, 30),
+            info: Color::rgb8(96, 165, 250),
+            info_surface: Color::rgb8(23, 37, 84),
+            warning: Color::rgb8(250, 204, 21),
+            warning_surface: Color::rgb8(66, 50, 10),
+            scrollbar: Color::rgba8(120, 120, 120, 140),
+            scrollbar_hover: Color::rgb8(140, 140, 140),
+            scrollbar_active: Color::rgb8(120, 120, 120),
+            scrollbar_track_hover: Color::rgba8(120, 120, 120, 30),
+            shadow: Color::BLACK,
+        }
+    }
+
+    /// Sets the accent color.
+    pub fn accent(mut self, accent: Color) -> Self {
+        self.accent = accent;
+        self
+    }
+
+    /// Sets the default text color.
+    pub fn text(mut self, text: Color) -> Self {
+        self.text = text;
+        self
+    }
+
+    /// Sets the window background color.
+    pub fn background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+/// The spacing of a [`Theme`], in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spacing {
+    /// Used for the padding of dropdowns and list items in them.
+    pub small: f64,
+    /// Used for the padding of controls and the gap in labeled checkboxes and radio buttons.
+    pub medium: f64,
+    /// Used for the distance between tooltips and the cursor. Cards are padded by twice this.
+    pub large: f64,
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Self {
+            small: 3.0,
+            medium: 5.0,
+            large: 10.0,
+        }
+    }
+}
+
+/// The corner radii of a [`Theme`], in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Radii {
+    /// Used for tooltips.
+    pub small: f64,
+    /// Used for controls.
+    pub medium: f64,
+    /// Used for the buttons and cards of the [`common`](crate::common) widgets.
+    pub large: f64,
+}
+
+impl Default for Radii {
+    fn default() -> Self {
+        Self {
+            small: 2.0,
+            medium: 5.0,
+            large: 15.0,
+        }
+    }
+}
+
+/// The text settings of a [`Theme`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    pub font_size: f32,
+    /// The default font family. If this is `None`, the renderer's default is used.
+    pub font_family: Option<String>,
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Self {
+            font_size: 12.0,
+            font_family: None,
+        }
+    }
+}
+
+/// A theme for Floem's built-in views.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub palette: Palette,
+    pub spacing: Spacing,
+    pub radii: Radii,
+    pub typography: Typography,
+    overrides: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+impl Theme {
+    /// Floem's default theme.
+    pub fn light() -> Self {
+        Self::with_palette(Palette::light())
+    }
+
+    /// A dark variant of the default theme.
+    pub fn dark() -> Self {
+        Self::with_palette(Palette::dark())
+    }
+
+    fn with_palette(palette: Palette) -> Self {
+        Self {
+            palette,
+            spacing: Spacing::default(),
+            radii: Radii::default(),
+            typography: Typography::default(),
+            overrides: Style::new(),
+        }
+    }
+
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn spacing(mut self, spacing: Spacing) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn radii(mut self, radii: Radii) -> Self {
+        self.radii = radii;
+        self
+    }
+
+    pub fn typography(mut self, typography: Typography) -> Self {
+        self.typography = typography;
+        self
+    }
+
+    /// Applies `style` to views with the class `C`, on top of the style generated from the tokens.
+    ///
+    /// Calling this several times for the same class merges the styles, with later calls
+    /// taking precedence.
+    pub fn class<C: StyleClass>(mut self, class: C, style: impl FnOnce(Style) -> Style) -> Self {
+        self.overrides = self.overrides.class(class, style);
+        self
+    }
+
+    /// Applies `style` to the root of the window, on top of the style generated from the tokens.
+    ///
+    /// Inherited properties such as the text color and font size set here apply to every view.
+    pub fn root_style(mut self, style: impl FnOnce(Style) -> Style) -> Self {
+        self.overrides = style(self.overrides);
+        self
+    }
+
+    /// Builds the stylesheet of this theme, which is applied to the root of a window.
+    pub fn to_style(&self) -> Style {
+        let palette = &self.palette;
+        let padding = self.spacing.medium;
+        let border_radius = self.radii.medium;
+        let font_size = self.typography.font_size;
+
+        let hover_bg_color = palette.hover;
+        let focus_hover_bg_color = palette.focus_hover;
+        let active_bg_color = palette.active;
+        let disabled_bg_color = palette.disabled;
 
-    let labeled_checkbox_style = Style::new()
-        .row_gap(padding)
-        .hover(|s| s.background(hover_bg_color))
-        .padding(padding)
-        .transition(Background, Transition::linear(400.millis()))
-        .border_radius(border_radius)
-        .active(|s| s.class(CheckboxClass, |s| s.background(active_bg_color)))
-        .focus(|s| {
-            s.class(CheckboxClass, |_| focus_applied_style.clone())
-                .hover(|s| s.background(focus_hover_bg_color))
-        })
-        .disabled(|s| {
-            s.color(Color::GRAY).class(CheckboxClass, |s| {
-                s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
-                    .color(Color::GRAY)
-                    .hover(|s| s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3)))
+        let focus_applied_style = Style::new().border_color(palette.focus);
+
+        let focus_visible_applied_style = Style::new().outline(3.0);
+
+        let focus_style = Style::new()
+            .outline_color(palette.focus_ring)
+            .focus(|_| focus_applied_style.clone())
+            .focus_visible(|_| focus_visible_applied_style.clone());
+
+        let border_style = Style::new()
+            .disabled(|s| s.border_color(palette.disabled_border))
+            .border(1.0)
+            .border_color(palette.border)
+            .padding(padding)
+            .border_radius(border_radius)
+            .apply(focus_style.clone());
+
+        let button_style = Style::new()
+            .apply(LabelCustomStyle::new().selectable(false).style())
+            .class(LabelClass, |s| {
+                s.apply(LabelCustomStyle::new().selectable(false).style())
             })
-        })
-        .apply(focus_style.clone());
-
-    let radio_button_style = Style::new()
-        .width(20.)
-        .height(20.)
-        .align_items(AlignItems::Center)
-        .justify_center()
-        .background(Color::WHITE)
-        .active(|s| s.background(active_bg_color))
-        .transition(Background, Transition::linear(400.millis()))
-        .hover(|s| s.background(hover_bg_color))
-        .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
-        .apply(border_style.clone())
-        .padding(0.)
-        .border_radius(100.0)
-        .apply(focus_style.clone())
-        .disabled(|s| {
-            s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
-                .color(Color::GRAY)
-        });
+            .background(palette.control)
+            .disabled(|s| {
+                s.background(disabled_bg_color)
+                    .border_color(palette.disabled_border)
+                    .color(palette.muted_text)
+            })
+            .active(|s| s.background(active_bg_color).color(palette.on_active))
+            .transition(Background, Transition::linear(400.millis()))
+            .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
+            .hover(|s| s.background(hover_bg_color))
+            .padding(padding)
+            .justify_center()
+            .items_center()
+            .apply(focus_style.clone())
+            .apply(border_style.clone())
+            .color(palette.control_text);
+
+        let checkbox_style = Style::new()
+            .width(20.)
+            .height(20.)
+            .background(palette.surface)
+            .active(|s| s.background(active_bg_color))
+            .transition(Background, Transition::linear(400.millis()))
+            .hover(|s| s.background(hover_bg_color))
+            .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
+            .apply(border_style.clone())
+            .apply(focus_style.clone())
+            .disabled(|s| s.background(disabled_bg_color).color(palette.muted_text));
+
+        let labeled_checkbox_style = Style::new()
+            .row_gap(padding)
+            .hover(|s| s.background(hover_bg_color))
+            .padding(padding)
+            .transition(Background, Transition::linear(400.millis()))
+            .border_radius(border_radius)
+            .active(|s| s.class(CheckboxClass, |s| s.background(active_bg_color)))
+            .focus(|s| {
+                s.class(CheckboxClass, |_| focus_applied_style.clone())
+                    .hover(|s| s.background(focus_hover_bg_color))
+            })
+            .disabled(|s| {
+                s.color(palette.muted_text).class(CheckboxClass, |s| {
+                    s.background(disabled_bg_color)
+                        .color(palette.muted_text)
+                        .hover(|s| s.background(disabled_bg_color))
+                })
+            })
+            .apply(focus_style.clone());
+
+        let radio_button_style = Style::new()
+            .width(20.)
+            .height(20.)
+            .align_items(AlignItems::Center)
+            .justify_center()
+            .background(palette.surface)
+            .active(|s| s.background(active_bg_color))
+            .transition(Background, Transition::linear(400.millis()))
+            .hover(|s| s.background(hover_bg_color))
+            .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
+            .apply(border_style.clone())
+            .padding(0.)
+            .border_radius(100.0)
+            .apply(focus_style.clone())
+            .disabled(|s| s.background(disabled_bg_color).color(palette.muted_text));
+
+        let radio_button_dot_style = Style::new()
+            .width(8.)
+            .height(8.)
+            .border_radius(100.0)
+            .background(palette.text)
+            .disabled(|s| {
+                s.background(palette.muted_text)
+                    .hover(|s| s.background(palette.muted_text))
+            });
+
+        let labeled_radio_button_style = Style::new()
+            .row_gap(padding)
+            .hover(|s| s.background(hover_bg_color))
+            .padding(padding)
+            .transition(Background, Transition::linear(400.millis()))
+            .border_radius(border_radius)
+            .active(|s| s.class(RadioButtonClass, |s| s.background(active_bg_color)))
+            .focus(|s| {
+                s.class(RadioButtonClass, |_| focus_applied_style.clone())
+                    .hover(|s| s.background(focus_hover_bg_color))
+            })
+            .disabled(|s| {
+                s.color(palette.muted_text).class(RadioButtonClass, |s| {
+                    s.background(disabled_bg_color)
+                        .color(palette.muted_text)
+                        .hover(|s| s.background(disabled_bg_color))
+                })
+            })
+            .apply(focus_style.clone());
 
-    let radio_button_dot_style = Style::new()
-        .width(8.)
-        .height(8.)
-        .border_radius(100.0)
-        .background(Color::BLACK)
-        .disabled(|s| {
-            s.background(Color::rgb(0.5, 0.5, 0.5))
-                .hover(|s| s.background(Color::rgb(0.5, 0.5, 0.5)))
+        let toggle_button_style = Style::new()
+            .active(|s| {
+                s.background(active_bg_color)
+                    .color(palette.on_active)
+                    .set(Foreground, Brush::Solid(palette.on_active))
+            })
+            .aspect_ratio(2.)
+            .background(palette.control)
+            .border_radius(50.pct())
+            .border(1.)
+            .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
+            .height(font_size * 1.75)
+            .hover(|s| s.background(hover_bg_color))
+            .padding(padding)
+            .set(Foreground, Brush::Solid(palette.handle))
+            .set(ToggleButtonCircleRad, 75.pct())
+            .set(ToggleButtonInset, 10.pct())
+            .apply(border_style.clone())
+            .apply(focus_style.clone());
+
+        let input_style = Style::new()
+            .background(palette.surface)
+            .hover(|s| s.background(palette.input_hover))
+            .focus(|s| s.hover(|s| s.background(palette.input_focus_hover)))
+            .apply(border_style.clone())
+            .apply(focus_style.clone())
+            .cursor(CursorStyle::Text)
+            .padding(padding)
+            .disabled(|s| s.background(disabled_bg_color).color(palette.muted_text));
+
+        let item_focused_style = Style::new().selected(|s| {
+            s.background(palette.selected)
+                .hover(|s| s.background(palette.selected_hover))
         });
 
-    let labeled_radio_button_style = Style::new()
-        .row_gap(padding)
-        .hover(|s| s.background(hover_bg_color))
-        .padding(padding)
-        .transition(Background, Transition::linear(400.millis()))
-        .border_radius(border_radius)
-        .active(|s| s.class(RadioButtonClass, |s| s.background(active_bg_color)))
-        .focus(|s| {
-            s.class(RadioButtonClass, |_| focus_applied_style.clone())
-                .hover(|s| s.background(focus_hover_bg_color))
-        })
-        .disabled(|s| {
-            s.color(Color::GRAY).class(RadioButtonClass, |s| {
-                s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
-                    .color(Color::GRAY)
-                    .hover(|s| s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3)))
+        let item_unfocused_style = Style::new()
+            .hover(|s| s.background(hover_bg_color))
+            .selected(|s| {
+                s.background(palette.selected_unfocused)
+                    .hover(|s| s.background(palette.selected_unfocused_hover))
+            });
+
+        let style = Style::new()
+            .class(ListClass, |s| {
+                s.focus(|s| s.class(ListItemClass, |_| item_focused_style))
+                    .class(ListItemClass, |_| item_unfocused_style)
             })
-        })
-        .apply(focus_style.clone());
-
-    let toggle_button_style = Style::new()
-        .active(|s| {
-            s.background(active_bg_color)
-                .color(Color::WHITE.with_alpha_factor(0.9))
-                .set(
-                    Foreground,
-                    Brush::Solid(Color::WHITE.with_alpha_factor(0.9)),
+            .class(LabeledCheckboxClass, |_| labeled_checkbox_style)
+            .class(CheckboxClass, |_| checkbox_style)
+            .class(RadioButtonClass, |_| radio_button_style)
+            .class(RadioButtonDotClass, |_| radio_button_dot_style)
+            .class(LabeledRadioButtonClass, |_| labeled_radio_button_style)
+            .class(TextInputClass, |_| input_style)
+            .class(ButtonClass, |_| button_style)
+            .apply_custom(
+                scroll::ScrollCustomStyle::new()
+                    .handle_border_radius(4.0)
+                    .handle_background(palette.scrollbar)
+                    .handle_thickness(16.0)
+                    .handle_rounded(false),
+            )
+            .class(scroll::Handle, |s| {
+                s.active(|s| s.background(palette.scrollbar_active))
+                    .hover(|s| s.background(palette.scrollbar_hover))
+            })
+            .class(scroll::Track, |s| {
+                s.hover(|s| s.background(palette.scrollbar_track_hover))
+            })
+            .class(ToggleButtonClass, |_| toggle_button_style)
+            .class(SliderClass, |s| {
+                s.apply_custom(
+                    slider::SliderCustomStyle::new()
+                        .bar_color(palette.text)
+                        .bar_radius(100.pct())
+                        .accent_bar_color(palette.accent)
+                        .accent_bar_radius(100.pct())
+                        .handle_color(Brush::Solid(palette.handle))
+                        .handle_radius(100.pct())
+                        .edge_align(true),
                 )
-        })
-        .aspect_ratio(2.)
-        .background(Color::rgb8(240, 240, 240))
-        .border_radius(50.pct())
-        .border(1.)
-        .focus(|s| s.hover(|s| s.background(focus_hover_bg_color)))
-        .height(FONT_SIZE * 1.75)
-        .hover(|s| s.background(hover_bg_color))
-        .padding(padding)
-        .set(Foreground, Brush::Solid(Color::DARK_GRAY))
-        .set(ToggleButtonCircleRad, 75.pct())
-        .set(ToggleButtonInset, 10.pct())
-        .apply(border_style.clone())
-        .apply(focus_style.clone());
-
-    const FONT_SIZE: f32 = 12.0;
-
-    let input_style = Style::new()
-        .background(Color::WHITE)
-        .hover(|s| s.background(light_hover_bg_color))
-        .focus(|s| s.hover(|s| s.background(light_focus_hover_bg_color)))
-        .apply(border_style.clone())
-        .apply(focus_style.clone())
-        .cursor(CursorStyle::Text)
-        .padding(padding)
-        .disabled(|s| {
-            s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
-                .color(Color::GRAY)
-        });
+                .height(15)
+                .width(100)
+            })
+            .class(PlaceholderTextClass, |s| {
+                s.color(palette.placeholder).font_size(font_size)
+            })
+            .class(TooltipClass, |s| {
+                s.border(0.5)
+                    .border_color(palette.border)
+                    .color(palette.tooltip_text)
+                    .border_radius(self.radii.small)
+                    .padding(padding)
+                    .margin(self.spacing.large)
+                    .background(palette.popup)
+                    .box_shadow_blur(2.0)
+                    .box_shadow_h_offset(2.0)
+                    .box_shadow_v_offset(2.0)
+                    .box_shadow_color(palette.shadow.with_alpha_factor(0.2))
+            })
+            .class(dropdown::DropdownClass, |s| {
+                s.width(75)
+                    .padding(self.spacing.small)
+                    .apply(border_style)
+                    .class(scroll::ScrollClass, |s| {
+                        s.width_full()
+                            .margin_top(self.spacing.small)
+                            .padding_vert(self.spacing.small)
+                            .background(palette.popup)
+                            .box_shadow_blur(2.0)
+                            .box_shadow_h_offset(2.0)
+                            .box_shadow_v_offset(2.0)
+                            .box_shadow_color(palette.shadow.with_alpha_factor(0.4))
+                            .border_radius(5.pct())
+                            .items_center()
+                            .class(ListItemClass, |s| {
+                                s.margin_horiz(self.spacing.small)
+                                    .padding(self.spacing.small)
+                                    .items_center()
+                            })
+                    })
+            })
+            .font_size(font_size)
+            .color(palette.text);
 
-    let item_focused_style = Style::new().selected(|s| {
-        s.background(selected_bg_color)
-            .hover(|s| s.background(selected_hover_bg_color))
-    });
+        let style = style.apply(common::theme_style(self));
 
-    let item_unfocused_style = Style::new()
-        .hover(|s| s.background(hover_bg_color))
-        .selected(|s| {
-            s.background(selected_unfocused_bg_color)
-                .hover(|s| s.background(selected_unfocused_hover_bg_color))
-        });
+        let style = match &self.typography.font_family {
+            Some(family) => style.font_family(family.clone()),
+            None => style,
+        };
+
+        style.apply(self.overrides.clone())
+    }
+}
+
+/// Which [`Theme`] a window uses.
+#[derive(Debug, Clone)]
+pub enum WindowTheme {
+    /// Always use this theme.
+    Fixed(Theme),
+    /// Use `light` or `dark` depending on the theme of the OS, switching when it changes.
+    System { light: Theme, dark: Theme },
+}
 
-    let theme = Style::new()
-        .class(ListClass, |s| {
-            s.focus(|s| s.class(ListItemClass, |_| item_focused_style))
-                .class(ListItemClass, |_| item_unfocused_style)
-        })
-        .class(LabeledCheckboxClass, |_| labeled_checkbox_style)
-        .class(CheckboxClass, |_| checkbox_style)
-        .class(RadioButtonClass, |_| radio_button_style)
-        .class(RadioButtonDotClass, |_| radio_button_dot_style)
-        .class(LabeledRadioButtonClass, |_| labeled_radio_button_style)
-        .class(TextInputClass, |_| input_style)
-        .class(ButtonClass, |_| button_style)
-        .apply_custom(
-            scroll::ScrollCustomStyle::new()
-                .handle_border_radius(4.0)
-                .handle_background(Color::rgba8(166, 166, 166, 140))
-                .handle_thickness(16.0)
-                .handle_rounded(false),
+impl WindowTheme {
+    /// Follows the OS between [`Theme::light`] and [`Theme::dark`].
+    pub fn system() -> Self {
+        WindowTheme::System {
+            light: Theme::light(),
+            dark: Theme::dark(),
+        }
+    }
+
+    /// Returns the theme to use when the OS theme is `os_theme`.
+    ///
+    /// The light theme is used if the OS theme is unknown.
+    pub fn resolve(&self, os_theme: Option<crate::window::Theme>) -> &Theme {
+        match self {
+            WindowTheme::Fixed(theme) => theme,
+            WindowTheme::System { light, dark } => match os_theme {
+                Some(crate::window::Theme::Dark) => dark,
+                _ => light,
+            },
+        }
+    }
+}
+
+impl Default for WindowTheme {
+    fn default() -> Self {
+        WindowTheme::Fixed(Theme::light())
+    }
+}
+
+impl From<Theme> for WindowTheme {
+    fn from(theme: Theme) -> Self {
+        WindowTheme::Fixed(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use peniko::Color;
+
+    use super::{Theme, WindowTheme};
+    use crate::{
+        headless::{HeadlessConfig, HeadlessWindow},
+        views::{empty, Decorators},
+    };
+
+    #[test]
+    fn window_is_filled_with_theme_background() {
+        let background = Color::rgb8(10, 20, 30);
+        let theme = Theme::dark().palette(Theme::dark().palette.background(background));
+        let image = HeadlessWindow::new(
+            empty,
+            HeadlessConfig::default().size((10.0, 10.0)).theme(theme),
         )
-        .class(scroll::Handle, |s| {
-            s.active(|s| s.background(Color::rgb8(166, 166, 166)))
-                .hover(|s| s.background(Color::rgb8(184, 184, 184)))
-        })
-        .class(scroll::Track, |s| {
-            s.hover(|s| s.background(Color::rgba8(166, 166, 166, 30)))
-        })
-        .class(ToggleButtonClass, |_| toggle_button_style)
-        .class(SliderClass, |s| {
-            s.apply_custom(
-                slider::SliderCustomStyle::new()
-                    .bar_color(Color::BLACK)
-                    .bar_radius(100.pct())
-                    .accent_bar_color(Color::GREEN)
-                    .accent_bar_radius(100.pct())
-                    .handle_color(Brush::Solid(Color::DARK_GRAY))
-                    .handle_radius(100.pct())
-                    .edge_align(true),
-            )
-            .height(15)
-            .width(100)
-        })
-        .class(PlaceholderTextClass, |s| {
-            s.color(Color::rgba8(158, 158, 158, 30))
-                .font_size(FONT_SIZE)
-        })
-        .class(TooltipClass, |s| {
-            s.border(0.5)
-                .border_color(Color::rgb8(140, 140, 140))
-                .color(Color::rgb8(80, 80, 80))
-                .border_radius(2.0)
-                .padding(padding)
-                .margin(10.0)
-                .background(Color::WHITE_SMOKE)
-                .box_shadow_blur(2.0)
-                .box_shadow_h_offset(2.0)
-                .box_shadow_v_offset(2.0)
-                .box_shadow_color(Color::BLACK.with_alpha_factor(0.2))
-        })
-        .class(dropdown::DropdownClass, |s| {
-            s.width(75)
-                .padding(3)
-                .apply(border_style)
-                .class(scroll::ScrollClass, |s| {
-                    s.width_full()
-                        .margin_top(3)
-                        .padding_vert(3)
-                        .background(Color::WHITE_SMOKE)
-                        .box_shadow_blur(2.0)
-                        .box_shadow_h_offset(2.0)
-                        .box_shadow_v_offset(2.0)
-                        .box_shadow_color(Color::BLACK.with_alpha_factor(0.4))
-                        .border_radius(5.pct())
-                        .items_center()
-                        .class(ListItemClass, |s| {
-                            s.margin_horiz(3).padding(3).items_center()
-                        })
-                })
-        })
-        .font_size(FONT_SIZE)
-        .color(Color::BLACK);
+        .render()
+        .to_rgba8();
+        assert_eq!(image.get_pixel(5, 5).0, [10, 20, 30, 255]);
+
+        let image = HeadlessWindow::new(
+            empty,
+            HeadlessConfig::default()
+                .size((10.0, 10.0))
+                .apply_default_theme(false),
+        )
+        .render()
+        .to_rgba8();
+        assert_eq!(image.get_pixel(5, 5).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn system_theme_follows_os() {
+        crate::style_class!(Swatch);
+
+        let theme = WindowTheme::System {
+            light: Theme::light().class(Swatch, |s| s.background(Color::RED)),
+            dark: Theme::dark().class(Swatch, |s| s.background(Color::BLUE)),
+        };
+        let mut window = HeadlessWindow::new(
+            || empty().class(Swatch).style(|s| s.size_full()),
+            HeadlessConfig::default().size((10.0, 10.0)).theme(theme),
+        );
+        assert_eq!(
+            window.render().to_rgba8().get_pixel(5, 5).0,
+            [255, 0, 0, 255]
+        );
 
-    Theme {
-        background: Color::rgb8(248, 248, 248),
-        style: Rc::new(theme),
+        window.os_theme_changed(crate::window::Theme::Dark);
+        assert_eq!(
+            window.render().to_rgba8().get_pixel(5, 5).0,
+            [0, 0, 255, 255]
+        );
     }
 }
@@ -0,0 +1,276 @@
+//! Touchscreen and touchpad gesture events.
+//!
+//! Each finger on a touchscreen is reported with [`Event::TouchStart`](crate::event::Event::TouchStart),
+//! then [`TouchMove`](crate::event::Event::TouchMove) and finally
+//! [`TouchEnd`](crate::event::Event::TouchEnd) or [`TouchCancel`](crate::event::Event::TouchCancel),
+//! all carrying the same [`TouchId`]. The view that stops the propagation of the `TouchStart`
+//! receives the rest of the events of that finger, even once it leaves the view.
+//!
+//! Pinching and rotating with two fingers on a touchscreen is also reported as
+//! [`PinchZoom`](crate::event::Event::PinchZoom) and [`Rotate`](crate::event::Event::Rotate)
+//! events, the same as the gestures of a touchpad.
+//!
+//! A pen on a touchscreen is delivered as pointer events instead, with a
+//! [`PointerType`](crate::pointer::PointerType) of `Pen`.
+
+use floem_winit::event::TouchPhase;
+use peniko::kurbo::Point;
+
+use crate::keyboard::Modifiers;
+
+/// Identifies a finger on a touchscreen, from the moment it touches the screen until it is
+/// lifted. Ids are never reused by a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TouchId(u64);
+
+#[derive(Debug, Clone)]
+pub struct TouchEvent {
+    pub id: TouchId,
+    pub pos: Point,
+    /// The pressure of the finger from `0.0` to `1.0`, if the screen reports it.
+    pub force: Option<f64>,
+    pub modifiers: Modifiers,
+}
+
+/// The stage of a continuous gesture such as a pinch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GesturePhase {
+    Started,
+    Changed,
+    Ended,
+    Cancelled,
+}
+
+impl From<TouchPhase> for GesturePhase {
+    fn from(value: TouchPhase) -> Self {
+        match value {
+            TouchPhase::Started => Self::Started,
+            TouchPhase::Moved => Self::Changed,
+            TouchPhase::Ended => Self::Ended,
+            TouchPhase::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+/// A pinch on a touchpad, or with two fingers on a touchscreen.
+#[derive(Debug, Clone)]
+pub struct PinchEvent {
+    /// The center of the pinch. On a touchpad, this is the cursor position.
+    pub pos: Point,
+    /// The change of scale since the previous event. Content scaled by `s` should now be
+    /// scaled by `s * (1.0 + delta)`, so positive values zoom in.
+    pub delta: f64,
+    pub phase: GesturePhase,
+    pub modifiers: Modifiers,
+}
+
+/// A rotation on a touchpad, or with two fingers on a touchscreen.
+#[derive(Debug, Clone)]
+pub struct RotateEvent {
+    /// The center of the rotation. On a touchpad, this is the cursor position.
+    pub pos: Point,
+    /// The change of angle since the previous event, in degrees. Positive values rotate
+    /// counterclockwise.
+    pub delta: f64,
+    pub phase: GesturePhase,
+    pub modifiers: Modifiers,
+}
+
+/// A double tap on a touchpad asking to zoom in on the content under the cursor, or back
+/// out if it is already zoomed in.
+#[derive(Debug, Clone)]
+pub struct DoubleTapZoomEvent {
+    pub pos: Point,
+    pub modifiers: Modifiers,
+}
+
+/// The pressure of a click on a touchpad that senses force.
+#[derive(Debug, Clone)]
+pub struct TouchpadPressureEvent {
+    pub pos: Point,
+    /// From `0.0` to `1.0`.
+    pub pressure: f64,
+    /// `0` while the touchpad is touched without clicking, `1` for a click and `2` for a
+    /// force click.
+    pub stage: i64,
+    pub modifiers: Modifiers,
+}
+
+/// A pinch and rotation made with the first two fingers on a touchscreen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TwoFingerGesture {
+    /// The point between the two fingers.
+    pub pos: Point,
+    /// See [`PinchEvent::delta`].
+    pub scale_delta: f64,
+    /// See [`RotateEvent::delta`].
+    pub rotate_delta: f64,
+    pub phase: GesturePhase,
+}
+
+/// Gives the fingers on a touchscreen their [`TouchId`] and turns the movement of the first
+/// two into a [`TwoFingerGesture`].
+#[derive(Default)]
+pub(crate) struct TouchTracker {
+    next_id: u64,
+    /// The platform id, floem id and position of each finger, in the order they touched.
+    touches: Vec<(u64, TouchId, Point)>,
+    gesture: bool,
+}
+
+impl TouchTracker {
+    /// Records a change of the finger with the platform id `platform_id`, returning its
+    /// [`TouchId`] and the change of the two-finger gesture, if any.
+    pub fn touch(
+        &mut self,
+        platform_id: u64,
+        phase: TouchPhase,
+        pos: Point,
+    ) -> (TouchId, Option<TwoFingerGesture>) {
+        let index = self
+            .touches
+            .iter()
+            .position(|(platform, _, _)| *platform == platform_id);
+
+        let (id, index) = match (phase, index) {
+            (TouchPhase::Started, _) | (_, None) => {
+                if let Some(index) = index {
+                    // The platform reused the id of a finger whose end was never reported.
+                    self.remove(index, GesturePhase::Cancelled);
+                }
+                let id = TouchId(self.next_id);
+                self.next_id += 1;
+                self.touches.push((platform_id, id, pos));
+                if self.touches.len() == 2 && phase == TouchPhase::Started {
+                    self.gesture = true;
+                    let pos = self.pair().map(|(a, b)| a.midpoint(b)).unwrap_or(pos);
+                    return (
+                        id,
+                        Some(TwoFingerGesture {
+                            pos,
+                            scale_delta: 0.0,
+                            rotate_delta: 0.0,
+                            phase: GesturePhase::Started,
+                        }),
+                    );
+                }
+                (id, self.touches.len() - 1)
+            }
+            (_, Some(index)) => (self.touches[index].1, index),
+        };
+
+        match phase {
+            TouchPhase::Started => (id, None),
+            TouchPhase::Moved => {
+                let before = self.pair();
+                self.touches[index].2 = pos;
+                let gesture = match (self.gesture && index < 2, before, self.pair()) {
+                    (true, Some(before), Some(after)) => Some(gesture_change(before, after)),
+                    _ => None,
+                };
+                (id, gesture)
+            }
+            TouchPhase::Ended => (id, self.remove(index, GesturePhase::Ended)),
+            TouchPhase::Cancelled => (id, self.remove(index, GesturePhase::Cancelled)),
+        }
+    }
+
+    /// The position of a finger that is still on the screen.
+    pub fn position(&self, id: TouchId) -> Option<Point> {
+        self.touches
+            .iter()
+            .find(|(_, touch, _)| *touch == id)
+            .map(|(_, _, pos)| *pos)
+    }
+
+    fn pair(&self) -> Option<(Point, Point)> {
+        match self.touches.as_slice() {
+            [(_, _, a), (_, _, b), ..] => Some((*a, *b)),
+            _ => None,
+        }
+    }
+
+    fn remove(&mut self, index: usize, phase: GesturePhase) -> Option<TwoFingerGesture> {
+        let gesture = (self.gesture && index < 2)
+            .then(|| self.pair())
+            .flatten()
+            .map(|(a, b)| TwoFingerGesture {
+                pos: a.midpoint(b),
+                scale_delta: 0.0,
+                rotate_delta: 0.0,
+                phase,
+            });
+        if gesture.is_some() {
+            self.gesture = false;
+        }
+        self.touches.remove(index);
+        gesture
+    }
+}
+
+fn gesture_change(before: (Point, Point), after: (Point, Point)) -> TwoFingerGesture {
+    let span_before = before.1 - before.0;
+    let span_after = after.1 - after.0;
+    let scale_delta = if span_before.hypot() > 0.0 {
+        span_after.hypot() / span_before.hypot() - 1.0
+    } else {
+        0.0
+    };
+    // The y axis points down, so a counterclockwise turn on screen decreases the angle.
+    let rotate_delta = normalize_degrees((span_before.angle() - span_after.angle()).to_degrees());
+    TwoFingerGesture {
+        pos: after.0.midpoint(after.1),
+        scale_delta,
+        rotate_delta,
+        phase: GesturePhase::Changed,
+    }
+}
+
+/// Maps an angle in degrees into `-180.0..180.0`.
+fn normalize_degrees(degrees: f64) -> f64 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_winit::event::TouchPhase;
+    use peniko::kurbo::Point;
+
+    use super::{GesturePhase, TouchTracker};
+
+    #[test]
+    fn two_fingers_pinch_and_rotate() {
+        let mut touches = TouchTracker::default();
+        let (first, gesture) = touches.touch(7, TouchPhase::Started, Point::new(0.0, 0.0));
+        assert!(gesture.is_none());
+        let (second, gesture) = touches.touch(3, TouchPhase::Started, Point::new(10.0, 0.0));
+        assert_ne!(first, second);
+        assert_eq!(gesture.unwrap().phase, GesturePhase::Started);
+
+        // Spreading the fingers to twice the distance zooms in by 2x.
+        let (id, gesture) = touches.touch(3, TouchPhase::Moved, Point::new(20.0, 0.0));
+        assert_eq!(id, second);
+        let gesture = gesture.unwrap();
+        assert_eq!(gesture.pos, Point::new(10.0, 0.0));
+        assert!((gesture.scale_delta - 1.0).abs() < 1e-9);
+        assert_eq!(gesture.rotate_delta, 0.0);
+
+        // Moving the second finger up turns the pair counterclockwise on screen.
+        let (_, gesture) = touches.touch(3, TouchPhase::Moved, Point::new(0.0, -20.0));
+        let gesture = gesture.unwrap();
+        assert!((gesture.rotate_delta - 90.0).abs() < 1e-9);
+        assert!(gesture.scale_delta.abs() < 1e-9);
+
+        let (id, gesture) = touches.touch(7, TouchPhase::Ended, Point::new(0.0, 0.0));
+        assert_eq!(id, first);
+        assert_eq!(gesture.unwrap().phase, GesturePhase::Ended);
+        assert!(touches
+            .touch(3, TouchPhase::Moved, Point::new(5.0, 5.0))
+            .1
+            .is_none());
+
+        // A platform id reused after the end of its finger gets a new id.
+        let (third, _) = touches.touch(7, TouchPhase::Started, Point::new(0.0, 0.0));
+        assert!(third != first && third != second);
+    }
+}
@@ -3,7 +3,9 @@ use std::{any::Any, cell::RefCell, collections::HashMap};
 use floem_winit::window::ResizeDirection;
 use peniko::kurbo::{Point, Rect, Size, Vec2};
 
-use crate::{id::ViewId, menu::Menu, view::View};
+use crate::{
+    id::ViewId, menu::Menu, theme::WindowTheme, touch::TouchId, view::View, window::FramePacing,
+};
 
 thread_local! {
     /// Stores all the update message with their original `ViewId`
@@ -31,6 +33,12 @@ pub(crate) enum UpdateMessage {
     ClearFocus(ViewId),
     Active(ViewId),
     ClearActive(ViewId),
+    /// The pointer pressed on the view became a gesture, so releasing it clicks no view.
+    CancelClick(ViewId),
+    CaptureTouch {
+        id: ViewId,
+        touch: TouchId,
+    },
     WindowScale(f64),
     Disabled {
         id: ViewId,
@@ -63,6 +71,8 @@ pub(crate) enum UpdateMessage {
     SetWindowTitle {
         title: String,
     },
+    SetTheme(Option<WindowTheme>),
+    SetFramePacing(FramePacing),
     AddOverlay {
         id: ViewId,
         position: Point,
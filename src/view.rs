@@ -45,7 +45,8 @@
 
 use floem_reactive::{ReadSignal, RwSignal, SignalGet};
 use floem_renderer::Renderer;
-use peniko::kurbo::{Circle, Insets, Line, Point, Rect, RoundedRect, Size};
+use peniko::kurbo::{Circle, Insets, Line, Point, Rect, RoundedRect, Shape, Size};
+use peniko::Brush;
 use std::any::Any;
 use taffy::tree::NodeId;
 
@@ -54,7 +55,7 @@ use crate::{
     context::{ComputeLayoutCx, EventCx, LayoutCx, PaintCx, StyleCx, UpdateCx},
     event::{Event, EventPropagation},
     id::ViewId,
-    style::{BoxShadowProp, LayoutProps, Style, StyleClassRef},
+    style::{BackgroundRepeat, BoxShadowProp, LayoutProps, Style, StyleClassRef},
     view_state::ViewStyleProps,
     views::{dyn_view, DynamicView},
 };
@@ -399,7 +400,7 @@ pub(crate) fn paint_bg(
                 Some(color) => color,
                 None => return,
             };
-            cx.fill(&circle, &bg, 0.0);
+            paint_bg_shape(cx, style, &circle, &bg, size);
         } else {
             paint_box_shadow(cx, computed_style, rect, Some(radius));
             let bg = match style.background() {
@@ -407,7 +408,7 @@ pub(crate) fn paint_bg(
                 None => return,
             };
             let rounded_rect = rect.to_rounded_rect(radius);
-            cx.fill(&rounded_rect, &bg, 0.0);
+            paint_bg_shape(cx, style, &rounded_rect, &bg, size);
         }
     } else {
         paint_box_shadow(cx, computed_style, size.to_rect(), None);
@@ -415,7 +416,24 @@ pub(crate) fn paint_bg(
             Some(color) => color,
             None => return,
         };
-        cx.fill(&size.to_rect(), &bg, 0.0);
+        paint_bg_shape(cx, style, &size.to_rect(), &bg, size);
+    }
+}
+
+fn paint_bg_shape(
+    cx: &mut PaintCx,
+    style: &ViewStyleProps,
+    shape: &impl Shape,
+    bg: &Brush,
+    size: Size,
+) {
+    if let Brush::Image(img) = bg {
+        let image_size = Size::new(img.width as f64, img.height as f64);
+        let rect = style.background_size().image_rect(image_size, size);
+        let repeat = style.background_repeat() == BackgroundRepeat::Repeat;
+        cx.fill_img(shape, img, rect, repeat);
+    } else {
+        cx.fill(shape, bg, 0.0);
     }
 }
 
@@ -3,13 +3,14 @@ use crate::{
     context::{
         EventCallback, InteractionState, MenuCallback, MoveListener, ResizeCallback, ResizeListener,
     },
+    drag::{DragPayload, DropTarget},
     event::EventListener,
     pointer::PointerInputEvent,
     prop_extractor,
     responsive::ScreenSizeBp,
     style::{
-        Background, BorderColor, BorderRadius, LayoutProps, Outline, OutlineColor, Style,
-        StyleClassRef, StyleSelectors,
+        Background, BackgroundRepeatProp, BackgroundSizeProp, BorderColor, BorderRadius,
+        LayoutProps, Opacity, Outline, OutlineColor, Style, StyleClassRef, StyleSelectors,
     },
 };
 use bitflags::bitflags;
@@ -72,6 +73,9 @@ prop_extractor! {
         pub outline_color: OutlineColor,
         pub border_color: BorderColor,
         pub background: Background,
+        pub background_repeat: BackgroundRepeatProp,
+        pub background_size: BackgroundSizeProp,
+        pub opacity: Opacity,
     }
 }
 
@@ -164,6 +168,8 @@ pub struct ViewState {
     pub(crate) event_listeners: HashMap<EventListener, Vec<Rc<RefCell<EventCallback>>>>,
     pub(crate) context_menu: Option<Rc<MenuCallback>>,
     pub(crate) popout_menu: Option<Rc<MenuCallback>>,
+    pub(crate) drag_payload: Option<Rc<dyn Fn() -> DragPayload>>,
+    pub(crate) drop_target: Option<DropTarget>,
     pub(crate) resize_listener: Option<Rc<RefCell<ResizeListener>>>,
     pub(crate) window_origin: Point,
     pub(crate) move_listener: Option<Rc<RefCell<MoveListener>>>,
@@ -194,6 +200,8 @@ impl ViewState {
             event_listeners: HashMap::new(),
             context_menu: None,
             popout_menu: None,
+            drag_payload: None,
+            drop_target: None,
             resize_listener: None,
             move_listener: None,
             cleanup_listener: None,
@@ -2,6 +2,8 @@
 //!
 //! The decorator trait is the primary interface for extending the appearance and functionality of ['View']s.
 
+use std::{cell::RefCell, rc::Rc};
+
 use floem_reactive::{create_effect, create_updater, SignalUpdate};
 use floem_winit::keyboard::Key;
 use peniko::kurbo::{Point, Rect};
@@ -9,7 +11,9 @@ use peniko::kurbo::{Point, Rect};
 use crate::{
     action::{set_window_menu, set_window_title, update_window_scale},
     animate::Animation,
+    drag::{DragPayload, DropEvent, DropTarget},
     event::{Event, EventListener, EventPropagation},
+    gesture::{self, PanEvent, PinchGesture, SwipeDirection},
     keyboard::Modifiers,
     menu::Menu,
     style::{Style, StyleClass, StyleSelector},
@@ -86,7 +90,7 @@ pub trait Decorators: IntoView<V = Self::DV> + Sized {
         view
     }
 
-    /// The visual style to apply when the mouse hovers over the element
+    /// The visual style of the view painted under the pointer while it is dragged.
     fn dragging_style(self, style: impl Fn(Style) -> Style + 'static) -> Self::DV {
         let view = self.into_view();
         let view_id = view.id();
@@ -136,6 +140,35 @@ pub trait Decorators: IntoView<V = Self::DV> + Sized {
         view
     }
 
+    /// Makes the view draggable, with `payload` giving the data of each drag.
+    ///
+    /// The payload is dropped on the first view under the pointer, from the deepest, that
+    /// accepts it with [`on_drop`](Decorators::on_drop).
+    fn on_drag_start(self, payload: impl Fn() -> DragPayload + 'static) -> Self::DV {
+        let view = self.draggable();
+        view.id().update_drag_payload(Rc::new(payload));
+        view
+    }
+
+    /// Makes the view a drop target for the payloads of [`on_drag_start`](Decorators::on_drag_start).
+    ///
+    /// `accepts` tells whether a payload can be dropped on the view, for example by its type
+    /// with [`DragPayload::is`] or its [`mime_type`](DragPayload::mime_type). Payloads that
+    /// aren't accepted are offered to the views under this one. While an accepted payload is
+    /// dragged over the view, it gets its [`drag_over`](Style::drag_over) style.
+    fn on_drop(
+        self,
+        accepts: impl Fn(&DragPayload) -> bool + 'static,
+        action: impl Fn(&DropEvent) + 'static,
+    ) -> Self::DV {
+        let view = self.into_view();
+        view.id().update_drop_target(DropTarget {
+            accepts: Rc::new(accepts),
+            on_drop: Rc::new(action),
+        });
+        view
+    }
+
     fn disabled(self, disabled_fn: impl Fn() -> bool + 'static) -> Self::DV {
         let view = self.into_view();
         let id = view.id();
@@ -286,6 +319,94 @@ pub trait Decorators: IntoView<V = Self::DV> + Sized {
         })
     }
 
+    /// Add a handler for the input of an embedded engine, such as the scene shown in a
+    /// [`wgpu_viewport`](crate::views::wgpu_viewport).
+    ///
+    /// The handler receives pointer, wheel, key and modifier events in the view's local
+    /// coordinates, after floem's own views had the chance to stop them:
+    /// - pointer events only arrive when no view on top of this one handled them. After a press
+    ///   on this view, pointer events keep arriving until the button is released, even outside of it.
+    /// - key events arrive while this view has focus. Clicking the view focuses it, so keys
+    ///   typed into a text input never reach the engine.
+    /// - `ModifiersChanged` always arrives, so the engine sees modifiers released while another
+    ///   view has focus.
+    fn on_engine_input(self, action: impl FnMut(&Event) -> EventPropagation + 'static) -> Self::DV {
+        let view = self.keyboard_navigatable();
+        let id = view.id();
+        let action = Rc::new(RefCell::new(action));
+        for listener in [
+            EventListener::PointerDown,
+            EventListener::PointerMove,
+            EventListener::PointerUp,
+            EventListener::PointerWheel,
+            EventListener::KeyDown,
+            EventListener::KeyUp,
+            EventListener::ModifiersChanged,
+        ] {
+            let action = action.clone();
+            id.add_event_listener(
+                listener,
+                Box::new(move |event| {
+                    if let Event::PointerDown(_) = event {
+                        id.request_active();
+                    }
+                    (action.borrow_mut())(event)
+                }),
+            );
+        }
+        view
+    }
+
+    /// Add a handler for a long press, when the pointer or a finger is held still on the view
+    /// for [`LONG_PRESS_DELAY`](gesture::LONG_PRESS_DELAY). The handler receives the position
+    /// of the press.
+    ///
+    /// The release ending a long press doesn't click the view. See the [`gesture`] module for
+    /// how gestures of nested views are resolved.
+    fn on_long_press(self, action: impl Fn(Point) + 'static) -> Self::DV {
+        let view = self.into_view();
+        gesture::add_long_press(view.id(), action);
+        view
+    }
+
+    /// Add handlers for a pan, when the pointer or a finger is pressed on the view and dragged.
+    ///
+    /// `start` is called once the pointer moves further than
+    /// [`DRAG_THRESHOLD`](gesture::DRAG_THRESHOLD), then `update` for every move, even outside
+    /// of the view, and `end` when the pointer is released. See the [`gesture`] module for how
+    /// gestures of nested views are resolved.
+    fn on_pan(
+        self,
+        start: impl Fn(&PanEvent) + 'static,
+        update: impl Fn(&PanEvent) + 'static,
+        end: impl Fn(&PanEvent) + 'static,
+    ) -> Self::DV {
+        let view = self.into_view();
+        gesture::add_pan(view.id(), start, update, end);
+        view
+    }
+
+    /// Add a handler for a swipe, when the pointer or a finger is dragged on the view and
+    /// released while moving quickly.
+    ///
+    /// The handler receives the direction the pointer was moving in. See the [`gesture`] module
+    /// for how gestures of nested views are resolved.
+    fn on_swipe(self, action: impl Fn(SwipeDirection) + 'static) -> Self::DV {
+        let view = self.into_view();
+        gesture::add_swipe(view.id(), action);
+        view
+    }
+
+    /// Add a handler for a pinch, with two fingers on a touchscreen or on a touchpad.
+    ///
+    /// Unlike [`EventListener::PinchZoom`], the handler also receives the scale since the start
+    /// of the pinch.
+    fn on_pinch(self, action: impl Fn(&PinchGesture) + 'static) -> Self::DV {
+        let view = self.into_view();
+        gesture::add_pinch(view.id(), action);
+        view
+    }
+
     fn on_resize(self, action: impl Fn(Rect) + 'static) -> Self::DV {
         let view = self.into_view();
         let id = view.id();
@@ -395,3 +516,60 @@ pub trait Decorators: IntoView<V = Self::DV> + Sized {
 impl<VW: View, IV: IntoView<V = VW>> Decorators for IV {
     type DV = VW;
 }
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+    use floem_winit::keyboard::Key;
+
+    use crate::{
+        event::{Event, EventPropagation},
+        headless::test_window_with,
+        keyboard::Modifiers,
+        view::View,
+        views::{empty, h_stack, text_input},
+    };
+
+    use super::Decorators;
+
+    #[test]
+    fn keys_typed_into_a_text_input_dont_reach_the_engine() {
+        let text = create_rw_signal(String::new());
+        let keys = create_rw_signal(0);
+        let modifiers = create_rw_signal(Vec::new());
+        let (mut window, (input, engine)) = test_window_with((100.0, 50.0), move || {
+            let input = text_input(text).style(|s| s.size(50.0, 50.0));
+            let engine = empty()
+                .on_engine_input(move |event| {
+                    match event {
+                        Event::KeyDown(_) | Event::KeyUp(_) => keys.update(|k| *k += 1),
+                        Event::ModifiersChanged(held) => modifiers.update(|m| m.push(*held)),
+                        _ => {}
+                    }
+                    EventPropagation::Stop
+                })
+                .style(|s| s.size(50.0, 50.0));
+            let ids = (input.id(), engine.id());
+            (h_stack((input, engine)), ids)
+        });
+
+        window.click((25.0, 25.0));
+        assert_eq!(window.focus(), Some(input));
+        window.type_text("wasd");
+        window.key_down(Key::Character("W".into()), Modifiers::SHIFT);
+        window.key_up(Key::Character("W".into()), Modifiers::empty());
+        assert_eq!(text.get_untracked(), "wasdW");
+        assert_eq!(keys.get_untracked(), 0);
+        // Modifier changes still arrive, so the engine doesn't think shift is held.
+        assert_eq!(
+            modifiers.get_untracked(),
+            vec![Modifiers::SHIFT, Modifiers::empty()]
+        );
+
+        // Once the engine's view has focus, it receives the keys.
+        window.click((75.0, 25.0));
+        assert_eq!(window.focus(), Some(engine));
+        window.key_press(Key::Character("w".into()));
+        assert_eq!(keys.get_untracked(), 2);
+    }
+}
@@ -377,3 +377,38 @@ impl<T> Drop for Dropdown<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet};
+    use floem_winit::keyboard::{Key, NamedKey};
+
+    use crate::{
+        headless::test_window,
+        view::IntoView,
+        views::{text, Decorators},
+    };
+
+    use super::Dropdown;
+
+    #[test]
+    fn arrow_keys_and_enter_choose_from_the_open_list() {
+        let chosen = create_rw_signal("a");
+        let mut window = test_window((100.0, 100.0), move || {
+            Dropdown::new_rw(
+                chosen,
+                |item| text(item).into_any(),
+                ["a", "b", "c"],
+                |item| text(item).into_any(),
+            )
+            .style(|s| s.size(100.0, 20.0))
+        });
+
+        // Clicking opens the list, which takes the focus.
+        window.click((50.0, 10.0));
+        window.key_press(Key::Named(NamedKey::ArrowDown));
+        window.key_press(Key::Named(NamedKey::ArrowDown));
+        window.key_press(Key::Named(NamedKey::Enter));
+        assert_eq!(chosen.get_untracked(), "b");
+    }
+}
@@ -10,6 +10,7 @@ use smallvec::SmallVec;
 use crate::{
     app_state::AppState,
     context::UpdateCx,
+    drag::Reorder,
     id::ViewId,
     view::{IntoView, View},
 };
@@ -29,6 +30,7 @@ where
     id: ViewId,
     children: Vec<Option<(ViewId, Scope)>>,
     view_fn: ViewFn<T>,
+    reorder: Option<Reorder>,
     phantom: PhantomData<T>,
 }
 
@@ -121,10 +123,27 @@ where
         id,
         children: Vec::new(),
         view_fn,
+        reorder: None,
         phantom: PhantomData,
     }
 }
 
+impl<T> DynStack<T> {
+    /// Lets the items be reordered by dragging one onto another.
+    ///
+    /// When an item is dropped, `on_reorder(from, to)` is called with the index of the dragged
+    /// item and of the item it was dropped on. It should move the item at `from` to `to` in the
+    /// list of items, for example with `items.insert(to, items.remove(from))`.
+    ///
+    /// This replaces the drag payload and drop target of the items, and their
+    /// [`dragging_style`](super::Decorators::dragging_style) and
+    /// [`drag_over`](crate::style::Style::drag_over) style can show the reordering.
+    pub fn reorderable(mut self, on_reorder: impl Fn(usize, usize) + 'static) -> Self {
+        self.reorder = Some(Reorder::new(on_reorder));
+        self
+    }
+}
+
 impl<T> View for DynStack<T> {
     fn id(&self) -> ViewId {
         self.id
@@ -143,6 +162,9 @@ impl<T> View for DynStack<T> {
                 &mut self.children,
                 &self.view_fn,
             );
+            if let Some(reorder) = &self.reorder {
+                reorder.attach(self.id);
+            }
             self.id.request_all();
         }
     }
@@ -147,6 +147,9 @@ pub use drag_resize_window_area::*;
 mod img;
 pub use img::*;
 
+mod wgpu_viewport;
+pub use wgpu_viewport::*;
+
 mod button;
 pub use button::*;
 
@@ -3,16 +3,24 @@ use floem_renderer::Renderer;
 use peniko::kurbo::{Point, Rect, Size, Vec2};
 use peniko::{Brush, Color};
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+#[cfg(target_arch = "wasm32")]
+use web_time::{Duration, Instant};
+
 use crate::style::CustomStylable;
 use crate::unit::PxPct;
 use crate::{
+    action::{exec_after, TimerToken},
     app_state::AppState,
     context::{ComputeLayoutCx, PaintCx},
     event::{Event, EventPropagation},
+    gesture::DRAG_THRESHOLD,
     id::ViewId,
     prop, prop_extractor,
     style::{Background, BorderColor, BorderRadius, Style, StyleSelector},
     style_class,
+    touch::TouchId,
     unit::Px,
     view::{IntoView, View},
 };
@@ -25,12 +33,42 @@ enum ScrollState {
     ScrollTo(Point),
     ScrollToPercent(f32),
     ScrollToView(ViewId),
+    /// Moves the content for the next frame of a fling.
+    FlingStep,
 }
 
 /// Minimum length for any scrollbar to be when measured on that
 /// scrollbar's primary axis.
 const SCROLLBAR_MIN_SIZE: f64 = 10.0;
 
+/// The time between two steps of a fling.
+const FLING_FRAME: Duration = Duration::from_millis(16);
+/// The fraction of its velocity a fling keeps after one sixtieth of a second.
+const FLING_FRICTION: f64 = 0.95;
+/// The speed in pixels per second under which a fling stops.
+const FLING_MIN_SPEED: f64 = 30.0;
+/// A finger that rests longer than this before it is lifted doesn't fling the content.
+const FLING_MAX_REST: Duration = Duration::from_millis(100);
+
+/// A finger panning the content.
+struct TouchPan {
+    id: TouchId,
+    /// Whether the finger moved far enough to pan and is now received by the scroll view.
+    /// Until then, the views under the finger can claim it for their own gestures.
+    claimed: bool,
+    last_pos: Point,
+    last_move: Instant,
+    /// The velocity of the content in pixels per second.
+    velocity: Vec2,
+}
+
+/// The content moving on after a touch pan, slowing down until it stops.
+struct Fling {
+    velocity: Vec2,
+    last_step: Instant,
+    timer: TimerToken,
+}
+
 /// Denotes which scrollbar, if any, is currently being dragged.
 #[derive(Debug, Copy, Clone)]
 enum BarHeldState {
@@ -103,6 +141,8 @@ pub struct Scroll {
 
     onscroll: Option<Box<dyn Fn(Rect)>>,
     held: BarHeldState,
+    touch_pan: Option<TouchPan>,
+    fling: Option<Fling>,
     v_handle_hover: bool,
     h_handle_hover: bool,
     v_track_hover: bool,
@@ -131,6 +171,8 @@ pub fn scroll<V: IntoView + 'static>(child: V) -> Scroll {
         computed_child_viewport: Rect::ZERO,
         onscroll: None,
         held: BarHeldState::None,
+        touch_pan: None,
+        fling: None,
         v_handle_hover: false,
         h_handle_hover: false,
         v_track_hover: false,
@@ -212,6 +254,54 @@ impl Scroll {
         self.clamp_child_viewport(app_state, self.child_viewport.with_origin(origin));
     }
 
+    /// Whether the content is larger than the viewport horizontally and vertically, so touches
+    /// can pan it.
+    fn can_pan(&self) -> (bool, bool) {
+        (
+            self.child_size.width > self.content_rect.width(),
+            self.child_size.height > self.content_rect.height(),
+        )
+    }
+
+    fn stop_fling(&mut self) {
+        if let Some(fling) = self.fling.take() {
+            fling.timer.cancel();
+        }
+    }
+
+    fn schedule_fling_step(&self) -> TimerToken {
+        let id = self.id;
+        exec_after(FLING_FRAME, move |_| {
+            id.update_state(ScrollState::FlingStep);
+        })
+    }
+
+    fn fling_step(&mut self, app_state: &mut AppState) {
+        let Some(fling) = self.fling.as_mut() else {
+            return;
+        };
+        let now = Instant::now();
+        let seconds = now.duration_since(fling.last_step).as_secs_f64();
+        fling.last_step = now;
+        fling.velocity *= FLING_FRICTION.powf(seconds * 60.0);
+        let delta = fling.velocity * seconds;
+        let moving = fling.velocity.hypot() >= FLING_MIN_SPEED;
+
+        if moving
+            && self
+                .clamp_child_viewport(app_state, self.child_viewport + delta)
+                .is_some()
+        {
+            let timer = self.schedule_fling_step();
+            if let Some(fling) = self.fling.as_mut() {
+                fling.timer = timer;
+            }
+        } else {
+            // Stopped by friction or by reaching the edge of the content.
+            self.fling = None;
+        }
+    }
+
     /// Pan the smallest distance that makes the target [`Rect`] visible.
     ///
     /// If the target rect is larger than viewport size, we will prioritize
@@ -660,6 +750,9 @@ impl View for Scroll {
                 ScrollState::ScrollToView(id) => {
                     self.do_scroll_to_view(cx.app_state, id, None);
                 }
+                ScrollState::FlingStep => {
+                    self.fling_step(cx.app_state);
+                }
             }
             self.id.request_layout();
         }
@@ -824,7 +917,81 @@ impl View for Scroll {
         cx: &mut crate::context::EventCx,
         event: &Event,
     ) -> EventPropagation {
+        match &event {
+            Event::TouchStart(touch)
+                if self.touch_pan.as_ref().map_or(true, |pan| !pan.claimed)
+                    && self.can_pan() != (false, false) =>
+            {
+                self.stop_fling();
+                // The views under the finger get the chance to claim it before it moves far
+                // enough to pan.
+                self.touch_pan = Some(TouchPan {
+                    id: touch.id,
+                    claimed: false,
+                    last_pos: touch.pos,
+                    last_move: Instant::now(),
+                    velocity: Vec2::ZERO,
+                });
+            }
+            Event::TouchMove(touch) => {
+                let can_pan = self.can_pan();
+                if let Some(pan) = self.touch_pan.as_mut().filter(|pan| pan.id == touch.id) {
+                    // The content follows the finger, so the viewport moves the other way.
+                    let delta = pan.last_pos - touch.pos;
+                    if !pan.claimed {
+                        let (pan_x, pan_y) = can_pan;
+                        if !((pan_x && delta.x.abs() > DRAG_THRESHOLD)
+                            || (pan_y && delta.y.abs() > DRAG_THRESHOLD))
+                        {
+                            return EventPropagation::Continue;
+                        }
+                        // Receive the rest of the finger, even once it leaves the scroll view.
+                        pan.claimed = true;
+                        cx.app_state.touch_targets.insert(touch.id, self.id);
+                    }
+                    let now = Instant::now();
+                    let seconds = now.duration_since(pan.last_move).as_secs_f64();
+                    if seconds > 0.0 {
+                        // Keep some of the previous estimate to smooth out jittery positions.
+                        pan.velocity = delta / seconds * 0.8 + pan.velocity * 0.2;
+                    }
+                    pan.last_pos = touch.pos;
+                    pan.last_move = now;
+                    self.clamp_child_viewport(cx.app_state, self.child_viewport + delta);
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::TouchEnd(touch) => {
+                if let Some(pan) = self.touch_pan.take_if(|pan| pan.id == touch.id) {
+                    if !pan.claimed {
+                        return EventPropagation::Continue;
+                    }
+                    if pan.last_move.elapsed() <= FLING_MAX_REST
+                        && pan.velocity.hypot() >= FLING_MIN_SPEED
+                    {
+                        self.fling = Some(Fling {
+                            velocity: pan.velocity,
+                            last_step: Instant::now(),
+                            timer: self.schedule_fling_step(),
+                        });
+                    }
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::TouchCancel(touch) => {
+                if self
+                    .touch_pan
+                    .take_if(|pan| pan.id == touch.id)
+                    .is_some_and(|pan| pan.claimed)
+                {
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+
         if let Event::PointerWheel(pointer_event) = &event {
+            self.stop_fling();
             if let Some(listener) = event.listener() {
                 if self
                     .id
@@ -1029,3 +1196,38 @@ impl<T: IntoView + 'static> ScrollExt for T {
         scroll(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet, SignalUpdate};
+
+    use crate::{
+        headless::test_window,
+        views::{empty, v_stack, Decorators},
+    };
+
+    use super::scroll;
+
+    #[test]
+    fn touch_pans_scroll_outside_of_it() {
+        let offset = create_rw_signal(0.0);
+        let mut window = test_window((100.0, 200.0), move || {
+            v_stack((
+                empty().style(|s| s.size(100.0, 100.0)),
+                scroll(empty().style(|s| s.size(100.0, 400.0)))
+                    .on_scroll(move |viewport| offset.set(viewport.y0))
+                    .style(|s| s.size(100.0, 100.0)),
+            ))
+        });
+
+        window.touch_start(1, (50.0, 190.0));
+        window.touch_move(1, (50.0, 170.0));
+        assert_eq!(offset.get_untracked(), 20.0);
+
+        // The scroll view claimed the touch once it moved, so it keeps panning once the finger
+        // leaves it.
+        window.touch_move(1, (50.0, 50.0));
+        assert_eq!(offset.get_untracked(), 140.0);
+        window.touch_end(1, (50.0, 50.0));
+    }
+}
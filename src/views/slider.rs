@@ -478,3 +478,30 @@ impl SliderCustomStyle {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet};
+    use floem_winit::keyboard::{Key, NamedKey};
+
+    use crate::{headless::test_window, views::Decorators};
+
+    use super::Slider;
+
+    #[test]
+    fn clicks_and_arrow_keys_move_the_slider() {
+        let percent = create_rw_signal(50.0);
+        let mut window = test_window((100.0, 20.0), move || {
+            Slider::new_get_update(percent).style(|s| s.size(100.0, 20.0))
+        });
+
+        window.click((20.0, 10.0));
+        assert_eq!(percent.get_untracked(), 20.0);
+
+        window.key_press(Key::Named(NamedKey::ArrowRight));
+        assert_eq!(percent.get_untracked(), 30.0);
+        window.key_press(Key::Named(NamedKey::ArrowLeft));
+        window.key_press(Key::Named(NamedKey::ArrowLeft));
+        assert_eq!(percent.get_untracked(), 10.0);
+    }
+}
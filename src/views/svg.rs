@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use floem_reactive::create_effect;
 use floem_renderer::{
     text::FONT_SYSTEM,
@@ -17,11 +19,45 @@ prop_extractor!(SvgProps {
     text_color: TextColor,
 });
 
+/// An SVG document that has been parsed once, and can be shared by any number of [`svg`] views.
+#[derive(Clone)]
+pub struct ParsedSvg {
+    tree: Rc<Tree>,
+    hash: Rc<[u8]>,
+}
+
+impl ParsedSvg {
+    pub fn parse(svg_str: &str) -> Result<Self, usvg::Error> {
+        let tree = {
+            let font_system = FONT_SYSTEM.lock();
+            Tree::from_str(svg_str, &usvg::Options::default(), font_system.db())?
+        };
+
+        let mut hasher = Sha256::new();
+        hasher.update(svg_str);
+        let hash = hasher.finalize().to_vec();
+
+        Ok(Self {
+            tree: Rc::new(tree),
+            hash: hash.into(),
+        })
+    }
+
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    /// The size of the document, as declared by its `width` and `height`.
+    pub fn size(&self) -> Size {
+        let size = self.tree.size();
+        Size::new(size.width() as f64, size.height() as f64)
+    }
+}
+
 pub struct Svg {
     id: ViewId,
     props: SvgProps,
-    svg_tree: Option<Tree>,
-    svg_hash: Option<Vec<u8>>,
+    svg: Option<ParsedSvg>,
 }
 
 impl Svg {
@@ -33,6 +69,15 @@ impl Svg {
         });
         self
     }
+
+    /// Reactively replaces the document with one that has already been parsed.
+    pub fn update_parsed(self, svg: impl Fn() -> ParsedSvg + 'static) -> Self {
+        let id = self.id;
+        create_effect(move |_| {
+            id.update_state(svg());
+        });
+        self
+    }
 }
 
 pub fn svg(svg_str: impl Into<String> + 'static) -> Svg {
@@ -40,8 +85,17 @@ pub fn svg(svg_str: impl Into<String> + 'static) -> Svg {
     id.update_state(svg_str.into());
     Svg {
         id,
-        svg_tree: None,
-        svg_hash: None,
+        svg: None,
+        props: Default::default(),
+    }
+    .class(SvgClass)
+}
+
+/// Displays an SVG document that has already been parsed, without parsing it again.
+pub fn parsed_svg(svg: ParsedSvg) -> Svg {
+    Svg {
+        id: ViewId::new(),
+        svg: Some(svg),
         props: Default::default(),
     }
     .class(SvgClass)
@@ -57,24 +111,23 @@ impl View for Svg {
     }
 
     fn update(&mut self, _cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
-        if let Ok(state) = state.downcast::<String>() {
-            let text = &*state;
-            let font_system = FONT_SYSTEM.lock();
-            let font_db = font_system.db();
-            self.svg_tree = Tree::from_str(text, &usvg::Options::default(), font_db).ok();
-
-            let mut hasher = Sha256::new();
-            hasher.update(text);
-            let hash = hasher.finalize().to_vec();
-            self.svg_hash = Some(hash);
-
+        let state = match state.downcast::<String>() {
+            Ok(text) => {
+                self.svg = ParsedSvg::parse(&text).ok();
+                self.id.request_layout();
+                return;
+            }
+            Err(state) => state,
+        };
+        if let Ok(svg) = state.downcast::<ParsedSvg>() {
+            self.svg = Some(*svg);
             self.id.request_layout();
         }
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
-        if let Some(tree) = self.svg_tree.as_ref() {
-            let hash = self.svg_hash.as_ref().unwrap();
+        if let Some(svg) = self.svg.as_ref() {
+            let (tree, hash) = (&*svg.tree, &*svg.hash);
             let layout = self.id.get_layout().unwrap_or_default();
             let rect = Size::new(layout.size.width as f64, layout.size.height as f64).to_rect();
             let color = self.props.text_color();
@@ -1048,6 +1048,9 @@ impl View for TextInput {
                 false
             }
             Event::KeyDown(event) => self.handle_key_down(cx, event),
+            Event::ImeCommit(text) if cx.app_state.is_focused(&self.id) => {
+                self.insert_text(&text.as_str().into())
+            }
             _ => false,
         };
 
@@ -1252,9 +1255,30 @@ impl View for TextInput {
 
 #[cfg(test)]
 mod tests {
+    use floem_reactive::{create_rw_signal, SignalGet};
+    use floem_winit::keyboard::{Key, NamedKey};
+
+    use crate::headless::test_window;
     use crate::views::text_input::get_dbl_click_selection;
+    use crate::views::Decorators;
+
+    use super::{replace_range, text_input};
+
+    #[test]
+    fn keys_and_ime_commits_edit_the_focused_input() {
+        let text = create_rw_signal(String::new());
+        let mut window = test_window((100.0, 30.0), move || {
+            text_input(text).style(|s| s.size_full())
+        });
 
-    use super::replace_range;
+        window.click((10.0, 15.0));
+        window.type_text("hi there");
+        assert_eq!(text.get_untracked(), "hi there");
+
+        window.key_press(Key::Named(NamedKey::Backspace));
+        window.ime_commit("é");
+        assert_eq!(text.get_untracked(), "hi theré");
+    }
 
     #[test]
     fn replace_range_start() {
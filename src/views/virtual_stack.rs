@@ -10,6 +10,7 @@ use taffy::{style::Dimension, tree::NodeId};
 
 use crate::{
     context::ComputeLayoutCx,
+    drag::Reorder,
     id::ViewId,
     style::Style,
     view::{self, IntoView, View},
@@ -66,10 +67,13 @@ where
     before_size: f64,
     content_size: f64,
     before_node: Option<NodeId>,
+    reorder: Option<Reorder>,
 }
 
 struct VirtualStackState<T> {
     diff: Diff<T>,
+    /// The index of the first loaded item in the whole list.
+    first_index: usize,
     before_size: f64,
     content_size: f64,
 }
@@ -138,6 +142,7 @@ where
         };
         let mut items = Vec::new();
 
+        let mut first_index = 0;
         let mut before_size = 0.0;
         let mut content_size = 0.0;
         match &item_size {
@@ -154,7 +159,8 @@ where
                 } else {
                     usize::MAX
                 };
-                before_size = item_size * (start.min(total_len)) as f64;
+                first_index = start.min(total_len);
+                before_size = item_size * first_index as f64;
 
                 for item in items_vector.slice(start..end) {
                     items.push(item);
@@ -171,6 +177,7 @@ where
                     if main_axis + item_size < min {
                         main_axis += item_size;
                         before_size += item_size;
+                        first_index += 1;
                         continue;
                     }
 
@@ -209,6 +216,7 @@ where
         {
             id.update_state(VirtualStackState {
                 diff,
+                first_index,
                 before_size,
                 content_size,
             });
@@ -229,6 +237,18 @@ where
         before_size: 0.0,
         content_size: 0.0,
         before_node: None,
+        reorder: None,
+    }
+}
+
+impl<T> VirtualStack<T> {
+    /// Lets the items be reordered by dragging one onto another, like
+    /// [`DynStack::reorderable`](super::DynStack::reorderable).
+    ///
+    /// The indices given to `on_reorder` are in the whole list, not only the loaded items.
+    pub fn reorderable(mut self, on_reorder: impl Fn(usize, usize) + 'static) -> Self {
+        self.reorder = Some(Reorder::new(on_reorder));
+        self
     }
 }
 
@@ -258,6 +278,10 @@ impl<T> View for VirtualStack<T> {
                 &mut self.children,
                 &self.view_fn,
             );
+            if let Some(reorder) = &self.reorder {
+                reorder.set_first_index(state.first_index);
+                reorder.attach(self.id);
+            }
             self.id.request_all();
         }
     }
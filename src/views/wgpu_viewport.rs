@@ -0,0 +1,218 @@
+use floem_renderer::Renderer;
+use peniko::kurbo::Size;
+
+use crate::{context::PaintCx, id::ViewId, view::View};
+
+/// The target of one frame of a [`wgpu_viewport`].
+pub struct ViewportFrame<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    /// Commands recorded here are submitted after the render callback returns,
+    /// before the window's frame.
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    /// The color target. It keeps its contents between frames.
+    pub color: &'a wgpu::TextureView,
+    /// The depth target, if the viewport was created with [`WgpuViewport::depth`].
+    pub depth: Option<&'a wgpu::TextureView>,
+    pub format: wgpu::TextureFormat,
+    pub depth_format: Option<wgpu::TextureFormat>,
+    /// The size of the targets in physical pixels.
+    pub width: u32,
+    pub height: u32,
+    /// The scale factor of the window.
+    pub scale: f64,
+}
+
+struct Targets {
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    color: std::sync::Arc<wgpu::TextureView>,
+    depth: Option<wgpu::TextureView>,
+}
+
+/// A view rendering a wgpu scene into a texture sized to its content rect. See [`wgpu_viewport`].
+pub struct WgpuViewport {
+    id: ViewId,
+    render: Box<dyn FnMut(&mut ViewportFrame)>,
+    /// The format of the color texture, or `None` for the format of the window's frames.
+    format: Option<wgpu::TextureFormat>,
+    depth_format: Option<wgpu::TextureFormat>,
+    continuous: bool,
+    targets: Option<Targets>,
+}
+
+/// A view that renders a wgpu scene inside the layout.
+///
+/// The view owns a color texture, and optionally a depth texture, matching the physical size
+/// of its content rect. Each time the view is painted, `render` is called to record commands
+/// drawing into them, and the color texture is then drawn like any other content of the view,
+/// so it is clipped, moved by scrolling and transformed along with it.
+///
+/// The texture is only drawn when floem renders with wgpu. Call `request_paint` on the view's
+/// id to render a new frame, or make the viewport [`continuous`](WgpuViewport::continuous).
+/// The color texture has the format of the window's frames unless it is set with
+/// [`WgpuViewport::format`].
+///
+/// ```rust,no_run
+/// use floem::views::{wgpu_viewport, Decorators};
+///
+/// wgpu_viewport(|frame| {
+///     let _pass = frame.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+///         label: Some("scene"),
+///         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+///             view: frame.color,
+///             resolve_target: None,
+///             ops: wgpu::Operations {
+///                 load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+///                 store: wgpu::StoreOp::Store,
+///             },
+///         })],
+///         depth_stencil_attachment: None,
+///         timestamp_writes: None,
+///         occlusion_query_set: None,
+///     });
+/// })
+/// .style(|s| s.flex_grow(1.0));
+/// ```
+pub fn wgpu_viewport(render: impl FnMut(&mut ViewportFrame) + 'static) -> WgpuViewport {
+    WgpuViewport {
+        id: ViewId::new(),
+        render: Box::new(render),
+        format: None,
+        depth_format: None,
+        continuous: false,
+        targets: None,
+    }
+}
+
+impl WgpuViewport {
+    /// Sets the format of the color texture. It must be filterable.
+    /// The default is the format of the window's frames, see
+    /// [`RendererConfig::format`](crate::window::RendererConfig::format).
+    pub fn format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = Some(format);
+        self.targets = None;
+        self
+    }
+
+    /// Adds a depth texture with the given format.
+    pub fn depth(mut self, format: wgpu::TextureFormat) -> Self {
+        self.depth_format = Some(format);
+        self.targets = None;
+        self
+    }
+
+    /// Renders a new frame every time the window is presented instead of only when the view
+    /// is repainted. The window keeps rendering frames at the pace of its
+    /// [`FramePacing`](crate::window::FramePacing) while a continuous viewport is painted.
+    pub fn continuous(mut self, continuous: bool) -> Self {
+        self.continuous = continuous;
+        self
+    }
+
+    fn update_targets(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) {
+        let stale = self.targets.as_ref().map_or(true, |targets| {
+            targets.width != width || targets.height != height || targets.format != format
+        });
+        if stale {
+            let texture = |label, format, usage| {
+                device
+                    .create_texture(&wgpu::TextureDescriptor {
+                        label: Some(label),
+                        size: wgpu::Extent3d {
+                            width,
+                            height,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format,
+                        usage,
+                        view_formats: &[],
+                    })
+                    .create_view(&wgpu::TextureViewDescriptor::default())
+            };
+            self.targets = Some(Targets {
+                width,
+                height,
+                format,
+                color: std::sync::Arc::new(texture(
+                    "Viewport color texture",
+                    format,
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                )),
+                depth: self.depth_format.map(|format| {
+                    texture(
+                        "Viewport depth texture",
+                        format,
+                        wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    )
+                }),
+            });
+        }
+    }
+}
+
+impl View for WgpuViewport {
+    fn id(&self) -> ViewId {
+        self.id
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Wgpu Viewport".into()
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let (Some(gpu_resources), Some(frame_format)) =
+            (cx.gpu_resources().cloned(), cx.frame_format())
+        else {
+            return;
+        };
+        let format = self.format.unwrap_or(frame_format.format);
+        let rect = self.id.get_content_rect();
+        let scale = cx.scale();
+        let size = Size::new(rect.width() * scale, rect.height() * scale).round();
+        if size.width < 1.0 || size.height < 1.0 {
+            return;
+        }
+        let (width, height) = (size.width as u32, size.height as u32);
+
+        let device = &gpu_resources.device;
+        self.update_targets(device, format, width, height);
+        let targets = self.targets.as_ref().unwrap();
+        let color = targets.color.clone();
+        let depth = targets.depth.as_ref();
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Viewport encoder"),
+        });
+        let mut frame = ViewportFrame {
+            device,
+            queue: &gpu_resources.queue,
+            encoder: &mut encoder,
+            color: &color,
+            depth,
+            format,
+            depth_format: self.depth_format,
+            width,
+            height,
+            scale,
+        };
+        (self.render)(&mut frame);
+        gpu_resources.queue.submit(Some(encoder.finish()));
+
+        cx.draw_texture(&color, rect);
+
+        if self.continuous {
+            cx.request_next_frame();
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub use floem_renderer::config::{BackendPreference, RendererConfig};
 pub use floem_winit::window::Fullscreen;
 pub use floem_winit::window::Icon;
 pub use floem_winit::window::ResizeDirection;
@@ -8,6 +9,7 @@ pub use floem_winit::window::WindowLevel;
 use peniko::kurbo::{Point, Size};
 
 use crate::app::{add_app_update_event, AppUpdateEvent};
+use crate::theme::WindowTheme;
 use crate::view::IntoView;
 
 /// Configures various attributes (e.g. size, position, transparency, etc.) of a window.
@@ -25,7 +27,10 @@ pub struct WindowConfig {
     pub undecorated: bool,
     pub window_level: WindowLevel,
     pub apply_default_theme: bool,
+    pub theme: Option<WindowTheme>,
     pub font_embolden: f32,
+    pub frame_pacing: FramePacing,
+    pub renderer: RendererConfig,
     #[allow(dead_code)]
     pub mac_os_config: Option<MacOSWindowConfig>,
     pub web_config: Option<WebWindowConfig>,
@@ -46,7 +51,10 @@ impl Default for WindowConfig {
             undecorated: false,
             window_level: WindowLevel::Normal,
             apply_default_theme: true,
+            theme: None,
             font_embolden: if cfg!(target_os = "macos") { 0.2 } else { 0. },
+            frame_pacing: FramePacing::default(),
+            renderer: RendererConfig::default(),
             mac_os_config: None,
             web_config: None,
         }
@@ -164,6 +172,18 @@ impl WindowConfig {
         self
     }
 
+    /// Sets the theme of the window, see [`Theme`](crate::theme::Theme).
+    ///
+    /// This takes precedence over [`apply_default_theme`](Self::apply_default_theme).
+    /// Pass [`WindowTheme::system()`] to follow the OS between the light and dark themes.
+    ///
+    /// The default is [`Theme::light`](crate::theme::Theme::light).
+    #[inline]
+    pub fn theme(mut self, theme: impl Into<WindowTheme>) -> Self {
+        self.theme = Some(theme.into());
+        self
+    }
+
     /// Sets the amount by which fonts are emboldened.
     ///
     /// The default is 0.0 except for on macOS where the default is 0.2
@@ -173,6 +193,27 @@ impl WindowConfig {
         self
     }
 
+    /// Sets when the window renders new frames, see [`FramePacing`].
+    ///
+    /// The default is [`FramePacing::OnDemand`].
+    #[inline]
+    pub fn frame_pacing(mut self, frame_pacing: FramePacing) -> Self {
+        self.frame_pacing = frame_pacing;
+        self
+    }
+
+    /// Sets the renderer the window is drawn with, and the surface format and present mode
+    /// it renders with, see [`RendererConfig`].
+    ///
+    /// If the renderer can't be created, for example because the surface doesn't support the
+    /// configuration, the application panics with the reason unless it has an
+    /// [`on_renderer_error`](crate::Application::on_renderer_error) hook.
+    #[inline]
+    pub fn renderer(mut self, renderer: RendererConfig) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
     /// Set up Mac-OS specific configuration.  The passed closure will only be
     /// called on macOS.
     #[allow(unused_variables, unused_mut)] // build will complain on non-macOS's otherwise
@@ -207,6 +248,27 @@ impl WindowConfig {
     }
 }
 
+/// When a window renders new frames.
+///
+/// The pacing of a window can be changed while it is open with
+/// [`set_frame_pacing`](crate::action::set_frame_pacing).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FramePacing {
+    /// Renders a frame only when something changed: a view requested a repaint, an animation
+    /// is running or the [engine](crate::engine) needs to be redrawn.
+    #[default]
+    OnDemand,
+    /// Renders frames continuously, at most `fps` frames per second.
+    Continuous { fps: f64 },
+    /// Renders a new frame as soon as the previous one was presented, which ties the frame
+    /// rate to the refresh rate of the display.
+    ///
+    /// When the surface doesn't present in a FIFO mode, or the window renders without wgpu,
+    /// presenting doesn't wait for vsync, so frames are rendered at the refresh rate of the
+    /// monitor instead.
+    VSync,
+}
+
 /// Mac-OS specific window configuration properties, accessible via `WindowConfig::with_mac_os_config( FnMut( MacOsWindowConfig ) )`
 ///
 /// See [the winit docs](https://docs.rs/winit/latest/winit/platform/macos/trait.WindowExtMacOS.html) for further
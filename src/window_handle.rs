@@ -1,10 +1,13 @@
 use std::{
-    any::Any,
     cell::RefCell,
+    collections::HashSet,
     mem,
     path::PathBuf,
     rc::Rc,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -13,11 +16,12 @@ use std::time::{Duration, Instant};
 use web_time::{Duration, Instant};
 
 use floem_reactive::{with_scope, RwSignal, Scope, SignalGet, SignalUpdate};
-use floem_renderer::gpu_resources::{self, GpuResources};
+use floem_renderer::config::RendererConfig;
+use floem_renderer::gpu_resources::{DeviceConfig, GpuResources};
 use floem_renderer::Renderer;
 use floem_winit::{
-    dpi::{LogicalPosition, LogicalSize, PhysicalSize},
-    event::{ElementState, Ime, MouseButton, MouseScrollDelta},
+    dpi::{LogicalPosition, LogicalSize},
+    event::{ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase},
     event_loop::EventLoopProxy,
     keyboard::{Key, ModifiersState, NamedKey},
     window::{CursorIcon, WindowId},
@@ -25,6 +29,8 @@ use floem_winit::{
 use image::DynamicImage;
 use peniko::kurbo::{Affine, Point, Rect, Size, Vec2};
 
+#[cfg(feature = "engine")]
+use crate::engine::{Engine, EngineFactory};
 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
 use crate::reactive::SignalWith;
 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
@@ -32,22 +38,28 @@ use crate::unit::UnitExt;
 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
 use crate::views::{container, stack};
 use crate::{
+    action::{exec_after, TimerToken},
     app::UserEvent,
     app_state::AppState,
     context::{
         ComputeLayoutCx, EventCx, FrameUpdate, LayoutCx, PaintCx, PaintState, StyleCx, UpdateCx,
     },
-    dropped_file::DroppedFileEvent,
-    event::{Event, EventListener},
+    dropped_file::{DroppedFileEvent, FileDragEvent},
+    event::{Event, EventListener, EventPropagation},
     id::ViewId,
     inspector::{self, Capture, CaptureState, CapturedView},
-    keyboard::{KeyEvent, Modifiers},
+    keyboard::{KeyEvent, KeyInfo, Modifiers},
     menu::Menu,
     nav::view_arrow_navigation,
-    pointer::{PointerButton, PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
+    pointer::{PointerButton, PointerInputEvent, PointerMoveEvent, PointerType, PointerWheelEvent},
     profiler::Profile,
+    renderer::RendererError,
     style::{CursorStyle, Style, StyleSelector},
-    theme::{default_theme, Theme},
+    theme::WindowTheme,
+    touch::{
+        DoubleTapZoomEvent, PinchEvent, RotateEvent, TouchEvent, TouchTracker,
+        TouchpadPressureEvent,
+    },
     update::{
         UpdateMessage, CENTRAL_DEFERRED_UPDATE_MESSAGES, CENTRAL_UPDATE_MESSAGES,
         CURRENT_RUNNING_VIEW_HANDLE, DEFERRED_UPDATE_MESSAGES, UPDATE_MESSAGES,
@@ -55,23 +67,24 @@ use crate::{
     view::{default_compute_layout, view_tab_navigation, IntoView, View},
     view_state::ChangeFlags,
     views::Decorators,
-    window_tracking::{remove_window_id_mapping, store_window_id_mapping},
+    window::FramePacing,
+    window_tracking::{remove_window_id_mapping, store_renderer_info, store_window_id_mapping},
 };
 
-pub type CustomRenderCallback = Box<dyn for<'a> Fn(&'a RefCell<&WindowHandle>) + 'static>;
-
 #[derive(Debug, Clone, Copy)]
 pub struct WindowSize {
     pub width: u32,
     pub height: u32,
 }
 
-pub struct EngineHandle {
-    // pub user_editor: Option<Box<dyn Any + Send + Sync>>, // all
-    pub user_editor: Option<Box<dyn Any>>, // all
-    pub render_pipeline: Option<wgpu::RenderPipeline>,
-    pub depth_view: Option<wgpu::TextureView>,
-    pub gpu_helper: Option<Arc<Mutex<GpuHelper>>>,
+/// Files dragged over a window from another application.
+struct FileDrag {
+    /// The platform reports the files one at a time, and they are given to views together
+    /// once it is done and the cursor position is known, see [`WindowHandle::flush_file_drag`].
+    paths: Vec<PathBuf>,
+    /// Whether views haven't received `paths` yet.
+    pending: bool,
+    dropped: bool,
 }
 
 /// The top-level window handle that owns the winit Window.
@@ -90,7 +103,9 @@ pub struct WindowHandle {
     app_state: AppState,
     pub paint_state: PaintState,
     size: RwSignal<Size>,
-    theme: Option<Theme>,
+    theme: Option<WindowTheme>,
+    /// The stylesheet generated from `theme` for the current OS theme.
+    theme_style: Option<Rc<Style>>,
     pub(crate) profile: Option<Profile>,
     os_theme: RwSignal<Option<floem_winit::window::Theme>>,
     is_maximized: bool,
@@ -98,55 +113,49 @@ pub struct WindowHandle {
     pub(crate) scale: f64,
     pub(crate) modifiers: Modifiers,
     pub cursor_position: Point,
+    /// Whether the pointer moved in the window since it last left it, so `cursor_position` is
+    /// where it is.
+    cursor_in_window: bool,
     pub window_position: Point,
     pub(crate) last_pointer_down: Option<(u8, Point, Instant)>,
+    /// The mouse buttons currently pressed.
+    pressed_buttons: HashSet<PointerButton>,
+    touches: TouchTracker,
     #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     pub(crate) context_menu: RwSignal<Option<(Menu, Point)>>,
-    dropper_file: Option<PathBuf>,
-    pub render_callback: Option<CustomRenderCallback>,
-    pub encode_callback: Option<
-        Box<
-            dyn for<'a> Fn(
-                    wgpu::CommandEncoder,
-                    wgpu::SurfaceTexture,
-                    Arc<wgpu::TextureView>,
-                    Arc<wgpu::TextureView>,
-                    // &'a WindowHandle,
-                    &Arc<GpuResources>,
-                    &EngineHandle,
-                ) -> (
-                    Option<wgpu::CommandEncoder>,
-                    Option<wgpu::SurfaceTexture>,
-                    Option<Arc<wgpu::TextureView>>,
-                    Option<Arc<wgpu::TextureView>>,
-                ) + 'static,
-        >,
-    >,
+    file_drag: Option<FileDrag>,
+    /// The GPU resources the window renders with. If set before the renderer is initialized,
+    /// they are used instead of the ones requested for the window.
     pub gpu_resources: Option<Arc<GpuResources>>,
     // pub user_editor: Option<Arc<Mutex<Editor>>>, // vector
     // pub user_engine: Option<Arc<Mutex<RendererState>>>, // engine
     // pub user_editor: Option<Arc<Mutex<dyn Any + Send + Sync>>>, // all
     // pub window_size: Option<WindowSize>,
+    /// The size of the surface to create, in physical pixels.
+    /// Defaults to the inner size of the window.
     pub window_width: Option<u32>,
     pub window_height: Option<u32>,
-    pub handle_mouse_input: Option<Box<dyn Fn(MouseButton, ElementState)>>,
-    pub handle_cursor_moved: Option<Box<dyn Fn(f64, f64, f64, f64)>>,
-    pub handle_window_resized: Option<Box<dyn FnMut(PhysicalSize<u32>, LogicalSize<f64>)>>,
-    pub handle_mouse_wheel: Option<Box<dyn FnMut(MouseScrollDelta)>>,
-    pub handle_keyboard_input: Option<Box<dyn FnMut(floem_winit::event::KeyEvent)>>,
-    pub handle_modifiers_changed: Option<Box<dyn FnMut(floem_winit::event::Modifiers)>>,
-    pub engine_handle: Option<EngineHandle>,
+    /// The scene rendered under the UI, created once the renderer is initialized.
+    #[cfg(feature = "engine")]
+    engine: Option<Box<dyn Engine>>,
+    frame_pacing: FramePacing,
+    /// The timer requesting the next frame with [`FramePacing::Continuous`].
+    frame_timer: Option<TimerToken>,
+    /// When the last frame started rendering.
+    last_frame: Option<Instant>,
 }
 
 impl WindowHandle {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         window: floem_winit::window::Window,
-        event_proxy: EventLoopProxy<UserEvent>,
         view_fn: impl FnOnce(floem_winit::window::WindowId) -> Box<dyn View> + 'static,
         transparent: bool,
-        apply_default_theme: bool,
+        theme: Option<WindowTheme>,
         size: Option<LogicalSize<f64>>,
         font_embolden: f32,
+        frame_pacing: FramePacing,
+        renderer_config: RendererConfig,
     ) -> Self {
         let scope = Scope::new();
         let window_id = window.id();
@@ -155,7 +164,7 @@ impl WindowHandle {
         let size: LogicalSize<f64> = size.unwrap_or(window.inner_size().to_logical(scale));
         let size = Size::new(size.width, size.height);
         let size = scope.create_rw_signal(Size::new(size.width, size.height));
-        let theme = scope.create_rw_signal(window.theme());
+        let os_theme = scope.create_rw_signal(window.theme());
         let is_maximized = window.is_maximized();
 
         set_current_view(id);
@@ -185,20 +194,12 @@ impl WindowHandle {
 
         let window = Arc::new(window);
         store_window_id_mapping(id, window_id, &window);
-        let gpu_resources = GpuResources::request(
-            move |window_id| {
-                event_proxy
-                    .send_event(UserEvent::GpuResourcesUpdate { window_id })
-                    .unwrap();
-            },
-            window.clone(),
-        );
         let paint_state = PaintState::new(
             window.clone(),
-            gpu_resources,
             scale,
             size.get_untracked() * scale,
             font_embolden,
+            renderer_config,
         );
         let mut window_handle = Self {
             window: Some(window),
@@ -209,57 +210,192 @@ impl WindowHandle {
             app_state: AppState::new(id),
             paint_state,
             size,
-            theme: apply_default_theme.then(default_theme),
-            os_theme: theme,
+            theme_style: theme_style(theme.as_ref(), os_theme.get_untracked()),
+            theme,
+            os_theme,
             is_maximized,
             transparent,
             profile: None,
             scale,
             modifiers: Modifiers::default(),
             cursor_position: Point::ZERO,
+            cursor_in_window: false,
             window_position: Point::ZERO,
             #[cfg(any(target_os = "linux", target_os = "freebsd"))]
             context_menu,
             last_pointer_down: None,
-            dropper_file: None,
-            render_callback: None,
+            pressed_buttons: HashSet::new(),
+            touches: TouchTracker::default(),
+            file_drag: None,
             gpu_resources: None, // not the Reciever, but actual resources
             // user_editor: None,
             // // user_engine: None,
             // render_pipeline: None,
             // depth_view: None,
-            engine_handle: None,
             // window_size: None,
             window_height: None,
             window_width: None,
-            encode_callback: None,
-            handle_mouse_input: None,
-            handle_cursor_moved: None,
-            handle_window_resized: None,
-            handle_keyboard_input: None,
-            handle_modifiers_changed: None,
-            // gpu_helper: None,
-            handle_mouse_wheel: None,
+            #[cfg(feature = "engine")]
+            engine: None,
+            frame_pacing,
+            frame_timer: None,
+            last_frame: None,
         };
         window_handle.app_state.set_root_size(size.get_untracked());
-        if let Some(theme) = theme.get_untracked() {
+        if let Some(theme) = os_theme.get_untracked() {
             window_handle.event(Event::ThemeChanged(theme));
         }
         window_handle
     }
 
+    /// Creates a window handle that is not backed by a winit window.
+    /// It paints with a headless CPU renderer, so `paint` returns the rendered frame.
+    pub(crate) fn new_headless(
+        view_fn: impl FnOnce(floem_winit::window::WindowId) -> Box<dyn View> + 'static,
+        size: Size,
+        scale: f64,
+        theme: Option<WindowTheme>,
+        font_embolden: f32,
+    ) -> Self {
+        static NEXT_HEADLESS_ID: AtomicU64 = AtomicU64::new(0);
+
+        let scope = Scope::new();
+        // Count down from the top of the id space so headless ids never clash with real windows.
+        let window_id = WindowId::from(u64::MAX - NEXT_HEADLESS_ID.fetch_add(1, Ordering::Relaxed));
+        let id = ViewId::new();
+        let size = scope.create_rw_signal(size);
+        let os_theme = scope.create_rw_signal(None);
+
+        set_current_view(id);
+
+        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+        let context_menu = scope.create_rw_signal(None);
+
+        let widget = with_scope(scope, move || view_fn(window_id));
+        let main_id = widget.id();
+        id.set_children(vec![widget]);
+
+        let view = WindowView { id };
+        id.set_view(view.into_any());
+
+        let paint_state =
+            PaintState::new_headless(scale, size.get_untracked() * scale, font_embolden);
+        let mut window_handle = Self {
+            window: None,
+            window_id,
+            id,
+            main_view: main_id,
+            scope,
+            app_state: AppState::new(id),
+            paint_state,
+            size,
+            theme_style: theme_style(theme.as_ref(), os_theme.get_untracked()),
+            theme,
+            os_theme,
+            is_maximized: false,
+            transparent: false,
+            profile: None,
+            scale,
+            modifiers: Modifiers::default(),
+            cursor_position: Point::ZERO,
+            cursor_in_window: false,
+            window_position: Point::ZERO,
+            #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+            context_menu,
+            last_pointer_down: None,
+            pressed_buttons: HashSet::new(),
+            touches: TouchTracker::default(),
+            file_drag: None,
+            gpu_resources: None,
+            window_height: None,
+            window_width: None,
+            #[cfg(feature = "engine")]
+            engine: None,
+            frame_pacing: FramePacing::OnDemand,
+            frame_timer: None,
+            last_frame: None,
+        };
+        window_handle
+            .app_state
+            .update_screen_size_bp(size.get_untracked());
+        window_handle.app_state.set_root_size(size.get_untracked());
+        window_handle
+    }
+
+    pub(crate) fn app_state(&self) -> &AppState {
+        &self.app_state
+    }
+
+    pub(crate) fn main_view(&self) -> ViewId {
+        self.main_view
+    }
+
     // pub fn set_editor<T: Any + Send + Sync>(&mut self, editor: Arc<Mutex<T>>) {
     //     self.user_editor = Some(Arc::new(Mutex::new(editor)));
     // }
 
-    pub(crate) fn init_renderer(&mut self) {
+    /// Requests the GPU resources of the window, sharing the instance, adapter and device of
+    /// `shared` if they can present to it. `GpuResourcesUpdate` is sent once they are acquired.
+    pub(crate) fn request_gpu_resources(
+        &mut self,
+        event_proxy: EventLoopProxy<UserEvent>,
+        device_config: DeviceConfig,
+        shared: Option<&GpuResources>,
+    ) {
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        let request = GpuResources::request(
+            move |window_id| {
+                event_proxy
+                    .send_event(UserEvent::GpuResourcesUpdate { window_id })
+                    .unwrap();
+            },
+            window,
+            device_config,
+            shared,
+        );
+        self.paint_state.set_gpu_request(request);
+    }
+
+    pub(crate) fn init_renderer(
+        &mut self,
+        #[cfg(feature = "engine")] engine: Option<&EngineFactory>,
+    ) -> Result<(), RendererError> {
+        let inner_size = self
+            .window
+            .as_ref()
+            .map(|window| window.inner_size())
+            .unwrap_or_default();
         self.paint_state.init_renderer(
             self.gpu_resources.clone(),
             Some(WindowSize {
-                width: self.window_width.expect("Couldn't get window width"),
-                height: self.window_height.expect("Couldn't get window height"),
+                width: self.window_width.unwrap_or(inner_size.width),
+                height: self.window_height.unwrap_or(inner_size.height),
             }),
-        );
+        )?;
+        if let Some(info) = self.paint_state.renderer().info() {
+            store_renderer_info(self.window_id, info);
+        }
+        if self.gpu_resources.is_none() {
+            self.gpu_resources = self.paint_state.renderer().gpu_resources().cloned();
+        }
+        #[cfg(feature = "engine")]
+        if let (Some(engine), Some(gpu_resources), Some(frame_format)) = (
+            engine,
+            self.paint_state.renderer().gpu_resources(),
+            self.paint_state.renderer().frame_format(),
+        ) {
+            let size = self.paint_state.renderer().size();
+            self.engine = Some(engine(
+                gpu_resources,
+                WindowSize {
+                    width: size.width as u32,
+                    height: size.height as u32,
+                },
+                frame_format,
+            ));
+        }
         // On the web, we need to get the canvas size once. The size will be updated automatically
         // when the canvas element is resized subsequently. This is the correct place to do so
         // because the renderer is not initialized until now.
@@ -274,6 +410,7 @@ impl WindowHandle {
         }
         // Now that the renderer is initialized, draw the first frame
         self.render_frame();
+        Ok(())
     }
 
     pub fn event(&mut self, event: Event) {
@@ -295,6 +432,9 @@ impl WindowHandle {
             (None, None)
         };
 
+        let was_file_drag_over = matches!(&event, Event::FileDragOver(_))
+            .then(|| std::mem::take(&mut cx.app_state.file_drag_over));
+
         let is_pointer_down = matches!(&event, Event::PointerDown(_));
         let was_focused = if is_pointer_down {
             cx.app_state.clicking.clear();
@@ -303,9 +443,10 @@ impl WindowHandle {
             cx.app_state.focus
         };
 
+        // Whether a view handled the event, in which case the engine doesn't receive it.
+        #[cfg_attr(not(feature = "engine"), allow(unused_assignments))]
+        let mut processed = false;
         if event.needs_focus() {
-            let mut processed = false;
-
             if !processed {
                 if let Some(id) = cx.app_state.focus {
                     processed |= cx
@@ -363,26 +504,20 @@ impl WindowHandle {
                     }
                 }
             }
+        } else if let Some(id) = event
+            .touch_id()
+            .and_then(|touch| cx.app_state.touch_targets.get(&touch).copied())
+        {
+            processed = true;
+            directed_view_event(&mut cx, id, event.clone());
         } else if cx.app_state.active.is_some() && event.is_pointer() {
+            processed = true;
             if cx.app_state.is_dragging() {
                 cx.unconditional_view_event(self.id, event.clone(), false);
             }
 
             let id = cx.app_state.active.unwrap();
-
-            {
-                let window_origin = id.state().borrow().window_origin;
-                let layout = id.get_layout().unwrap_or_default();
-                let viewport = id.state().borrow().viewport.unwrap_or_default();
-                cx.unconditional_view_event(
-                    id,
-                    event.clone().offset((
-                        window_origin.x - layout.location.x as f64 + viewport.x0,
-                        window_origin.y - layout.location.y as f64 + viewport.y0,
-                    )),
-                    true,
-                );
-            }
+            directed_view_event(&mut cx, id, event.clone());
 
             if let Event::PointerUp(_) = &event {
                 // To remove the styles applied by the Active selector
@@ -393,11 +528,22 @@ impl WindowHandle {
                 cx.app_state.active = None;
             }
         } else {
-            cx.unconditional_view_event(self.id, event.clone(), false);
+            processed = cx
+                .unconditional_view_event(self.id, event.clone(), false)
+                .is_processed();
         }
 
         if let Event::PointerUp(_) = &event {
             cx.app_state.drag_start = None;
+            // Drop targets lose their style once the payload is released.
+            for id in cx.app_state.dragging_over.clone() {
+                if cx.app_state.has_style_for_sel(id, StyleSelector::DragOver) {
+                    id.request_style();
+                }
+            }
+        }
+        if let Event::TouchEnd(touch) | Event::TouchCancel(touch) = &event {
+            cx.app_state.touch_targets.remove(&touch.id);
         }
         if is_pointer_move {
             let hovered = &cx.app_state.hovered.clone();
@@ -426,6 +572,9 @@ impl WindowHandle {
                 .unwrap()
                 .symmetric_difference(dragging_over)
             {
+                if cx.app_state.has_style_for_sel(*id, StyleSelector::DragOver) {
+                    id.request_style();
+                }
                 if dragging_over.contains(id) {
                     id.apply_event(&EventListener::DragEnter, &event);
                 } else {
@@ -433,6 +582,20 @@ impl WindowHandle {
                 }
             }
         }
+        if let (Some(was_file_drag_over), Event::FileDragOver(drag)) = (was_file_drag_over, &event)
+        {
+            // Views are left before the next ones are entered, so a drop zone can tell when
+            // the files move from one zone to another.
+            let file_drag_over = &cx.app_state.file_drag_over;
+            for id in was_file_drag_over.difference(file_drag_over) {
+                let event = Event::FileDragLeave(drag.clone());
+                file_drag_event(*id, EventListener::FileDragLeave, event);
+            }
+            for id in file_drag_over.difference(&was_file_drag_over) {
+                let event = Event::FileDragEnter(drag.clone());
+                file_drag_event(*id, EventListener::FileDragEnter, event);
+            }
+        }
         if was_focused != cx.app_state.focus {
             cx.app_state.focus_changed(was_focused, cx.app_state.focus);
         }
@@ -453,9 +616,59 @@ impl WindowHandle {
             cx.app_state.clicking.clear();
         }
 
+        #[cfg(feature = "engine")]
+        if let Some(engine) = self.engine.as_mut() {
+            let for_engine = match &event {
+                Event::PointerDown(_)
+                | Event::PointerUp(_)
+                | Event::PointerMove(_)
+                | Event::PointerWheel(_)
+                | Event::TouchStart(_)
+                | Event::TouchMove(_)
+                | Event::TouchEnd(_)
+                | Event::TouchCancel(_)
+                | Event::PinchZoom(_)
+                | Event::Rotate(_)
+                | Event::DoubleTapZoom(_)
+                | Event::TouchpadPressure(_) => true,
+                Event::KeyDown(_) | Event::KeyUp(_) => !self.is_editing_text(),
+                // The engine needs to know when modifiers are released while a view has focus.
+                Event::ModifiersChanged(_) => true,
+                _ => false,
+            };
+            if for_engine && !processed {
+                engine.input(&event);
+                if engine.needs_redraw() {
+                    if let Some(window) = self.window.as_ref() {
+                        window.request_redraw();
+                    }
+                }
+            }
+        }
         self.process_update();
     }
 
+    /// Whether the focused view edits text, so keys are meant for it rather than the engine.
+    #[cfg(feature = "engine")]
+    fn is_editing_text(&self) -> bool {
+        use crate::style::StyleClass;
+
+        let Some(focus) = self.app_state.focus else {
+            return false;
+        };
+        let text_classes = [
+            crate::views::TextInputClass::class_ref(),
+            #[cfg(feature = "editor")]
+            crate::views::editor::view::EditorViewClass::class_ref(),
+        ];
+        focus
+            .state()
+            .borrow()
+            .classes
+            .iter()
+            .any(|class| text_classes.contains(class))
+    }
+
     pub(crate) fn scale(&mut self, scale: f64) {
         self.scale = scale;
         let scale = self.scale * self.app_state.scale;
@@ -465,6 +678,10 @@ impl WindowHandle {
 
     pub(crate) fn os_theme_changed(&mut self, theme: floem_winit::window::Theme) {
         self.os_theme.set(Some(theme));
+        if let Some(WindowTheme::System { .. }) = self.theme {
+            self.theme_style = theme_style(self.theme.as_ref(), Some(theme));
+            self.id.request_style_recursive();
+        }
         self.event(Event::ThemeChanged(theme));
     }
 
@@ -478,6 +695,14 @@ impl WindowHandle {
         self.event(Event::WindowResized(size));
         let scale = self.scale * self.app_state.scale;
         self.paint_state.resize(scale, size * self.scale);
+        #[cfg(feature = "engine")]
+        if let Some(engine) = self.engine.as_mut() {
+            let size = self.paint_state.renderer().size();
+            engine.resize(WindowSize {
+                width: size.width as u32,
+                height: size.height as u32,
+            });
+        }
         self.app_state.set_root_size(size);
 
         if let Some(window) = self.window.as_ref() {
@@ -499,9 +724,9 @@ impl WindowHandle {
         self.event(Event::WindowMoved(point));
     }
 
-    pub(crate) fn key_event(&mut self, key_event: floem_winit::event::KeyEvent) {
+    pub(crate) fn key_event(&mut self, key: KeyInfo) {
         let event = KeyEvent {
-            key: key_event,
+            key,
             modifiers: self.modifiers,
         };
         let is_altgr = matches!(event.key.logical_key, Key::Named(NamedKey::AltGraph));
@@ -518,25 +743,107 @@ impl WindowHandle {
         }
     }
 
+    pub(crate) fn hovered_file(&mut self, path: PathBuf) {
+        if self.file_drag.as_ref().is_some_and(|drag| drag.dropped) {
+            // A new drag starts before the drop was given to views, which can't wait any longer.
+            self.deliver_file_drag(self.cursor_position);
+        }
+        let drag = self.file_drag.get_or_insert_with(|| FileDrag {
+            paths: Vec::new(),
+            pending: false,
+            dropped: false,
+        });
+        drag.paths.push(path);
+        drag.pending = true;
+    }
+
+    pub(crate) fn hovered_file_cancelled(&mut self) {
+        if let Some(drag) = self.file_drag.take() {
+            self.file_drag_leave(drag.paths, self.cursor_position);
+        }
+    }
+
     pub(crate) fn dropped_file(&mut self, path: PathBuf) {
-        self.dropper_file = Some(path.clone());
+        match self.file_drag.as_mut() {
+            Some(drag) if drag.dropped => drag.paths.push(path),
+            // The hovered files are reported again when they are dropped.
+            _ => {
+                self.file_drag = Some(FileDrag {
+                    paths: vec![path],
+                    pending: true,
+                    dropped: true,
+                })
+            }
+        }
+    }
+
+    /// Gives the files reported since the last call to the views, once the platform has
+    /// reported all the files of a drag or drop.
+    ///
+    /// While the cursor position is unknown, such as when files are dragged in from outside
+    /// the window, the files are given to views once the pointer moves instead.
+    pub(crate) fn flush_file_drag(&mut self) {
+        if self.cursor_in_window {
+            self.deliver_file_drag(self.cursor_position);
+        }
+    }
+
+    fn deliver_file_drag(&mut self, pos: Point) {
+        let Some(drag) = self.file_drag.as_mut().filter(|drag| drag.pending) else {
+            return;
+        };
+        drag.pending = false;
+        if drag.dropped {
+            let paths = self.file_drag.take().unwrap().paths;
+            self.event(Event::DroppedFile(DroppedFileEvent {
+                paths: paths.clone(),
+                pos,
+            }));
+            self.file_drag_leave(paths, pos);
+        } else {
+            let paths = drag.paths.clone();
+            self.event(Event::FileDragOver(FileDragEvent { paths, pos }));
+        }
+    }
+
+    /// Sends `FileDragLeave` to every view the dragged files are over.
+    fn file_drag_leave(&mut self, paths: Vec<PathBuf>, pos: Point) {
+        set_current_view(self.id);
+        let event = Event::FileDragLeave(FileDragEvent { paths, pos }).scale(self.app_state.scale);
+        for id in std::mem::take(&mut self.app_state.file_drag_over) {
+            file_drag_event(id, EventListener::FileDragLeave, event.clone());
+        }
+        self.process_update();
     }
 
     pub(crate) fn pointer_move(&mut self, pos: Point) {
-        if let Some(path) = self.dropper_file.take() {
-            self.event(Event::DroppedFile(DroppedFileEvent { path, pos }));
+        self.cursor_in_window = true;
+        if let Some(drag) = self.file_drag.as_mut() {
+            drag.pending = true;
+            let dropped = drag.dropped;
+            self.deliver_file_drag(pos);
+            if !dropped {
+                // The pointer only moves the files while it drags them.
+                self.cursor_position = pos;
+                return;
+            }
         }
         if self.cursor_position != pos {
             self.cursor_position = pos;
             let event = PointerMoveEvent {
                 pos,
                 modifiers: self.modifiers,
+                pointer_type: PointerType::Mouse,
+                pressure: self.mouse_pressure(),
+                tilt: None,
+                twist: None,
             };
             self.event(Event::PointerMove(event));
         }
     }
 
     pub(crate) fn pointer_leave(&mut self) {
+        self.cursor_in_window = false;
         set_current_view(self.id);
         let mut cx = EventCx {
             app_state: &mut self.app_state,
@@ -579,24 +886,13 @@ impl WindowHandle {
 
     pub(crate) fn mouse_input(&mut self, button: MouseButton, state: ElementState) {
         let button: PointerButton = button.into();
+        if state.is_pressed() {
+            self.pressed_buttons.insert(button);
+        } else {
+            self.pressed_buttons.remove(&button);
+        }
         let count = if state.is_pressed() && button.is_primary() {
-            if let Some((count, last_pos, instant)) = self.last_pointer_down.as_mut() {
-                if *count == 4 {
-                    *count = 1;
-                } else if instant.elapsed().as_millis() < 500
-                    && last_pos.distance(self.cursor_position) < 4.0
-                {
-                    *count += 1;
-                } else {
-                    *count = 1;
-                }
-                *instant = Instant::now();
-                *last_pos = self.cursor_position;
-                *count
-            } else {
-                self.last_pointer_down = Some((1, self.cursor_position, Instant::now()));
-                1
-            }
+            self.click_count()
         } else {
             0
         };
@@ -605,6 +901,10 @@ impl WindowHandle {
             button,
             modifiers: self.modifiers,
             count,
+            pointer_type: PointerType::Mouse,
+            pressure: self.mouse_pressure(),
+            tilt: None,
+            twist: None,
         };
         match state {
             ElementState::Pressed => {
@@ -616,6 +916,150 @@ impl WindowHandle {
         }
     }
 
+    /// The pressure of the mouse, which is `0.5` while a button is pressed.
+    fn mouse_pressure(&self) -> f64 {
+        if self.pressed_buttons.is_empty() {
+            0.0
+        } else {
+            0.5
+        }
+    }
+
+    /// Counts a primary press at the cursor position, returning `2` and `3` for double and
+    /// triple clicks.
+    fn click_count(&mut self) -> u8 {
+        if let Some((count, last_pos, instant)) = self.last_pointer_down.as_mut() {
+            if *count == 4 {
+                *count = 1;
+            } else if instant.elapsed().as_millis() < 500
+                && last_pos.distance(self.cursor_position) < 4.0
+            {
+                *count += 1;
+            } else {
+                *count = 1;
+            }
+            *instant = Instant::now();
+            *last_pos = self.cursor_position;
+            *count
+        } else {
+            self.last_pointer_down = Some((1, self.cursor_position, Instant::now()));
+            1
+        }
+    }
+
+    /// The tip of a pen touched, moved on or left the surface at `pos`, with `pressure`
+    /// from `0.0` to `1.0` and `tilt` in degrees from the normal of the surface. Pens are
+    /// delivered as pointer events with the primary button.
+    pub(crate) fn pen(&mut self, phase: TouchPhase, pos: Point, pressure: f64, tilt: Option<f64>) {
+        self.cursor_position = pos;
+        self.cursor_in_window = true;
+        match phase {
+            TouchPhase::Started => {
+                let event = PointerInputEvent {
+                    pos,
+                    button: PointerButton::Primary,
+                    modifiers: self.modifiers,
+                    count: self.click_count(),
+                    pointer_type: PointerType::Pen,
+                    pressure,
+                    tilt,
+                    twist: None,
+                };
+                self.event(Event::PointerDown(event));
+            }
+            TouchPhase::Moved => {
+                let event = PointerMoveEvent {
+                    pos,
+                    modifiers: self.modifiers,
+                    pointer_type: PointerType::Pen,
+                    pressure,
+                    tilt,
+                    twist: None,
+                };
+                self.event(Event::PointerMove(event));
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let event = PointerInputEvent {
+                    pos,
+                    button: PointerButton::Primary,
+                    modifiers: self.modifiers,
+                    count: 0,
+                    pointer_type: PointerType::Pen,
+                    pressure: 0.0,
+                    tilt,
+                    twist: None,
+                };
+                self.event(Event::PointerUp(event));
+            }
+        }
+    }
+
+    /// A finger on a touchscreen, identified by the platform with `id`, moved to `pos`.
+    /// `force` is the normalized pressure of the finger, if the screen reports it.
+    pub(crate) fn touch(&mut self, id: u64, phase: TouchPhase, pos: Point, force: Option<f64>) {
+        let (id, gesture) = self.touches.touch(id, phase, pos);
+        let event = TouchEvent {
+            id,
+            pos,
+            force,
+            modifiers: self.modifiers,
+        };
+        self.event(match phase {
+            TouchPhase::Started => Event::TouchStart(event),
+            TouchPhase::Moved => Event::TouchMove(event),
+            TouchPhase::Ended => Event::TouchEnd(event),
+            TouchPhase::Cancelled => Event::TouchCancel(event),
+        });
+        if let Some(gesture) = gesture {
+            self.event(Event::PinchZoom(PinchEvent {
+                pos: gesture.pos,
+                delta: gesture.scale_delta,
+                phase: gesture.phase,
+                modifiers: self.modifiers,
+            }));
+            self.event(Event::Rotate(RotateEvent {
+                pos: gesture.pos,
+                delta: gesture.rotate_delta,
+                phase: gesture.phase,
+                modifiers: self.modifiers,
+            }));
+        }
+    }
+
+    pub(crate) fn touchpad_magnify(&mut self, delta: f64, phase: TouchPhase) {
+        self.event(Event::PinchZoom(PinchEvent {
+            pos: self.cursor_position,
+            delta,
+            phase: phase.into(),
+            modifiers: self.modifiers,
+        }));
+    }
+
+    pub(crate) fn touchpad_rotate(&mut self, delta: f32, phase: TouchPhase) {
+        self.event(Event::Rotate(RotateEvent {
+            pos: self.cursor_position,
+            delta: delta as f64,
+            phase: phase.into(),
+            modifiers: self.modifiers,
+        }));
+    }
+
+    pub(crate) fn double_tap_zoom(&mut self) {
+        self.event(Event::DoubleTapZoom(DoubleTapZoomEvent {
+            pos: self.cursor_position,
+            modifiers: self.modifiers,
+        }));
+    }
+
+    pub(crate) fn touchpad_pressure(&mut self, pressure: f32, stage: i64) {
+        self.event(Event::TouchpadPressure(TouchpadPressureEvent {
+            pos: self.cursor_position,
+            pressure: pressure as f64,
+            stage,
+            modifiers: self.modifiers,
+        }));
+    }
+
     pub(crate) fn focused(&mut self, focused: bool) {
         if focused {
             self.event(Event::WindowGotFocus);
@@ -626,8 +1070,8 @@ impl WindowHandle {
 
     fn style(&mut self) {
         let mut cx = StyleCx::new(&mut self.app_state, self.id);
-        if let Some(theme) = &self.theme {
-            cx.current = theme.style.clone();
+        if let Some(style) = &self.theme_style {
+            cx.current = style.clone();
         }
         cx.style_view(self.id);
     }
@@ -657,7 +1101,9 @@ impl WindowHandle {
         cx.compute_view_layout(self.id);
     }
 
-    pub(crate) fn render_frame(&mut self) {
+    pub(crate) fn render_frame(&mut self) -> Option<DynamicImage> {
+        self.last_frame = Some(Instant::now());
+
         // Processes updates scheduled on this frame.
         for update in mem::take(&mut self.app_state.scheduled_updates) {
             match update {
@@ -668,12 +1114,79 @@ impl WindowHandle {
         }
 
         self.process_update_no_paint();
-        self.paint();
+        let image = self.paint();
 
         // Request a new frame if there's any scheduled updates.
         if !self.app_state.scheduled_updates.is_empty() {
             self.schedule_repaint();
         }
+
+        image
+    }
+
+    /// Requests the frame following the one that was just rendered, as the frame pacing asks.
+    pub(crate) fn schedule_next_frame(&mut self) {
+        let needs_redraw = mem::take(&mut self.app_state.next_frame_requested);
+        #[cfg(feature = "engine")]
+        let needs_redraw = needs_redraw
+            || self
+                .engine
+                .as_ref()
+                .is_some_and(|engine| engine.needs_redraw());
+        if needs_redraw {
+            self.schedule_repaint();
+        }
+
+        match self.frame_pacing {
+            FramePacing::OnDemand => {}
+            FramePacing::VSync => {
+                let waits_for_vsync = matches!(
+                    self.paint_state.renderer().present_mode(),
+                    Some(
+                        wgpu::PresentMode::Fifo
+                            | wgpu::PresentMode::FifoRelaxed
+                            | wgpu::PresentMode::AutoVsync
+                    )
+                );
+                if waits_for_vsync {
+                    // Acquiring the next frame waits for vsync.
+                    self.schedule_repaint();
+                } else {
+                    // Nothing waits for vsync, so render at the refresh rate of the monitor.
+                    let refresh_rate = self
+                        .window
+                        .as_ref()
+                        .and_then(|window| window.current_monitor())
+                        .and_then(|monitor| monitor.refresh_rate_millihertz())
+                        .map_or(60.0, |millihertz| millihertz as f64 / 1000.0);
+                    self.schedule_timed_frame(refresh_rate);
+                }
+            }
+            FramePacing::Continuous { fps } => self.schedule_timed_frame(fps),
+        }
+    }
+
+    /// Requests a frame once `1 / fps` seconds have passed since the last one.
+    fn schedule_timed_frame(&mut self, fps: f64) {
+        if let Some(timer) = self.frame_timer.take() {
+            timer.cancel();
+        }
+        let Ok(frame_duration) = Duration::try_from_secs_f64(1.0 / fps) else {
+            return;
+        };
+        let elapsed = self
+            .last_frame
+            .map_or(frame_duration, |last| last.elapsed());
+        if elapsed >= frame_duration {
+            self.schedule_repaint();
+        } else if let Some(window) = self.window.as_ref() {
+            let window = Arc::downgrade(window);
+            self.frame_timer = Some(exec_after(frame_duration - elapsed, move |_| {
+                if let Some(window) = window.upgrade() {
+                    window.request_redraw();
+                }
+            }));
+        }
     }
 
     pub fn paint(&mut self) -> Option<DynamicImage> {
@@ -690,15 +1203,20 @@ impl WindowHandle {
         cx.paint_state
             .renderer_mut()
             .begin(cx.app_state.capture.is_some());
-        if !self.transparent {
+        // An engine's scene is the background of its window.
+        #[cfg(feature = "engine")]
+        let has_background = !self.transparent && self.engine.is_none();
+        #[cfg(not(feature = "engine"))]
+        let has_background = !self.transparent;
+        if has_background {
             let scale = cx.app_state.scale;
-            // let color = self
-            //     .theme
-            //     .as_ref()
-            //     .map(|theme| theme.background)
-            //     .unwrap_or(peniko::Color::RED);
-            let color = peniko::Color::TRANSPARENT;
-            // fill window with default white background if it's not transparent
+            // fill window with the theme's background, or white without a theme
+            let color = self.theme.as_ref().map_or(peniko::Color::WHITE, |theme| {
+                theme
+                    .resolve(self.os_theme.get_untracked())
+                    .palette
+                    .background
+            });
             cx.fill(
                 &self
                     .size
@@ -716,108 +1234,26 @@ impl WindowHandle {
                 window.pre_present_notify();
             }
         }
-        // cx.paint_state.renderer_mut().finish()
-        // let dynamic_image =
-        //     cx.paint_state
-        //         .renderer_mut()
-        //         .finish(|encoder, frame, view, resolve_view| {
-        //             self.call_encode_callback(encoder, frame, view, resolve_view)
-        //         });
-
-        let encode_callback = self.encode_callback.as_ref().map(|cb| cb.clone());
+        #[cfg(feature = "engine")]
+        let engine = self.engine.as_mut();
+        #[cfg(feature = "engine")]
+        let depth_stencil = cx.paint_state.renderer().depth_stencil_view().cloned();
 
-        let gpu_resources = self
-            .gpu_resources
-            .as_ref()
-            .expect("Couldn't get GPU Resources");
-        let engine_handle = self
-            .engine_handle
-            .as_ref()
-            .expect("Couldn't get engine handle");
-
-        let dynamic_image =
-            cx.paint_state
-                .renderer_mut()
-                .finish(|encoder, frame, view, resolve_view| {
-                    if let Some(callback) = encode_callback {
-                        callback(
-                            encoder,
-                            frame,
-                            view,
-                            resolve_view,
-                            gpu_resources,
-                            engine_handle,
-                        )
-                    } else {
-                        (None, None, None, None)
+        // The callback is only invoked by the GPU renderer, so a headless
+        // window never needs the engine.
+        cx.paint_state
+            .renderer_mut()
+            .finish(|encoder, frame, view, resolve_view| {
+                #[cfg(feature = "engine")]
+                let encoder = {
+                    let mut encoder = encoder;
+                    if let Some(engine) = engine {
+                        engine.encode(&mut encoder, &view, depth_stencil.as_deref());
                     }
-                });
-
-        dynamic_image
-    }
-
-    pub fn set_encode_callback(
-        &mut self,
-        callback: Box<
-            dyn for<'a> Fn(
-                    wgpu::CommandEncoder,
-                    wgpu::SurfaceTexture,
-                    Arc<wgpu::TextureView>,
-                    Arc<wgpu::TextureView>,
-                    // &'a WindowHandle,
-                    &Arc<GpuResources>,
-                    &EngineHandle,
-                ) -> (
-                    Option<wgpu::CommandEncoder>,
-                    Option<wgpu::SurfaceTexture>,
-                    Option<Arc<wgpu::TextureView>>,
-                    Option<Arc<wgpu::TextureView>>,
-                ) + 'static,
-        >,
-    ) {
-        self.encode_callback = Some(callback);
-    }
-
-    // pub fn call_encode_callback(&self) {
-    //     if let Some(callback) = &self.encode_callback {
-    //         let window_handle_ref = RefCell::new(self);
-    //         callback(&window_handle_ref);
-    //     }
-    // }
-
-    pub fn call_encode_callback(
-        &mut self,
-        command_encoder: wgpu::CommandEncoder,
-        frame: wgpu::SurfaceTexture,
-        view: Arc<wgpu::TextureView>,
-        resolve_view: Arc<wgpu::TextureView>,
-    ) -> (
-        Option<wgpu::CommandEncoder>,
-        Option<wgpu::SurfaceTexture>,
-        Option<Arc<wgpu::TextureView>>,
-        Option<Arc<wgpu::TextureView>>,
-    ) {
-        if let Some(callback) = &self.encode_callback {
-            let gpu_resources = self
-                .gpu_resources
-                .as_ref()
-                .expect("Couldn't get GPU Resources");
-            let engine_handle = self
-                .engine_handle
-                .as_ref()
-                .expect("Couldn't get engine handle");
-
-            callback(
-                command_encoder,
-                frame,
-                view,
-                resolve_view,
-                gpu_resources,
-                engine_handle,
-            )
-        } else {
-            (None, None, None, None)
-        }
+                    encoder
+                };
+                (Some(encoder), Some(frame), Some(view), Some(resolve_view))
+            })
     }
 
     pub(crate) fn capture(&mut self) -> Capture {
@@ -1038,6 +1474,34 @@ impl WindowHandle {
                             cx.app_state.active = None;
                         }
                     }
+                    UpdateMessage::CancelClick(_) => {
+                        for id in std::mem::take(&mut cx.app_state.clicking) {
+                            id.state().borrow_mut().last_pointer_down = None;
+                            // To remove the styles applied by the Active selector
+                            if cx.app_state.has_style_for_sel(id, StyleSelector::Active) {
+                                id.request_style_recursive();
+                            }
+                        }
+                    }
+                    UpdateMessage::CaptureTouch { id, touch } => {
+                        // The finger may have been lifted before the message is processed.
+                        let Some(pos) = self.touches.position(touch) else {
+                            continue;
+                        };
+                        let previous = cx.app_state.touch_targets.insert(touch, id);
+                        if let Some(previous) = previous.filter(|previous| *previous != id) {
+                            let mut cx = EventCx {
+                                app_state: cx.app_state,
+                            };
+                            let event = TouchEvent {
+                                id: touch,
+                                pos,
+                                force: None,
+                                modifiers: self.modifiers,
+                            };
+                            directed_view_event(&mut cx, previous, Event::TouchCancel(event));
+                        }
+                    }
                     UpdateMessage::ScrollTo { id, rect } => {
                         self.id
                             .view()
@@ -1124,6 +1588,21 @@ impl WindowHandle {
                         self.update_window_menu(menu);
                         // self.handle.set_menu(platform_menu);
                     }
+                    UpdateMessage::SetTheme(theme) => {
+                        self.theme_style =
+                            theme_style(theme.as_ref(), self.os_theme.get_untracked());
+                        self.theme = theme;
+                        self.id.request_style_recursive();
+                    }
+                    UpdateMessage::SetFramePacing(frame_pacing) => {
+                        self.frame_pacing = frame_pacing;
+                        if let Some(timer) = self.frame_timer.take() {
+                            timer.cancel();
+                        }
+                        if let Some(window) = self.window.as_ref() {
+                            window.request_redraw();
+                        }
+                    }
                     UpdateMessage::SetWindowTitle { title } => {
                         if let Some(window) = self.window.as_ref() {
                             window.set_title(&title);
@@ -1376,8 +1855,46 @@ impl WindowHandle {
         if is_altgr {
             modifiers.set(Modifiers::ALTGR, true);
         }
-        self.modifiers = modifiers;
+        self.set_modifiers(modifiers);
     }
+
+    /// Sets the modifiers held, telling the views and the engine if they changed.
+    pub(crate) fn set_modifiers(&mut self, modifiers: Modifiers) {
+        if self.modifiers != modifiers {
+            self.modifiers = modifiers;
+            self.event(Event::ModifiersChanged(modifiers));
+        }
+    }
+}
+
+/// Builds the stylesheet of the theme `theme` resolves to for `os_theme`.
+fn theme_style(
+    theme: Option<&WindowTheme>,
+    os_theme: Option<floem_winit::window::Theme>,
+) -> Option<Rc<Style>> {
+    theme.map(|theme| Rc::new(theme.resolve(os_theme).to_style()))
+}
+
+/// Sends a window-positioned event to `id` only, such as the active view receiving pointer
+/// events while it holds the pointer.
+fn directed_view_event(cx: &mut EventCx, id: ViewId, event: Event) -> EventPropagation {
+    let window_origin = id.state().borrow().window_origin;
+    let layout = id.get_layout().unwrap_or_default();
+    let viewport = id.state().borrow().viewport.unwrap_or_default();
+    cx.unconditional_view_event(
+        id,
+        event.offset((
+            window_origin.x - layout.location.x as f64 + viewport.x0,
+            window_origin.y - layout.location.y as f64 + viewport.y0,
+        )),
+        true,
+    )
+}
+
+/// Gives a file drag event in window coordinates to the `listener`s of `id` alone.
+fn file_drag_event(id: ViewId, listener: EventListener, event: Event) {
+    let window_origin = id.state().borrow().window_origin;
+    id.apply_event(&listener, &event.offset((window_origin.x, window_origin.y)));
 }
 
 pub(crate) fn get_current_view() -> ViewId {
@@ -1765,49 +2282,3 @@ impl View for WindowView {
         "Window".into()
     }
 }
-
-pub struct GpuHelper {
-    pub depth_view: Option<wgpu::TextureView>,
-    pub gpu_resources: Option<std::sync::Arc<GpuResources>>,
-}
-
-impl GpuHelper {
-    pub fn new() -> Self {
-        GpuHelper {
-            depth_view: None,
-            gpu_resources: None,
-        }
-    }
-
-    pub fn recreate_depth_view(
-        &mut self,
-        gpu_resources: &std::sync::Arc<GpuResources>,
-        // window_size: &WindowSize,
-        window_width: u32,
-        window_height: u32,
-    ) {
-        let depth_texture = gpu_resources
-            .device
-            .create_texture(&wgpu::TextureDescriptor {
-                size: wgpu::Extent3d {
-                    width: window_width.clone(),
-                    height: window_height.clone(),
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 4, // used in a multisampled environment
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Depth24Plus,
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::TEXTURE_BINDING,
-                label: Some("Depth Texture"),
-                view_formats: &[],
-            });
-
-        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        self.depth_view = Some(depth_view);
-
-        // (depth_texture, depth_view)
-    }
-}
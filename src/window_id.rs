@@ -1,4 +1,5 @@
 use crate::{
+    renderer::RendererInfo,
     screen_layout::screen_layout_for_window,
     window_tracking::{force_window_repaint, with_window},
     ScreenLayout, ViewId,
@@ -6,8 +7,8 @@ use crate::{
 use std::{cell::RefCell, collections::HashMap};
 
 use super::window_tracking::{
-    monitor_bounds, root_view_id, window_inner_screen_bounds, window_inner_screen_position,
-    window_outer_screen_bounds, window_outer_screen_position,
+    monitor_bounds, renderer_info, root_view_id, window_inner_screen_bounds,
+    window_inner_screen_position, window_outer_screen_bounds, window_outer_screen_position,
 };
 use floem_winit::{
     dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Pixel},
@@ -219,6 +220,10 @@ pub trait WindowIdExt: WindowIdExtSealed {
     /// Get the dots-per-inch scaling of this window or 1.0 if the platform does not
     /// support it (Android).
     fn scale(&self) -> f64;
+
+    /// Get the renderer this window is drawn with and the GPU adapter it uses, or `None`
+    /// if the renderer hasn't been created yet or couldn't be created.
+    fn renderer_info(&self) -> Option<RendererInfo>;
 }
 
 impl WindowIdExt for WindowId {
@@ -285,6 +290,10 @@ impl WindowIdExt for WindowId {
     fn scale(&self) -> f64 {
         with_window(self, Window::scale_factor).unwrap_or(1.0)
     }
+
+    fn renderer_info(&self) -> Option<RendererInfo> {
+        renderer_info(self)
+    }
 }
 
 /// Called by `ApplicationHandle` at the end of the event loop callback.
@@ -3,7 +3,7 @@
 //! that views can retrieve the `WindowId` of the window that contains them
 //! and use the methods that look up the `Window` for that id to retrieve information
 //! such as screen position.
-use crate::ViewId;
+use crate::{renderer::RendererInfo, ViewId};
 use floem_winit::{
     dpi::{PhysicalPosition, PhysicalSize},
     monitor::MonitorHandle,
@@ -31,12 +31,21 @@ pub fn remove_window_id_mapping(root_id: &ViewId, window_id: &WindowId) {
     with_window_map_mut(move |m| m.remove(root_id, window_id));
 }
 
+/// Record the renderer the window with `window_id` is drawn with, once it is created.
+pub fn store_renderer_info(window_id: WindowId, info: RendererInfo) {
+    with_window_map_mut(move |m| {
+        m.renderer_info_for_window_id
+            .insert(window_id, info.clone());
+    });
+}
+
 /// Maps root-id:window-id:window triples, so a view can get its root and
 /// from that locate the window-id (if any) that it belongs to.
 #[derive(Default, Debug)]
 struct WindowMapping {
     window_for_window_id: HashMap<WindowId, Arc<Window>>,
     window_id_for_root_view_id: HashMap<ViewId, WindowId>,
+    renderer_info_for_window_id: HashMap<WindowId, RendererInfo>,
 }
 
 impl WindowMapping {
@@ -48,6 +57,7 @@ impl WindowMapping {
     fn remove(&mut self, root: &ViewId, window_id: &WindowId) {
         let root_found = self.window_id_for_root_view_id.remove(root).is_some();
         let window_found = self.window_for_window_id.remove(window_id).is_some();
+        self.renderer_info_for_window_id.remove(window_id);
         debug_assert!(root_found == window_found,
             "Window mapping state inconsistent. Remove root {:?} success was {} but remove {:?} success was {}",
             root, root_found, window_id, window_found);
@@ -136,6 +146,10 @@ pub fn force_window_repaint(id: &WindowId) -> bool {
     .unwrap_or(false)
 }
 
+pub fn renderer_info(window_id: &WindowId) -> Option<RendererInfo> {
+    with_window_map(|m| m.renderer_info_for_window_id.get(window_id).cloned()).unwrap_or(None)
+}
+
 pub fn window_id_for_root(root_id: ViewId) -> Option<WindowId> {
     with_window_map(|map| map.window_id_for_root(&root_id)).unwrap_or(None)
 }
@@ -3,15 +3,16 @@ use floem_renderer::swash::SwashScaler;
 use floem_renderer::text::{CacheKey, SwashContent, TextLayout};
 use floem_renderer::tiny_skia::{
     self, FillRule, FilterQuality, GradientStop, LinearGradient, Mask, MaskType, Paint, Path,
-    PathBuilder, Pattern, Pixmap, RadialGradient, Shader, SpreadMode, Stroke, Transform,
+    PathBuilder, Pattern, Pixmap, PixmapPaint, RadialGradient, Shader, SpreadMode, Stroke,
+    Transform,
 };
-use floem_renderer::Img;
 use floem_renderer::Renderer;
-use image::DynamicImage;
+use floem_renderer::{gradient, Img};
+use image::{DynamicImage, RgbaImage};
 use peniko::kurbo::{PathEl, Size};
 use peniko::{
     kurbo::{Affine, Point, Rect, Shape},
-    BrushRef, Color, GradientKind,
+    BlendMode, BrushRef, Color, Compose, Extend, Gradient, GradientKind, Mix,
 };
 use softbuffer::{Context, Surface};
 use std::collections::HashMap;
@@ -29,6 +30,15 @@ macro_rules! try_ret {
     };
 }
 
+/// A layer started by `push_layer`.
+struct Layer {
+    /// The pixmap that was drawn into before the layer was pushed.
+    pixmap: Pixmap,
+    alpha: f32,
+    blend_mode: tiny_skia::BlendMode,
+    clip: Option<Mask>,
+}
+
 struct Glyph {
     pixmap: Pixmap,
     left: f32,
@@ -40,8 +50,10 @@ struct CacheColor(bool);
 
 pub struct TinySkiaRenderer<W> {
     #[allow(unused)]
-    context: Context<W>,
-    surface: Surface<W, W>,
+    context: Option<Context<W>>,
+    /// The surface the pixmap is presented to. `None` for a headless renderer,
+    /// in which case `finish` returns the rendered image instead.
+    surface: Option<Surface<W, W>>,
     pixmap: Pixmap,
     mask: Mask,
     scale: f64,
@@ -55,6 +67,7 @@ pub struct TinySkiaRenderer<W> {
     #[allow(clippy::type_complexity)]
     glyph_cache: HashMap<(CacheKey, Color), (CacheColor, Option<Rc<Glyph>>)>,
     swash_scaler: SwashScaler,
+    layers: Vec<Layer>,
 }
 
 impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle>
@@ -81,8 +94,8 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
         let mask = Mask::new(width, height).ok_or_else(|| anyhow!("unable to create mask"))?;
 
         Ok(Self {
-            context,
-            surface,
+            context: Some(context),
+            surface: Some(surface),
             pixmap,
             mask,
             scale,
@@ -92,17 +105,20 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
             image_cache: Default::default(),
             glyph_cache: Default::default(),
             swash_scaler: SwashScaler::new(font_embolden),
+            layers: Vec::new(),
         })
     }
 
     pub fn resize(&mut self, width: u32, height: u32, scale: f64) {
-        if width != self.pixmap.width() || height != self.pixmap.width() {
-            self.surface
-                .resize(
-                    NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
-                    NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
-                )
-                .expect("failed to resize surface");
+        if width != self.pixmap.width() || height != self.pixmap.height() {
+            if let Some(surface) = self.surface.as_mut() {
+                surface
+                    .resize(
+                        NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
+                        NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
+                    )
+                    .expect("failed to resize surface");
+            }
             self.pixmap = Pixmap::new(width, height).expect("unable to create pixmap");
             self.mask = Mask::new(width, height).expect("unable to create mask");
         }
@@ -122,6 +138,59 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
     }
 }
 
+impl<W> TinySkiaRenderer<W> {
+    /// Creates a renderer that paints into an offscreen pixmap without a window.
+    /// `finish` returns the rendered frame as a `DynamicImage`.
+    pub fn new_headless(width: u32, height: u32, scale: f64, font_embolden: f32) -> Result<Self> {
+        let width = width.max(1);
+        let height = height.max(1);
+
+        let pixmap =
+            Pixmap::new(width, height).ok_or_else(|| anyhow!("unable to create pixmap"))?;
+
+        let mask = Mask::new(width, height).ok_or_else(|| anyhow!("unable to create mask"))?;
+
+        Ok(Self {
+            context: None,
+            surface: None,
+            pixmap,
+            mask,
+            scale,
+            transform: Affine::IDENTITY,
+            clip: None,
+            cache_color: CacheColor(false),
+            image_cache: Default::default(),
+            glyph_cache: Default::default(),
+            swash_scaler: SwashScaler::new(font_embolden),
+            layers: Vec::new(),
+        })
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.surface.is_none()
+    }
+
+    /// The pixmap holding the last rendered frame.
+    pub fn pixmap(&self) -> &Pixmap {
+        &self.pixmap
+    }
+
+    /// Converts the premultiplied pixmap into a straight-alpha `DynamicImage`.
+    fn render_image(&self) -> Option<DynamicImage> {
+        let data = self
+            .pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let pixel = pixel.demultiply();
+                [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            })
+            .collect();
+        image::RgbaImage::from_raw(self.pixmap.width(), self.pixmap.height(), data)
+            .map(DynamicImage::ImageRgba8)
+    }
+}
+
 fn to_color(color: Color) -> tiny_skia::Color {
     tiny_skia::Color::from_rgba8(color.r, color.g, color.b, color.a)
 }
@@ -130,6 +199,52 @@ fn to_point(point: Point) -> tiny_skia::Point {
     tiny_skia::Point::from_xy(point.x as f32, point.y as f32)
 }
 
+fn to_blend_mode(blend_mode: BlendMode) -> tiny_skia::BlendMode {
+    match blend_mode.mix {
+        Mix::Normal | Mix::Clip => match blend_mode.compose {
+            Compose::Clear => tiny_skia::BlendMode::Clear,
+            Compose::Copy => tiny_skia::BlendMode::Source,
+            Compose::Dest => tiny_skia::BlendMode::Destination,
+            Compose::SrcOver => tiny_skia::BlendMode::SourceOver,
+            Compose::DestOver => tiny_skia::BlendMode::DestinationOver,
+            Compose::SrcIn => tiny_skia::BlendMode::SourceIn,
+            Compose::DestIn => tiny_skia::BlendMode::DestinationIn,
+            Compose::SrcOut => tiny_skia::BlendMode::SourceOut,
+            Compose::DestOut => tiny_skia::BlendMode::DestinationOut,
+            Compose::SrcAtop => tiny_skia::BlendMode::SourceAtop,
+            Compose::DestAtop => tiny_skia::BlendMode::DestinationAtop,
+            Compose::Xor => tiny_skia::BlendMode::Xor,
+            Compose::Plus | Compose::PlusLighter => tiny_skia::BlendMode::Plus,
+        },
+        Mix::Multiply => tiny_skia::BlendMode::Multiply,
+        Mix::Screen => tiny_skia::BlendMode::Screen,
+        Mix::Overlay => tiny_skia::BlendMode::Overlay,
+        Mix::Darken => tiny_skia::BlendMode::Darken,
+        Mix::Lighten => tiny_skia::BlendMode::Lighten,
+        Mix::ColorDodge => tiny_skia::BlendMode::ColorDodge,
+        Mix::ColorBurn => tiny_skia::BlendMode::ColorBurn,
+        Mix::HardLight => tiny_skia::BlendMode::HardLight,
+        Mix::SoftLight => tiny_skia::BlendMode::SoftLight,
+        Mix::Difference => tiny_skia::BlendMode::Difference,
+        Mix::Exclusion => tiny_skia::BlendMode::Exclusion,
+        Mix::Hue => tiny_skia::BlendMode::Hue,
+        Mix::Saturation => tiny_skia::BlendMode::Saturation,
+        Mix::Color => tiny_skia::BlendMode::Color,
+        Mix::Luminosity => tiny_skia::BlendMode::Luminosity,
+    }
+}
+
+/// Converts a straight-alpha image into a premultiplied pixmap.
+fn rgba_to_pixmap(image: &RgbaImage) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(image.width(), image.height())?;
+    for (a, &b) in pixmap.pixels_mut().iter_mut().zip(image.pixels()) {
+        *a = tiny_skia::Color::from_rgba8(b.0[0], b.0[1], b.0[2], b.0[3])
+            .premultiply()
+            .to_color_u8();
+    }
+    Some(pixmap)
+}
+
 impl<W> TinySkiaRenderer<W> {
     fn shape_to_path(&self, shape: &impl Shape) -> Option<Path> {
         let mut builder = PathBuilder::new();
@@ -154,7 +269,14 @@ impl<W> TinySkiaRenderer<W> {
         builder.finish()
     }
 
-    fn brush_to_paint<'b>(&self, brush: impl Into<BrushRef<'b>>) -> Option<Paint<'static>> {
+    /// Converts a brush into a paint for a shape with the given local `bounds`.
+    /// Gradients tiny-skia has no shader for are rasterized into `pattern`, which the paint borrows.
+    fn brush_to_paint<'a, 'b>(
+        &self,
+        brush: impl Into<BrushRef<'b>>,
+        bounds: Rect,
+        pattern: &'a mut Option<Pixmap>,
+    ) -> Option<Paint<'a>> {
         let shader = match brush.into() {
             BrushRef::Solid(c) => Shader::SolidColor(to_color(c)),
             BrushRef::Gradient(g) => {
@@ -163,31 +285,37 @@ impl<W> TinySkiaRenderer<W> {
                     .iter()
                     .map(|s| GradientStop::new(s.offset, to_color(s.color)))
                     .collect();
+                let spread_mode = match g.extend {
+                    Extend::Pad => SpreadMode::Pad,
+                    Extend::Repeat => SpreadMode::Repeat,
+                    Extend::Reflect => SpreadMode::Reflect,
+                };
                 match g.kind {
                     GradientKind::Linear { start, end } => LinearGradient::new(
                         to_point(start),
                         to_point(end),
                         stops,
-                        SpreadMode::Pad,
+                        spread_mode,
                         Transform::identity(),
                     )?,
                     GradientKind::Radial {
                         start_center,
-                        start_radius: _,
+                        start_radius,
                         end_center,
                         end_radius,
-                    } => {
-                        // FIXME: Doesn't use `start_radius`
-                        RadialGradient::new(
-                            to_point(start_center),
-                            to_point(end_center),
-                            end_radius,
-                            stops,
-                            SpreadMode::Pad,
-                            Transform::identity(),
-                        )?
+                    } if start_radius == 0.0 => RadialGradient::new(
+                        to_point(start_center),
+                        to_point(end_center),
+                        end_radius,
+                        stops,
+                        spread_mode,
+                        Transform::identity(),
+                    )?,
+                    // tiny-skia has no sweep gradients, and its radial gradients
+                    // always start with a zero radius.
+                    GradientKind::Radial { .. } | GradientKind::Sweep { .. } => {
+                        self.rasterized_gradient(g, bounds, pattern)?
                     }
-                    GradientKind::Sweep { .. } => return None,
                 }
             }
             BrushRef::Image(_) => return None,
@@ -198,6 +326,33 @@ impl<W> TinySkiaRenderer<W> {
         })
     }
 
+    fn rasterized_gradient<'a>(
+        &self,
+        gradient: &Gradient,
+        bounds: Rect,
+        pattern: &'a mut Option<Pixmap>,
+    ) -> Option<Shader<'a>> {
+        // The pixmap is resampled when drawn, so cap its size for very large shapes.
+        let width = (bounds.width() * self.scale).ceil().clamp(1.0, 1024.0) as u32;
+        let height = (bounds.height() * self.scale).ceil().clamp(1.0, 1024.0) as u32;
+        let image = gradient::rasterize(gradient, bounds, width, height)?;
+        let pixmap = pattern.insert(rgba_to_pixmap(&image)?);
+        Some(Pattern::new(
+            pixmap.as_ref(),
+            SpreadMode::Pad,
+            FilterQuality::Bilinear,
+            1.0,
+            Transform::from_row(
+                (bounds.width() / width as f64) as f32,
+                0.0,
+                0.0,
+                (bounds.height() / height as f64) as f32,
+                bounds.x0 as f32,
+                bounds.y0 as f32,
+            ),
+        ))
+    }
+
     /// Transform a `Rect`, applying `self.transform`, into a `tiny_skia::Rect` and
     /// residual transform.
     fn rect(&self, rect: Rect) -> Option<tiny_skia::Rect> {
@@ -271,7 +426,7 @@ impl<W> TinySkiaRenderer<W> {
         &mut self,
         pixmap: &Pixmap,
         rect: tiny_skia::Rect,
-        paint: Option<Paint<'static>>,
+        paint: Option<Paint<'_>>,
     ) {
         let paint = if let Some(paint) = paint {
             paint
@@ -298,6 +453,23 @@ impl<W> TinySkiaRenderer<W> {
         self.render_pixmap_rect(&fill, rect);
     }
 
+    fn fill_paint(&mut self, shape: &impl Shape, paint: &Paint) {
+        if let Some(rect) = shape.as_rect() {
+            let rect = try_ret!(self.rect(rect));
+            self.pixmap
+                .fill_rect(rect, paint, self.current_transform(), None);
+        } else {
+            let path = try_ret!(self.shape_to_path(shape));
+            self.pixmap.fill_path(
+                &path,
+                paint,
+                FillRule::Winding,
+                self.current_transform(),
+                self.clip.is_some().then_some(&self.mask),
+            );
+        }
+    }
+
     fn current_transform(&self) -> Transform {
         let transform = self.transform.as_coeffs();
         let scale = self.scale as f32;
@@ -360,13 +532,20 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
     for TinySkiaRenderer<W>
 {
     fn begin(&mut self, _capture: bool) {
+        if !self.layers.is_empty() {
+            // Layers left over from an unbalanced frame.
+            self.pixmap = self.layers.swap_remove(0).pixmap;
+            self.layers.clear();
+        }
         self.transform = Affine::IDENTITY;
         self.pixmap.fill(tiny_skia::Color::WHITE);
         self.clip = None;
     }
 
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
-        let paint = try_ret!(self.brush_to_paint(brush));
+        let bounds = shape.bounding_box().inflate(width / 2.0, width / 2.0);
+        let mut pattern = None;
+        let paint = try_ret!(self.brush_to_paint(brush, bounds, &mut pattern));
         let path = try_ret!(self.shape_to_path(shape));
         self.pixmap.stroke_path(
             &path,
@@ -383,21 +562,73 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
     fn fill<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, _blur_radius: f64) {
         // FIXME: Handle _blur_radius
 
-        let paint = try_ret!(self.brush_to_paint(brush));
-        if let Some(rect) = shape.as_rect() {
-            let rect = try_ret!(self.rect(rect));
-            self.pixmap
-                .fill_rect(rect, &paint, self.current_transform(), None);
+        let brush = brush.into();
+        if let BrushRef::Image(img) = brush {
+            let origin = shape.bounding_box().origin();
+            let rect = Rect::from_origin_size(origin, (img.width as f64, img.height as f64));
+            return self.fill_img(shape, img, rect, img.extend != Extend::Pad);
+        }
+
+        let mut pattern = None;
+        let paint = try_ret!(self.brush_to_paint(brush, shape.bounding_box(), &mut pattern));
+        self.fill_paint(shape, &paint);
+    }
+
+    fn fill_img(&mut self, shape: &impl Shape, img: &peniko::Image, rect: Rect, repeat: bool) {
+        // A single copy gets a transparent border, so padding doesn't smear its edges over the shape.
+        let border = if repeat { 0 } else { 1 };
+        let mut hash = floem_renderer::image_brush_hash(img);
+        hash.push(border as u8);
+
+        let pixmap = if let Some((color, pixmap)) = self.image_cache.get_mut(&hash) {
+            *color = self.cache_color;
+            pixmap.clone()
         } else {
-            let path = try_ret!(self.shape_to_path(shape));
-            self.pixmap.fill_path(
-                &path,
-                &paint,
-                FillRule::Winding,
-                self.current_transform(),
-                self.clip.is_some().then_some(&self.mask),
+            let image = try_ret!(RgbaImage::from_raw(
+                img.width,
+                img.height,
+                img.data.data().to_vec()
+            ));
+            let tile = try_ret!(rgba_to_pixmap(&image));
+            let mut pixmap = try_ret!(Pixmap::new(img.width + 2 * border, img.height + 2 * border));
+            pixmap.draw_pixmap(
+                border as i32,
+                border as i32,
+                tile.as_ref(),
+                &PixmapPaint::default(),
+                Transform::identity(),
+                None,
             );
-        }
+            let pixmap = Rc::new(pixmap);
+            self.image_cache
+                .insert(hash, (self.cache_color, pixmap.clone()));
+            pixmap
+        };
+
+        let sx = rect.width() / img.width as f64;
+        let sy = rect.height() / img.height as f64;
+        let paint = Paint {
+            shader: Pattern::new(
+                pixmap.as_ref().as_ref(),
+                if repeat {
+                    SpreadMode::Repeat
+                } else {
+                    SpreadMode::Pad
+                },
+                FilterQuality::Bilinear,
+                1.0,
+                Transform::from_row(
+                    sx as f32,
+                    0.0,
+                    0.0,
+                    sy as f32,
+                    (rect.x0 - border as f64 * sx) as f32,
+                    (rect.y0 - border as f64 * sy) as f32,
+                ),
+            ),
+            ..Default::default()
+        };
+        self.fill_paint(shape, &paint);
     }
 
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
@@ -470,12 +701,7 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
         }
 
         let rgba_image = img.img.clone().into_rgba8();
-        let mut pixmap = try_ret!(Pixmap::new(rgba_image.width(), rgba_image.height()));
-        for (a, &b) in pixmap.pixels_mut().iter_mut().zip(rgba_image.pixels()) {
-            *a = tiny_skia::Color::from_rgba8(b.0[0], b.0[1], b.0[2], b.0[3])
-                .premultiply()
-                .to_color_u8();
-        }
+        let pixmap = try_ret!(rgba_to_pixmap(&rgba_image));
 
         self.render_pixmap_rect(&pixmap, rect);
 
@@ -483,6 +709,10 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
             .insert(img.hash.to_owned(), (self.cache_color, Rc::new(pixmap)));
     }
 
+    fn draw_texture(&mut self, _texture: &Arc<wgpu::TextureView>, _rect: Rect) {
+        // GPU textures can't be read back into the pixmap without blocking on the device.
+    }
+
     fn draw_svg<'b>(
         &mut self,
         svg: floem_renderer::Svg<'b>,
@@ -492,9 +722,13 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
         let width = (rect.width() * self.scale).round() as u32;
         let height = (rect.height() * self.scale).round() as u32;
 
-        let rect = try_ret!(self.rect(rect));
+        let mut pattern = None;
+        let paint = match brush {
+            Some(brush) => self.brush_to_paint(brush, rect, &mut pattern),
+            None => None,
+        };
 
-        let paint = brush.and_then(|brush| self.brush_to_paint(brush));
+        let rect = try_ret!(self.rect(rect));
 
         if let Some((color, pixmap)) = self.image_cache.get_mut(svg.hash) {
             *color = self.cache_color;
@@ -547,6 +781,38 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
         self.clip = None;
     }
 
+    fn push_layer(&mut self, alpha: f32, blend_mode: impl Into<BlendMode>, clip: &impl Shape) {
+        let clip = self.shape_to_path(clip).and_then(|path| {
+            let mut mask = Mask::new(self.pixmap.width(), self.pixmap.height())?;
+            mask.fill_path(&path, FillRule::Winding, true, self.current_transform());
+            Some(mask)
+        });
+        let layer = try_ret!(Pixmap::new(self.pixmap.width(), self.pixmap.height()));
+        self.layers.push(Layer {
+            pixmap: std::mem::replace(&mut self.pixmap, layer),
+            alpha,
+            blend_mode: to_blend_mode(blend_mode.into()),
+            clip,
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        let layer = try_ret!(self.layers.pop());
+        let content = std::mem::replace(&mut self.pixmap, layer.pixmap);
+        self.pixmap.draw_pixmap(
+            0,
+            0,
+            content.as_ref(),
+            &PixmapPaint {
+                opacity: layer.alpha,
+                blend_mode: layer.blend_mode,
+                quality: FilterQuality::Nearest,
+            },
+            Transform::identity(),
+            layer.clip.as_ref(),
+        );
+    }
+
     fn finish<F>(&mut self, callback: F) -> Option<DynamicImage>
     where
         F: FnOnce(
@@ -570,8 +836,11 @@ impl<W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle
         // Swap the cache color.
         self.cache_color = CacheColor(!self.cache_color.0);
 
-        let mut buffer = self
-            .surface
+        let Some(surface) = self.surface.as_mut() else {
+            return self.render_image();
+        };
+
+        let mut buffer = surface
             .buffer_mut()
             .expect("failed to get the surface buffer");
 
@@ -1,21 +1,26 @@
+mod texture_compositor;
+
+use std::collections::HashMap;
 use std::mem;
 use std::sync::mpsc::sync_channel;
 use std::sync::Arc;
 
 use anyhow::Result;
+use floem_renderer::config::{FrameFormat, RendererConfig};
 use floem_renderer::gpu_resources::GpuResources;
 use floem_renderer::swash::SwashScaler;
 use floem_renderer::text::{self, CacheKey, TextLayout};
-use floem_renderer::{tiny_skia, Img, Renderer};
-use floem_vger_rs::{Image, PaintIndex, PixelFormat, Vger};
+use floem_renderer::{gradient, tiny_skia, Img, Renderer};
+use floem_vger_rs::{Image, ImageIndex, PaintIndex, PixelFormat, Vger};
 use image::{DynamicImage, EncodableLayout, RgbaImage};
 use peniko::kurbo::Size;
 use peniko::{
     kurbo::{Affine, Point, Rect, Shape},
-    BrushRef, Color, GradientKind,
+    BlendMode, BrushRef, Color, Extend, Gradient, GradientKind,
 };
 use sha2::Digest;
 use sha2::Sha256;
+use texture_compositor::{Blend, TextureCompositor, TextureDraw};
 use wgpu::{
     Device, DeviceType, Queue, StoreOp, Surface, SurfaceConfiguration, TextureFormat, TextureView,
 };
@@ -26,17 +31,191 @@ pub struct VgerRenderer {
     // queue: Arc<Queue>,
     // surface: Surface<'static>,
     gpu_resources: Arc<GpuResources>,
-    vger: Vger,
-    alt_vger: Option<Vger>,
+    /// The vger instance recording the segment of the frame being painted, see [`Step`].
+    painter: Painter,
+    /// The vger instances recording the other segments of the frame, by segment index.
+    /// The slot of the segment being painted is `None`.
+    painters: Vec<Option<Painter>>,
+    /// The index of the segment `painter` records.
+    segment: usize,
+    /// Whether anything was drawn into the segment being painted.
+    segment_drawn: bool,
+    /// What the frame is made of so far, in paint order.
+    steps: Vec<Step>,
     config: SurfaceConfiguration,
     scale: f64,
     transform: Affine,
     clip: Option<Rect>,
+    /// The scissor set on vger, in physical pixels, with its corner radius.
+    scissor: Option<(Rect, f32)>,
     capture: bool,
     swash_scaler: SwashScaler,
     frame_count: u32,
-    pub multisampled_texture: Arc<wgpu::Texture>,
-    pub multisampled_view: Arc<wgpu::TextureView>,
+    /// The cache color value set for cache entries accessed this frame.
+    cache_color: CacheColor,
+    /// The layers pushed and not popped yet, from the outermost.
+    layers: Vec<Layer>,
+    /// The textures layers are rendered into, when they aren't in use.
+    layer_textures: Vec<LayerTexture>,
+    z_index: i32,
+    texture_compositor: Option<TextureCompositor>,
+    frame_format: FrameFormat,
+    /// The target vger draws into before it is resolved to the surface, if the frame is
+    /// multisampled. Otherwise vger draws into the surface directly.
+    pub multisampled_texture: Option<Arc<wgpu::Texture>>,
+    pub multisampled_view: Option<Arc<wgpu::TextureView>>,
+    /// The depth/stencil texture given to the engine, if the renderer was configured with one.
+    pub depth_stencil_texture: Option<Arc<wgpu::Texture>>,
+    pub depth_stencil_view: Option<Arc<wgpu::TextureView>>,
+}
+
+type FrameTarget = (Arc<wgpu::Texture>, Arc<wgpu::TextureView>);
+
+#[derive(PartialEq, Clone, Copy)]
+struct CacheColor(bool);
+
+/// A vger instance and the images uploaded to it for image patterns.
+struct Painter {
+    vger: Vger,
+    /// Gradients vger has no native paint for are keyed by [`gradient::rasterized_hash`],
+    /// image brushes by [`floem_renderer::image_brush_hash`].
+    image_cache: HashMap<Vec<u8>, (CacheColor, ImageIndex)>,
+}
+
+impl Painter {
+    /// Creates a vger instance whose pipelines draw frames of the given format.
+    fn new(gpu_resources: &Arc<GpuResources>, frame_format: FrameFormat) -> Self {
+        Self {
+            vger: Vger::new(
+                gpu_resources.clone(),
+                frame_format.format,
+                frame_format.sample_count,
+                frame_format
+                    .depth_stencil_format
+                    .map(ui_depth_stencil_state),
+            ),
+            image_cache: HashMap::new(),
+        }
+    }
+
+    /// Removes cache entries which were not accessed with `cache_color`, and deletes their
+    /// images.
+    fn evict(&mut self, cache_color: CacheColor) {
+        let vger = &mut self.vger;
+        self.image_cache.retain(|_, (color, image)| {
+            let keep = *color == cache_color;
+            if !keep {
+                vger.delete_image(*image);
+            }
+            keep
+        });
+    }
+}
+
+/// A part of the frame, rendered in paint order.
+///
+/// vger renders everything it records in a single pass, so content painted over a texture is
+/// recorded by another vger instance than content painted under it. Each run of content
+/// between two textures is a segment, recorded by the instance with the segment's index.
+enum Step {
+    /// The segment with the given index.
+    Vger(usize),
+    Texture(TextureDraw),
+    /// A layer, rendered offscreen and then composited onto what was painted before it.
+    Layer(Layer),
+}
+
+/// A layer started by `push_layer`.
+struct Layer {
+    /// Until the layer is popped, what was painted before it. Once popped, its content.
+    steps: Vec<Step>,
+    alpha: f32,
+    blend_mode: BlendMode,
+    /// The clip in physical pixels, with its corner radius.
+    clip: (Rect, f32),
+}
+
+/// A texture a layer is rendered into and composited from, and the multisampled texture it is
+/// resolved from if the frame is multisampled.
+struct LayerTexture {
+    multisampled_view: Option<TextureView>,
+    view: Arc<TextureView>,
+}
+
+impl LayerTexture {
+    fn new(device: &Device, config: &SurfaceConfiguration, sample_count: u32) -> Self {
+        let create = |label, sample_count, usage| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    size: wgpu::Extent3d {
+                        width: config.width,
+                        height: config.height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: config.format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | usage,
+                    label: Some(label),
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        Self {
+            multisampled_view: (sample_count > 1).then(|| {
+                create(
+                    "Multisampled layer texture",
+                    sample_count,
+                    wgpu::TextureUsages::empty(),
+                )
+            }),
+            view: Arc::new(create(
+                "Layer texture",
+                1,
+                wgpu::TextureUsages::TEXTURE_BINDING,
+            )),
+        }
+    }
+}
+
+/// The color attachment of the passes rendering a frame.
+#[derive(Clone, Copy)]
+struct PassTarget<'a> {
+    /// The texture passes draw into.
+    view: &'a TextureView,
+    /// The texture `view` is resolved to at the end of each pass, if `view` is multisampled.
+    resolve_target: Option<&'a TextureView>,
+}
+
+impl<'a> PassTarget<'a> {
+    /// Passes drawing into `target`, through `multisampled` if the frame is multisampled.
+    fn new(multisampled: Option<&'a TextureView>, target: &'a TextureView) -> Self {
+        match multisampled {
+            Some(view) => Self {
+                view,
+                resolve_target: Some(target),
+            },
+            None => Self {
+                view: target,
+                resolve_target: None,
+            },
+        }
+    }
+
+    fn color_attachment(
+        &self,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) -> wgpu::RenderPassColorAttachment<'a> {
+        wgpu::RenderPassColorAttachment {
+            view: self.view,
+            resolve_target: self.resolve_target,
+            ops: wgpu::Operations {
+                load,
+                store: StoreOp::Store,
+            },
+        }
+    }
 }
 
 impl VgerRenderer {
@@ -47,6 +226,7 @@ impl VgerRenderer {
         height: u32,
         scale: f64,
         font_embolden: f32,
+        renderer_config: RendererConfig,
     ) -> Result<Self> {
         // let GpuResources {
         //     surface,
@@ -61,7 +241,6 @@ impl VgerRenderer {
             .expect("Couldn't get gpu surface");
         let adapter = &gpu_resources_ref.adapter;
         let device = &gpu_resources_ref.device;
-        let queue = &gpu_resources_ref.queue;
 
         if adapter.get_info().device_type == DeviceType::Cpu {
             return Err(anyhow::anyhow!("only cpu adapter found"));
@@ -80,87 +259,54 @@ impl VgerRenderer {
             ));
         }
 
-        let device = Arc::new(device);
-        let queue = Arc::new(queue);
-
-        let surface_caps = surface.get_capabilities(&adapter);
-        // let texture_format = surface_caps
-        //     .formats
-        //     .into_iter()
-        //     .find(|it| {
-        //         matches!(
-        //             it,
-        //             TextureFormat::Rgba8Unorm | TextureFormat::Bgra8UnormSrgb
-        //         )
-        //     })
-        //     .ok_or_else(|| {
-        //         anyhow::anyhow!("surface should support Rgba8Unorm or Bgra8UnormSrgb")
-        //     })?;
-        let texture_format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let surface_caps = surface.get_capabilities(adapter);
+        let frame_format = renderer_config.resolve(&surface_caps, adapter)?;
 
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: texture_format,
+            format: frame_format.format,
             width,
             height,
-            present_mode: wgpu::PresentMode::Fifo,
+            present_mode: renderer_config.present_mode,
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
             // alpha_mode: wgpu::CompositeAlphaMode::PreMultiplied,
             // alpha_mode: wgpu::CompositeAlphaMode::Inherit,
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
-        surface.configure(&device, &config);
-
-        // let vger = floem_vger_rs::Vger::new(device.clone(), queue.clone(), texture_format);
-
-        // if let Some(gpu_resources) = &window_handle.gpu_resources {
-        // let device = Arc::clone(&device);
-        // let queue = Arc::clone(&queue);
-        let vger = floem_vger_rs::Vger::new(gpu_resources.clone(), texture_format);
-        // ... rest of your code
-        // }
-
-        // let device = Arc::clone(&device.clone());
-        // let queue = Arc::clone(&queue);
-
-        let multisampled_texture = gpu_resources
-            .device
-            .create_texture(&wgpu::TextureDescriptor {
-                size: wgpu::Extent3d {
-                    width: config.width,
-                    height: config.height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 4,
-                dimension: wgpu::TextureDimension::D2,
-                format: config.format,
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                label: Some("Multisampled render texture"),
-                view_formats: &[],
-            });
+        surface.configure(device, &config);
 
-        let multisampled_texture = Arc::new(multisampled_texture);
+        let painter = Painter::new(&gpu_resources, frame_format);
 
-        let multisampled_view =
-            multisampled_texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        let multisampled_view = Arc::new(multisampled_view);
+        let (multisampled, depth_stencil) = create_frame_targets(device, &config, frame_format);
+        let (multisampled_texture, multisampled_view) = multisampled.unzip();
+        let (depth_stencil_texture, depth_stencil_view) = depth_stencil.unzip();
 
         Ok(Self {
             gpu_resources,
-            vger,
-            alt_vger: None,
+            painter,
+            painters: vec![None],
+            segment: 0,
+            segment_drawn: false,
+            steps: Vec::new(),
             scale,
             config,
             transform: Affine::IDENTITY,
             clip: None,
+            scissor: None,
             capture: false,
             swash_scaler: SwashScaler::new(font_embolden),
             frame_count: 0,
+            cache_color: CacheColor(false),
+            layers: Vec::new(),
+            layer_textures: Vec::new(),
+            z_index: 0,
+            texture_compositor: None,
+            frame_format,
             multisampled_texture,
             multisampled_view,
+            depth_stencil_texture,
+            depth_stencil_view,
         })
     }
 
@@ -173,33 +319,11 @@ impl VgerRenderer {
                 return;
             }
 
-            let multisampled_texture =
-                self.gpu_resources
-                    .device
-                    .create_texture(&wgpu::TextureDescriptor {
-                        size: wgpu::Extent3d {
-                            width: self.config.width,
-                            height: self.config.height,
-                            depth_or_array_layers: 1,
-                        },
-                        mip_level_count: 1,
-                        sample_count: 4,
-                        dimension: wgpu::TextureDimension::D2,
-                        format: self.config.format,
-                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                        label: Some("Multisampled render texture"),
-                        view_formats: &[],
-                    });
-
-            let multisampled_texture = Arc::new(multisampled_texture);
-
-            let multisampled_view =
-                multisampled_texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-            let multisampled_view = Arc::new(multisampled_view);
-
-            self.multisampled_texture = multisampled_texture;
-            self.multisampled_view = multisampled_view;
+            let (multisampled, depth_stencil) =
+                create_frame_targets(&self.gpu_resources.device, &self.config, self.frame_format);
+            (self.multisampled_texture, self.multisampled_view) = multisampled.unzip();
+            (self.depth_stencil_texture, self.depth_stencil_view) = depth_stencil.unzip();
+            self.layer_textures.clear();
 
             let surface = self
                 .gpu_resources
@@ -223,38 +347,138 @@ impl VgerRenderer {
     pub fn size(&self) -> Size {
         Size::new(self.config.width as f64, self.config.height as f64)
     }
+
+    /// The GPU device, queue and surface this renderer draws with.
+    pub fn gpu_resources(&self) -> &Arc<GpuResources> {
+        &self.gpu_resources
+    }
+
+    /// The format and sample count of the frames this renderer draws.
+    pub fn frame_format(&self) -> FrameFormat {
+        self.frame_format
+    }
+
+    /// The present mode the surface is configured with.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.config.present_mode
+    }
 }
 
 impl VgerRenderer {
-    fn brush_to_paint<'b>(&mut self, brush: impl Into<BrushRef<'b>>) -> Option<PaintIndex> {
+    /// Converts a brush into a paint for a shape with the given local `bounds`.
+    fn brush_to_paint<'b>(
+        &mut self,
+        brush: impl Into<BrushRef<'b>>,
+        bounds: Rect,
+    ) -> Option<PaintIndex> {
         let paint = match brush.into() {
-            BrushRef::Solid(color) => self.vger.color_paint(vger_color(color)),
+            BrushRef::Solid(color) => self.painter.vger.color_paint(vger_color(color)),
             BrushRef::Gradient(g) => match g.kind {
-                GradientKind::Linear { start, end } => {
-                    let mut stops = g.stops.iter();
-                    let first_stop = stops.next()?;
-                    let second_stop = stops.next()?;
-                    let inner_color = vger_color(first_stop.color);
-                    let outer_color = vger_color(second_stop.color);
-                    let start = floem_vger_rs::defs::LocalPoint::new(
-                        start.x as f32 * first_stop.offset,
-                        start.y as f32 * first_stop.offset,
-                    );
-                    let end = floem_vger_rs::defs::LocalPoint::new(
-                        end.x as f32 * second_stop.offset,
-                        end.y as f32 * second_stop.offset,
-                    );
-                    self.vger
-                        .linear_gradient(start, end, inner_color, outer_color, 0.0)
+                // vger's own linear gradients only interpolate between two colors and pad.
+                GradientKind::Linear { start, end }
+                    if g.stops.len() == 2 && g.extend == Extend::Pad =>
+                {
+                    let (first_stop, second_stop) = (g.stops[0], g.stops[1]);
+                    let axis = end - start;
+                    let inner = start + axis * first_stop.offset as f64;
+                    let outer = start + axis * second_stop.offset as f64;
+                    self.painter.vger.linear_gradient(
+                        self.vger_point(inner),
+                        self.vger_point(outer),
+                        vger_color(first_stop.color),
+                        vger_color(second_stop.color),
+                        0.0,
+                    )
                 }
-                GradientKind::Radial { .. } => return None,
-                GradientKind::Sweep { .. } => return None,
+                _ => self.rasterized_gradient(g, bounds)?,
             },
             BrushRef::Image(_) => return None,
         };
         Some(paint)
     }
 
+    /// Rasterizes `gradient` over `bounds` and returns an image pattern covering them.
+    fn rasterized_gradient(&mut self, gradient: &Gradient, bounds: Rect) -> Option<PaintIndex> {
+        if gradient.stops.is_empty() {
+            return None;
+        }
+        let start = self.vger_point(bounds.origin());
+        let end = self.vger_point(Point::new(bounds.x1, bounds.y1));
+        // The texture is resampled when drawn, so cap its size for very large shapes.
+        let width = (end.x - start.x).abs().ceil().clamp(1.0, 1024.0) as u32;
+        let height = (end.y - start.y).abs().ceil().clamp(1.0, 1024.0) as u32;
+
+        let key = gradient::rasterized_hash(gradient, bounds, width, height);
+        let image = if let Some((color, image)) = self.painter.image_cache.get_mut(&key) {
+            *color = self.cache_color;
+            *image
+        } else {
+            let pixels = gradient::rasterize(gradient, bounds, width, height)?;
+            let image = self
+                .painter
+                .vger
+                .create_image_pixels(pixels.as_bytes(), width, height);
+            self.painter
+                .image_cache
+                .insert(key, (self.cache_color, image));
+            image
+        };
+        Some(self.painter.vger.image_pattern(start, end, image, 1.0))
+    }
+
+    fn fill_paint(&mut self, path: &impl Shape, paint: PaintIndex, blur_radius: f64) {
+        self.segment_drawn = true;
+        let coeffs = self.transform.as_coeffs();
+        let scale = (coeffs[0] + coeffs[3]) / 2. * self.scale;
+        if let Some(rect) = path.as_rect() {
+            self.painter.vger.fill_rect(
+                self.vger_rect(rect),
+                0.0,
+                paint,
+                (blur_radius * scale) as f32,
+            );
+        } else if let Some(rect) = path.as_rounded_rect() {
+            self.painter.vger.fill_rect(
+                self.vger_rect(rect.rect()),
+                (rect.radii().top_left * scale) as f32,
+                paint,
+                (blur_radius * scale) as f32,
+            );
+        } else if let Some(circle) = path.as_circle() {
+            self.painter.vger.fill_circle(
+                self.vger_point(circle.center),
+                (circle.radius * scale) as f32,
+                paint,
+            )
+        } else {
+            let mut first = true;
+            for segment in path.path_segments(0.1) {
+                match segment {
+                    peniko::kurbo::PathSeg::Line(line) => {
+                        if first {
+                            first = false;
+                            self.painter.vger.move_to(self.vger_point(line.p0));
+                        }
+                        self.painter
+                            .vger
+                            .quad_to(self.vger_point(line.p1), self.vger_point(line.p1));
+                    }
+                    peniko::kurbo::PathSeg::Quad(quad) => {
+                        if first {
+                            first = false;
+                            self.painter.vger.move_to(self.vger_point(quad.p0));
+                        }
+                        self.painter
+                            .vger
+                            .quad_to(self.vger_point(quad.p1), self.vger_point(quad.p2));
+                    }
+                    peniko::kurbo::PathSeg::Cubic(_) => {}
+                }
+            }
+            self.painter.vger.fill(paint);
+        }
+    }
+
     fn vger_point(&self, point: Point) -> floem_vger_rs::defs::LocalPoint {
         let coeffs = self.transform.as_coeffs();
 
@@ -278,7 +502,205 @@ impl VgerRenderer {
         floem_vger_rs::defs::LocalRect::new(origin, size)
     }
 
-    fn render_image(&mut self, encoder: &mut wgpu::CommandEncoder) -> Option<DynamicImage> {
+    /// Returns the bounds of `rect` in physical pixels, once transformed.
+    fn physical_rect(&self, rect: Rect) -> Rect {
+        let origin = self.vger_point(rect.origin());
+        let end = self.vger_point(Point::new(rect.x1, rect.y1));
+        Rect::new(origin.x as f64, origin.y as f64, end.x as f64, end.y as f64).abs()
+    }
+
+    /// Sets the scissor of vger, a rect in physical pixels with a corner radius.
+    fn set_scissor(&mut self, scissor: Option<(Rect, f32)>) {
+        self.scissor = scissor;
+        match scissor {
+            Some((rect, radius)) => {
+                let origin = floem_vger_rs::defs::LocalPoint::new(rect.x0 as f32, rect.y0 as f32);
+                let end = floem_vger_rs::defs::LocalPoint::new(rect.x1 as f32, rect.y1 as f32);
+                let rect = floem_vger_rs::defs::LocalRect::new(origin, (end - origin).to_size());
+                self.painter.vger.scissor(rect, radius);
+            }
+            None => self.painter.vger.reset_scissor(),
+        }
+    }
+
+    /// Starts recording the current segment, with the scissor and z-index painted with so far.
+    fn begin_segment(&mut self) {
+        self.painter.vger.begin(
+            self.config.width as f32,
+            self.config.height as f32,
+            self.scale as f32,
+        );
+        self.set_scissor(self.scissor);
+        self.painter.vger.set_z_index(self.z_index);
+        self.segment_drawn = false;
+    }
+
+    /// Ends the segment being recorded, if anything was drawn into it, so what is painted
+    /// next is rendered over it.
+    fn end_segment(&mut self) {
+        if !self.segment_drawn {
+            return;
+        }
+        self.steps.push(Step::Vger(self.segment));
+        self.switch_painter(self.segment + 1);
+        self.begin_segment();
+    }
+
+    /// Makes `painter` the vger instance of the segment with the given index.
+    fn switch_painter(&mut self, segment: usize) {
+        if segment == self.segment {
+            return;
+        }
+        if self.painters.len() <= segment {
+            self.painters.resize_with(segment + 1, || None);
+        }
+        let painter = self.painters[segment]
+            .take()
+            .unwrap_or_else(|| Painter::new(&self.gpu_resources, self.frame_format));
+        self.painters[self.segment] = Some(mem::replace(&mut self.painter, painter));
+        self.segment = segment;
+    }
+
+    fn painter_mut(&mut self, segment: usize) -> &mut Painter {
+        if segment == self.segment {
+            &mut self.painter
+        } else {
+            self.painters[segment]
+                .as_mut()
+                .expect("Every recorded segment has a painter")
+        }
+    }
+
+    /// Records the passes rendering `steps` over `target`. Each segment is encoded by its
+    /// vger instance into a new command encoder, so the commands recorded before are moved
+    /// to `command_buffers` and recording continues in `encoder`.
+    fn encode_steps(
+        &mut self,
+        steps: &[Step],
+        target: PassTarget,
+        command_buffers: &mut Vec<wgpu::CommandBuffer>,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let textures = |a: &Step, b: &Step| matches!((a, b), (Step::Texture(_), Step::Texture(_)));
+        let (width, height) = (self.config.width, self.config.height);
+        let depth_stencil_view = self.depth_stencil_view.clone();
+        for steps in steps.chunk_by(textures) {
+            match &steps[0] {
+                Step::Vger(segment) => {
+                    let desc = wgpu::RenderPassDescriptor {
+                        label: None,
+                        color_attachments: &[Some(target.color_attachment(wgpu::LoadOp::Load))],
+                        // vger's pipelines test against the depth/stencil texture the engine drew
+                        // the scene with.
+                        depth_stencil_attachment: depth_stencil_view.as_deref().map(|view| {
+                            depth_stencil_attachment(
+                                view,
+                                self.frame_format.depth_stencil_format,
+                                wgpu::LoadOp::Load,
+                                wgpu::LoadOp::Load,
+                                StoreOp::Store,
+                            )
+                        }),
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    };
+                    let vger = &mut self.painter_mut(*segment).vger;
+                    let segment_encoder = vger.encode(&desc).expect("Couldn't get encoder");
+                    command_buffers.push(mem::replace(encoder, segment_encoder).finish());
+                    vger.run_render_pass(&desc, encoder);
+                }
+                Step::Texture(_) => {
+                    let draws: Vec<&TextureDraw> = steps
+                        .iter()
+                        .filter_map(|step| match step {
+                            Step::Texture(draw) => Some(draw),
+                            _ => None,
+                        })
+                        .collect();
+                    let device = &self.gpu_resources.device;
+                    let frame_format = self.frame_format;
+                    self.texture_compositor
+                        .get_or_insert_with(|| {
+                            TextureCompositor::new(
+                                device,
+                                frame_format.format,
+                                frame_format.sample_count,
+                            )
+                        })
+                        .encode(
+                            device,
+                            encoder,
+                            target.color_attachment(wgpu::LoadOp::Load),
+                            width,
+                            height,
+                            &draws,
+                        );
+                }
+                Step::Layer(layer) => {
+                    let texture = self.layer_textures.pop().unwrap_or_else(|| {
+                        LayerTexture::new(
+                            &self.gpu_resources.device,
+                            &self.config,
+                            self.frame_format.sample_count,
+                        )
+                    });
+                    let layer_target =
+                        PassTarget::new(texture.multisampled_view.as_ref(), &texture.view);
+                    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("Layer clear pass"),
+                        color_attachments: &[Some(
+                            layer_target
+                                .color_attachment(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+                        )],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+                    self.encode_steps(&layer.steps, layer_target, command_buffers, encoder);
+
+                    let (w, h) = (width as f32, height as f32);
+                    let draw = TextureDraw {
+                        texture: texture.view.clone(),
+                        corners: [[0.0, 0.0], [w, 0.0], [w, h], [0.0, h]],
+                        clip: Some(layer.clip),
+                        alpha: layer.alpha,
+                        blend: Blend::Mode(layer.blend_mode),
+                    };
+                    let device = &self.gpu_resources.device;
+                    let frame_format = self.frame_format;
+                    self.texture_compositor
+                        .get_or_insert_with(|| {
+                            TextureCompositor::new(
+                                device,
+                                frame_format.format,
+                                frame_format.sample_count,
+                            )
+                        })
+                        .encode(
+                            device,
+                            encoder,
+                            target.color_attachment(wgpu::LoadOp::Load),
+                            width,
+                            height,
+                            &[&draw],
+                        );
+                    self.layer_textures.push(texture);
+                }
+            }
+        }
+    }
+
+    /// Renders `steps` into a texture instead of the surface, and reads it back.
+    fn render_image(&mut self, steps: &[Step]) -> Option<DynamicImage> {
+        // Only frames with 8 bits per channel can be read back as an RGBA image.
+        let bgra = match self.config.format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            _ => return None,
+        };
+        let gpu_resources = self.gpu_resources.clone();
+        let device = &gpu_resources.device;
+
         let width_align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT - 1;
         let width = (self.config.width + width_align) & !width_align;
         let height = self.config.height;
@@ -291,46 +713,48 @@ impl VgerRenderer {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
+            format: self.config.format,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
             label: Some("render_texture"),
-            view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+            view_formats: &[],
         };
-        let texture = self.gpu_resources.device.create_texture(&texture_desc);
+        let texture = device.create_texture(&texture_desc);
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let view = Arc::new(view);
-        let desc = wgpu::RenderPassDescriptor {
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                    store: StoreOp::Store,
-                },
-            })],
-            ..Default::default()
-        };
-
-        // self.vger.encode(&desc);
-        self.vger.run_render_pass(&desc, encoder);
+        let multisampled_view = self.multisampled_view.clone();
+        let target = PassTarget::new(multisampled_view.as_deref(), &view);
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Capture clear pass"),
+            color_attachments: &[Some(
+                target.color_attachment(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+            )],
+            depth_stencil_attachment: self.depth_stencil_view.as_deref().map(|view| {
+                depth_stencil_attachment(
+                    view,
+                    self.frame_format.depth_stencil_format,
+                    wgpu::LoadOp::Clear(1.0),
+                    wgpu::LoadOp::Clear(0),
+                    StoreOp::Store,
+                )
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let mut command_buffers = Vec::new();
+        self.encode_steps(steps, target, &mut command_buffers, &mut encoder);
 
         let bytes_per_pixel = 4;
-        let buffer = self
-            .gpu_resources
-            .device
-            .create_buffer(&wgpu::BufferDescriptor {
-                label: None,
-                size: (width as u64 * height as u64 * bytes_per_pixel),
-                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (width as u64 * height as u64 * bytes_per_pixel),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
         let bytes_per_row = width * bytes_per_pixel as u32;
         assert!(bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0);
 
-        let mut encoder = self
-            .gpu_resources
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
         encoder.copy_texture_to_buffer(
             texture.as_image_copy(),
             wgpu::ImageCopyBuffer {
@@ -343,11 +767,9 @@ impl VgerRenderer {
             },
             texture_desc.size,
         );
-        // TODO: reimplemnt on stunts
-        // TODO: reimplement now with switched render passes?
-        let command_buffer = encoder.finish();
-        self.gpu_resources.queue.submit(Some(command_buffer));
-        self.gpu_resources.device.poll(wgpu::Maintain::Wait);
+        command_buffers.push(encoder.finish());
+        gpu_resources.queue.submit(command_buffers);
+        device.poll(wgpu::Maintain::Wait);
 
         let slice = buffer.slice(..);
         let (tx, rx) = sync_channel(1);
@@ -357,9 +779,7 @@ impl VgerRenderer {
             if let Ok(r) = rx.try_recv() {
                 break r.ok().expect("see");
             }
-            if let wgpu::MaintainResult::Ok =
-                self.gpu_resources.device.poll(wgpu::MaintainBase::Wait)
-            {
+            if let wgpu::MaintainResult::Ok = device.poll(wgpu::MaintainBase::Wait) {
                 rx.recv().ok().expect("see").ok().expect("see");
                 break;
             }
@@ -374,6 +794,11 @@ impl VgerRenderer {
             cropped_buffer.extend_from_slice(&buffer[cursor..(cursor + row_size)]);
             cursor += bytes_per_row as usize;
         }
+        if bgra {
+            for pixel in cropped_buffer.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
 
         // (
         //     Some(encoder),
@@ -389,38 +814,31 @@ impl VgerRenderer {
 
 impl Renderer for VgerRenderer {
     fn begin(&mut self, capture: bool) {
-        // Switch to the capture Vger if needed
-        if self.capture != capture {
-            self.capture = capture;
-            if self.alt_vger.is_none() {
-                self.alt_vger = Some(floem_vger_rs::Vger::new(
-                    self.gpu_resources.clone(),
-                    TextureFormat::Rgba8Unorm,
-                ));
-            }
-            mem::swap(&mut self.vger, self.alt_vger.as_mut().unwrap())
-        }
-
+        self.capture = capture;
         self.transform = Affine::IDENTITY;
-        self.vger.begin(
-            self.config.width as f32,
-            self.config.height as f32,
-            self.scale as f32,
-        );
+        self.clip = None;
+        self.scissor = None;
+        self.layers.clear();
+        self.z_index = 0;
+        self.steps.clear();
+        self.switch_painter(0);
+        self.begin_segment();
     }
 
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
         let coeffs = self.transform.as_coeffs();
         let scale = (coeffs[0] + coeffs[3]) / 2. * self.scale;
-        let paint = match self.brush_to_paint(brush) {
+        let bounds = shape.bounding_box().inflate(width / 2.0, width / 2.0);
+        let paint = match self.brush_to_paint(brush, bounds) {
             Some(paint) => paint,
             None => return,
         };
+        self.segment_drawn = true;
         let width = (width * scale).round() as f32;
         if let Some(rect) = shape.as_rect() {
             let min = rect.origin();
             let max = min + rect.size().to_vec2();
-            self.vger.stroke_rect(
+            self.painter.vger.stroke_rect(
                 self.vger_point(min),
                 self.vger_point(max),
                 0.0,
@@ -431,7 +849,7 @@ impl Renderer for VgerRenderer {
             let min = rect.origin();
             let max = min + rect.rect().size().to_vec2();
             let radius = (rect.radii().top_left * scale) as f32;
-            self.vger.stroke_rect(
+            self.painter.vger.stroke_rect(
                 self.vger_point(min),
                 self.vger_point(max),
                 radius,
@@ -439,14 +857,14 @@ impl Renderer for VgerRenderer {
                 paint,
             );
         } else if let Some(line) = shape.as_line() {
-            self.vger.stroke_segment(
+            self.painter.vger.stroke_segment(
                 self.vger_point(line.p0),
                 self.vger_point(line.p1),
                 width,
                 paint,
             );
         } else if let Some(circle) = shape.as_circle() {
-            self.vger.stroke_arc(
+            self.painter.vger.stroke_arc(
                 self.vger_point(circle.center),
                 (circle.radius * scale) as f32,
                 width,
@@ -457,14 +875,14 @@ impl Renderer for VgerRenderer {
         } else {
             for segment in shape.path_segments(0.0) {
                 match segment {
-                    peniko::kurbo::PathSeg::Line(ln) => self.vger.stroke_segment(
+                    peniko::kurbo::PathSeg::Line(ln) => self.painter.vger.stroke_segment(
                         self.vger_point(ln.p0),
                         self.vger_point(ln.p1),
                         width,
                         paint,
                     ),
                     peniko::kurbo::PathSeg::Quad(bez) => {
-                        self.vger.stroke_bezier(
+                        self.painter.vger.stroke_bezier(
                             self.vger_point(bez.p0),
                             self.vger_point(bez.p1),
                             self.vger_point(bez.p2),
@@ -480,56 +898,61 @@ impl Renderer for VgerRenderer {
     }
 
     fn fill<'b>(&mut self, path: &impl Shape, brush: impl Into<BrushRef<'b>>, blur_radius: f64) {
-        let coeffs = self.transform.as_coeffs();
-        let scale = (coeffs[0] + coeffs[3]) / 2. * self.scale;
-        let paint = match self.brush_to_paint(brush) {
+        let brush = brush.into();
+        if let BrushRef::Image(img) = brush {
+            let origin = path.bounding_box().origin();
+            let rect = Rect::from_origin_size(origin, (img.width as f64, img.height as f64));
+            return self.fill_img(path, img, rect, img.extend != Extend::Pad);
+        }
+
+        let paint = match self.brush_to_paint(brush, path.bounding_box()) {
             Some(paint) => paint,
             None => return,
         };
-        if let Some(rect) = path.as_rect() {
-            self.vger.fill_rect(
-                self.vger_rect(rect),
-                0.0,
-                paint,
-                (blur_radius * scale) as f32,
-            );
-        } else if let Some(rect) = path.as_rounded_rect() {
-            self.vger.fill_rect(
-                self.vger_rect(rect.rect()),
-                (rect.radii().top_left * scale) as f32,
-                paint,
-                (blur_radius * scale) as f32,
-            );
-        } else if let Some(circle) = path.as_circle() {
-            self.vger.fill_circle(
-                self.vger_point(circle.center),
-                (circle.radius * scale) as f32,
-                paint,
-            )
+        self.fill_paint(path, paint, blur_radius);
+    }
+
+    fn fill_img(&mut self, path: &impl Shape, img: &peniko::Image, rect: Rect, repeat: bool) {
+        let hash = floem_renderer::image_brush_hash(img);
+        let image = if let Some((color, image)) = self.painter.image_cache.get_mut(&hash) {
+            *color = self.cache_color;
+            *image
         } else {
-            let mut first = true;
-            for segment in path.path_segments(0.1) {
-                match segment {
-                    peniko::kurbo::PathSeg::Line(line) => {
-                        if first {
-                            first = false;
-                            self.vger.move_to(self.vger_point(line.p0));
-                        }
-                        self.vger
-                            .quad_to(self.vger_point(line.p1), self.vger_point(line.p1));
-                    }
-                    peniko::kurbo::PathSeg::Quad(quad) => {
-                        if first {
-                            first = false;
-                            self.vger.move_to(self.vger_point(quad.p0));
-                        }
-                        self.vger
-                            .quad_to(self.vger_point(quad.p1), self.vger_point(quad.p2));
-                    }
-                    peniko::kurbo::PathSeg::Cubic(_) => {}
-                }
-            }
-            self.vger.fill(paint);
+            let image =
+                self.painter
+                    .vger
+                    .create_image_pixels(img.data.data(), img.width, img.height);
+            self.painter
+                .image_cache
+                .insert(hash, (self.cache_color, image));
+            image
+        };
+        let paint = self.painter.vger.image_pattern(
+            self.vger_point(rect.origin()),
+            self.vger_point(Point::new(rect.x1, rect.y1)),
+            image,
+            1.0,
+        );
+
+        if repeat {
+            self.fill_paint(path, paint, 0.0);
+        } else if let Some(shape) = path.as_rect() {
+            self.fill_paint(&shape.intersect(rect), paint, 0.0);
+        } else if let Some(shape) = path.as_rounded_rect() {
+            let shape = shape.rect().intersect(rect).to_rounded_rect(shape.radii());
+            self.fill_paint(&shape, paint, 0.0);
+        } else {
+            // Scissor other shapes to the copy, so they don't show the pattern's repeats.
+            let scissor = self.scissor;
+            let copy = self.physical_rect(rect);
+            let copy = match scissor {
+                Some((clip, radius)) if clip.intersect(copy) == clip => (clip, radius),
+                Some((clip, _)) => (clip.intersect(copy), 0.0),
+                None => (copy, 0.0),
+            };
+            self.set_scissor(Some(copy));
+            self.fill_paint(path, paint, 0.0);
+            self.set_scissor(scissor);
         }
     }
 
@@ -551,6 +974,7 @@ impl Renderer for VgerRenderer {
             // TODO: render a rectangle instead
             return;
         }
+        self.segment_drawn = true;
 
         let clip = self.clip;
         for line in layout.layout_runs() {
@@ -583,7 +1007,7 @@ impl Renderer for VgerRenderer {
                     Some(c) => Color::rgba8(c.r(), c.g(), c.b(), c.a()),
                     None => Color::BLACK,
                 };
-                if let Some(paint) = self.brush_to_paint(color) {
+                if let Some(paint) = self.brush_to_paint(color, Rect::ZERO) {
                     let glyph_x = x * self.scale as f32;
                     let glyph_y = (y * self.scale as f32).round();
                     let font_size = (glyph_run.font_size * scale as f32).round() as u32;
@@ -597,7 +1021,7 @@ impl Renderer for VgerRenderer {
 
                     let glyph_x = new_x as f32;
                     let glyph_y = new_y as f32;
-                    self.vger.render_glyph(
+                    self.painter.vger.render_glyph(
                         glyph_x,
                         glyph_y,
                         glyph_run.font_id,
@@ -617,6 +1041,7 @@ impl Renderer for VgerRenderer {
 
     fn draw_img(&mut self, img: Img<'_>, rect: Rect) {
         self.frame_count = self.frame_count + 1;
+        self.segment_drawn = true;
         let transform = self.transform.as_coeffs();
 
         let scale_x = transform[0] * self.scale;
@@ -640,21 +1065,45 @@ impl Renderer for VgerRenderer {
         // hasher.update(&self.frame_count.to_le_bytes()); // You might need to add frame_count to the renderer
         // let force_hash = hasher.finalize().to_vec();
 
-        self.vger.render_image(x, y, img.hash, width, height, || {
-            let rgba = img.img.clone().into_rgba8();
-            let data = rgba.as_bytes().to_vec();
+        self.painter
+            .vger
+            .render_image(x, y, img.hash, width, height, || {
+                let rgba = img.img.clone().into_rgba8();
+                let data = rgba.as_bytes().to_vec();
 
-            let (width, height) = rgba.dimensions();
+                let (width, height) = rgba.dimensions();
 
-            println!("render image {:?} {:?} {:?}", width, height, data.len());
+                println!("render image {:?} {:?} {:?}", width, height, data.len());
 
-            Image {
-                width,
-                height,
-                data,
-                pixel_format: PixelFormat::Rgba,
-            }
-        });
+                Image {
+                    width,
+                    height,
+                    data,
+                    pixel_format: PixelFormat::Rgba,
+                }
+            });
+    }
+
+    fn draw_texture(&mut self, texture: &Arc<wgpu::TextureView>, rect: Rect) {
+        let corner = |x, y| {
+            let point = self.vger_point(Point::new(x, y));
+            [point.x, point.y]
+        };
+        let corners = [
+            corner(rect.x0, rect.y0),
+            corner(rect.x1, rect.y0),
+            corner(rect.x1, rect.y1),
+            corner(rect.x0, rect.y1),
+        ];
+        let draw = TextureDraw {
+            texture: texture.clone(),
+            corners,
+            clip: self.scissor,
+            alpha: 1.0,
+            blend: Blend::Over,
+        };
+        self.end_segment();
+        self.steps.push(Step::Texture(draw));
     }
 
     fn draw_svg<'b>(
@@ -680,9 +1129,10 @@ impl Renderer for VgerRenderer {
         let width = (rect.width() * scale_x).round().max(1.0) as u32;
         let height = (rect.height() * scale_y).round().max(1.0) as u32;
 
-        let paint = brush.and_then(|b| self.brush_to_paint(b));
+        let paint = brush.and_then(|b| self.brush_to_paint(b, rect));
 
-        self.vger.render_svg(
+        self.segment_drawn = true;
+        self.painter.vger.render_svg(
             x,
             y,
             svg.hash,
@@ -712,20 +1162,15 @@ impl Renderer for VgerRenderer {
     }
 
     fn set_z_index(&mut self, z_index: i32) {
-        self.vger.set_z_index(z_index);
+        self.z_index = z_index;
+        self.painter.vger.set_z_index(z_index);
     }
 
     fn clip(&mut self, shape: &impl Shape) {
-        let (rect, radius) = if let Some(rect) = shape.as_rect() {
-            (rect, 0.0)
-        } else if let Some(rect) = shape.as_rounded_rect() {
-            (rect.rect(), rect.radii().top_left)
-        } else {
-            (shape.bounding_box(), 0.0)
-        };
+        let (rect, radius) = clip_rect(shape);
 
-        self.vger
-            .scissor(self.vger_rect(rect), (radius * self.scale) as f32);
+        let scissor = (self.physical_rect(rect), (radius * self.scale) as f32);
+        self.set_scissor(Some(scissor));
 
         let transform = self.transform.as_coeffs();
 
@@ -746,10 +1191,31 @@ impl Renderer for VgerRenderer {
     }
 
     fn clear_clip(&mut self) {
-        self.vger.reset_scissor();
+        self.set_scissor(None);
         self.clip = None;
     }
 
+    fn push_layer(&mut self, alpha: f32, blend_mode: impl Into<BlendMode>, clip: &impl Shape) {
+        self.end_segment();
+        let (rect, radius) = clip_rect(clip);
+        let clip = (self.physical_rect(rect), (radius * self.scale) as f32);
+        self.layers.push(Layer {
+            steps: mem::take(&mut self.steps),
+            alpha,
+            blend_mode: blend_mode.into(),
+            clip,
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        let Some(mut layer) = self.layers.pop() else {
+            return;
+        };
+        self.end_segment();
+        mem::swap(&mut self.steps, &mut layer.steps);
+        self.steps.push(Step::Layer(layer));
+    }
+
     fn finish<F>(&mut self, callback: F) -> Option<DynamicImage>
     where
         F: FnOnce(
@@ -764,87 +1230,181 @@ impl Renderer for VgerRenderer {
             Option<Arc<wgpu::TextureView>>,
         ),
     {
-        let surface = self
-            .gpu_resources
-            .surface
-            .as_ref()
-            .expect("Couldn't get gpu surface");
+        while !self.layers.is_empty() {
+            self.pop_layer();
+        }
+        if self.segment_drawn {
+            self.steps.push(Step::Vger(self.segment));
+        }
+        let steps = mem::take(&mut self.steps);
+
+        let image = if self.capture {
+            self.render_image(&steps)
+        } else {
+            let surface = self
+                .gpu_resources
+                .surface
+                .as_ref()
+                .expect("Couldn't get gpu surface");
+
+            if let Ok(frame) = surface.get_current_texture() {
+                let texture_view = frame
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+
+                let texture_view = Arc::new(texture_view);
 
-        if let Ok(frame) = surface.get_current_texture() {
-            let texture_view = frame
-                .texture
-                .create_view(&wgpu::TextureViewDescriptor::default());
-
-            let texture_view = Arc::new(texture_view);
-
-            let desc = wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &self.multisampled_view,       // Use the multisampled view here
-                    resolve_target: Some(&texture_view), // Resolve to the swapchain texture
-                    ops: wgpu::Operations {
-                        // load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
-                        // store: StoreOp::Store,
-                        load: wgpu::LoadOp::Load,
-                        // store: wgpu::StoreOp::Store,
-                        store: wgpu::StoreOp::Discard,
+                // The color target engines draw into.
+                let multisampled_view = self.multisampled_view.clone();
+                let target = multisampled_view
+                    .clone()
+                    .unwrap_or_else(|| texture_view.clone());
+
+                let mut encoder = self.gpu_resources.device.create_command_encoder(
+                    &wgpu::CommandEncoderDescriptor {
+                        label: Some("Frame encoder"),
                     },
-                })],
-                depth_stencil_attachment: None,
-                // depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                //     view: &depth_view,
-                //     depth_ops: Some(wgpu::Operations {
-                //         load: wgpu::LoadOp::Clear(1.0),
-                //         store: StoreOp::Store,
-                //     }),
-                //     stencil_ops: None,
-                // }),
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            };
+                );
 
-            let mut encoder = self.vger.encode(&desc);
+                // render pass 0 (clears the frame, and the depth/stencil texture for the engine)
+                let clear_target = PassTarget::new(multisampled_view.as_deref(), &texture_view);
+                encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Clear pass"),
+                    color_attachments: &[Some(
+                        clear_target
+                            .color_attachment(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+                    )],
+                    depth_stencil_attachment: self.depth_stencil_view.as_deref().map(|view| {
+                        depth_stencil_attachment(
+                            view,
+                            self.frame_format.depth_stencil_format,
+                            wgpu::LoadOp::Clear(1.0),
+                            wgpu::LoadOp::Clear(0),
+                            StoreOp::Store,
+                        )
+                    }),
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
 
-            if self.capture {
-                self.render_image(encoder.as_mut().expect("Couldn't get encoder"))
-            } else {
                 // render pass 1 (user app)
-                let (mut encoder, frame, multi_view, texture_view_updated) = callback(
-                    encoder.expect("Couldn't get encoder"),
-                    frame,
-                    self.multisampled_view.clone(),
-                    texture_view.clone(),
-                );
+                let (encoder, frame, _, texture_view_updated) =
+                    callback(encoder, frame, target.clone(), texture_view.clone());
 
                 let mut encoder = encoder.expect("Couldn't get encoder");
                 let frame = frame.expect("Couldn't get frame");
-                let multi_view = multi_view.expect("Couldn't get multi_view");
                 let texture_view_updated =
                     texture_view_updated.expect("Couldn't get texture_view_updated");
 
-                // render pass 2 (floem and vger)
-                self.vger.run_render_pass(&desc, &mut encoder);
+                // render passes 2 and on (floem, in paint order)
+                let mut command_buffers = Vec::new();
+                let target = PassTarget::new(multisampled_view.as_deref(), &texture_view_updated);
+                self.encode_steps(&steps, target, &mut command_buffers, &mut encoder);
 
-                // present both passes
-                let command_buffer = encoder.finish();
-                self.gpu_resources.queue.submit(Some(command_buffer));
+                // present all passes
+                command_buffers.push(encoder.finish());
+                self.gpu_resources.queue.submit(command_buffers);
                 self.gpu_resources.device.poll(wgpu::Maintain::Poll);
                 frame.present();
-
-                // return (
-                //     Some(encoder),
-                //     Some(frame),
-                //     Some(multi_view.clone()),
-                //     Some(texture_view_updated),
-                //     None,
-                // );
-
-                // (None, None, None, None, None)
-                None
             }
-        } else {
             None
+        };
+
+        // Remove cache entries which were not accessed, and the images vger holds for them.
+        self.painter.evict(self.cache_color);
+        for painter in self.painters.iter_mut().flatten() {
+            painter.evict(self.cache_color);
         }
+
+        // Swap the cache color.
+        self.cache_color = CacheColor(!self.cache_color.0);
+
+        image
+    }
+}
+
+/// Returns an attachment of the depth/stencil texture `view`, with operations for the aspects
+/// its `format` has.
+fn depth_stencil_attachment(
+    view: &TextureView,
+    format: Option<TextureFormat>,
+    depth_load: wgpu::LoadOp<f32>,
+    stencil_load: wgpu::LoadOp<u32>,
+    store: StoreOp,
+) -> wgpu::RenderPassDepthStencilAttachment<'_> {
+    let format = format.expect("A depth/stencil texture always has a format");
+    wgpu::RenderPassDepthStencilAttachment {
+        view,
+        depth_ops: format.has_depth_aspect().then_some(wgpu::Operations {
+            load: depth_load,
+            store,
+        }),
+        stencil_ops: format.has_stencil_aspect().then_some(wgpu::Operations {
+            load: stencil_load,
+            store,
+        }),
+    }
+}
+
+/// The depth/stencil state of vger's pipelines when the frame has a depth/stencil texture in
+/// `format`. The UI is drawn at a depth of `0.0` with the `Less` comparison and doesn't write
+/// depth or stencil, so it is hidden wherever the engine left a depth of `0.0`.
+fn ui_depth_stencil_state(format: TextureFormat) -> wgpu::DepthStencilState {
+    wgpu::DepthStencilState {
+        format,
+        depth_write_enabled: false,
+        depth_compare: if format.has_depth_aspect() {
+            wgpu::CompareFunction::Less
+        } else {
+            wgpu::CompareFunction::Always
+        },
+        stencil: wgpu::StencilState::default(),
+        bias: wgpu::DepthBiasState::default(),
+    }
+}
+
+/// Creates the textures the frame is drawn into besides the surface texture: the multisampled
+/// target vger draws into before it is resolved to the surface if the frame is multisampled,
+/// and the depth/stencil texture if there is one.
+fn create_frame_targets(
+    device: &Device,
+    config: &SurfaceConfiguration,
+    frame_format: FrameFormat,
+) -> (Option<FrameTarget>, Option<FrameTarget>) {
+    let create = |label, format| {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: frame_format.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            label: Some(label),
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (Arc::new(texture), Arc::new(view))
+    };
+    let multisampled = (frame_format.sample_count > 1)
+        .then(|| create("Multisampled render texture", config.format));
+    let depth_stencil = frame_format
+        .depth_stencil_format
+        .map(|format| create("Depth stencil texture", format));
+    (multisampled, depth_stencil)
+}
+
+/// Returns the rect a clip `shape` is reduced to, and its corner radius.
+fn clip_rect(shape: &impl Shape) -> (Rect, f64) {
+    if let Some(rect) = shape.as_rect() {
+        (rect, 0.0)
+    } else if let Some(rect) = shape.as_rounded_rect() {
+        (rect.rect(), rect.radii().top_left)
+    } else {
+        (shape.bounding_box(), 0.0)
     }
 }
 
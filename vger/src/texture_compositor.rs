@@ -0,0 +1,372 @@
+//! Draws textures onto the frame: textures rendered outside of vger, such as the targets of
+//! viewport views, and the layers vger renders offscreen.
+//!
+//! Textures are drawn into the same target as vger content, with the same sample count. If the
+//! frame is multisampled, its multisampled target is resolved again at the end of the pass.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use peniko::kurbo::Rect;
+use peniko::{BlendMode, Compose, Mix};
+use wgpu::util::DeviceExt;
+use wgpu::{BlendComponent, BlendFactor, BlendOperation, BlendState};
+
+const SHADER: &str = r#"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) alpha: f32,
+    @location(2) clip: vec4<f32>,
+    @location(3) clip_radius: f32,
+};
+
+@vertex
+fn vs_main(
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) alpha: f32,
+    @location(3) clip: vec4<f32>,
+    @location(4) clip_radius: f32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    out.uv = uv;
+    out.alpha = alpha;
+    out.clip = clip;
+    out.clip_radius = clip_radius;
+    return out;
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+// How much of the pixel is inside the clip, a rounded rect.
+fn coverage(in: VertexOutput) -> f32 {
+    let half_size = (in.clip.zw - in.clip.xy) * 0.5;
+    let center = in.clip.xy + half_size;
+    let q = abs(in.position.xy - center) - half_size + vec2<f32>(in.clip_radius);
+    let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - in.clip_radius;
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
+
+@fragment
+fn fs_straight(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = coverage(in);
+    if coverage <= 0.0 {
+        discard;
+    }
+    let color = textureSample(source, source_sampler, in.uv);
+    return vec4<f32>(color.rgb, color.a * in.alpha * coverage);
+}
+
+@fragment
+fn fs_premultiplied(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = coverage(in);
+    if coverage <= 0.0 {
+        discard;
+    }
+    let color = textureSample(source, source_sampler, in.uv);
+    return color * in.alpha * coverage;
+}
+"#;
+
+/// Position, texture coordinates, alpha, clip rect and clip radius.
+const VERTEX_FLOATS: usize = 10;
+
+/// A texture to draw onto the frame.
+pub(crate) struct TextureDraw {
+    pub texture: Arc<wgpu::TextureView>,
+    /// The corners of the texture in physical pixels, in the order top left, top right,
+    /// bottom right, bottom left.
+    pub corners: [[f32; 2]; 4],
+    /// The clip rect in physical pixels, with its corner radius.
+    pub clip: Option<(Rect, f32)>,
+    pub alpha: f32,
+    pub blend: Blend,
+}
+
+/// How a texture is blended with the frame.
+#[derive(Clone, Copy)]
+pub(crate) enum Blend {
+    /// Drawn over the frame. The texture's colors aren't premultiplied by its alpha, like
+    /// the textures rendered by views.
+    Over,
+    /// Blended with a blend mode. The texture's colors are premultiplied by its alpha, like
+    /// the layers rendered by vger.
+    ///
+    /// Blend modes are implemented with the GPU's fixed function blending, which can't mix
+    /// colors with arbitrary functions. `Multiply` and `Screen` are exact over an opaque
+    /// frame, `Darken` and `Lighten` compare colors without weighting them by alpha, and
+    /// the other mix modes blend as `Normal`.
+    Mode(BlendMode),
+}
+
+impl Blend {
+    /// Returns the blend state of the pipeline drawing with this blend, and the entry point of
+    /// its fragment shader.
+    fn pipeline_key(self) -> (BlendState, &'static str) {
+        use BlendFactor::*;
+
+        let mode = match self {
+            Blend::Over => return (BlendState::ALPHA_BLENDING, "fs_straight"),
+            Blend::Mode(mode) => mode,
+        };
+        let component = |src_factor, dst_factor| BlendComponent {
+            src_factor,
+            dst_factor,
+            operation: BlendOperation::Add,
+        };
+        let over = component(One, OneMinusSrcAlpha);
+        let state = match mode.mix {
+            Mix::Multiply => BlendState {
+                color: component(Dst, OneMinusSrcAlpha),
+                alpha: over,
+            },
+            Mix::Screen => BlendState {
+                color: component(One, OneMinusSrc),
+                alpha: over,
+            },
+            Mix::Darken | Mix::Lighten => BlendState {
+                color: BlendComponent {
+                    src_factor: One,
+                    dst_factor: One,
+                    operation: if mode.mix == Mix::Darken {
+                        BlendOperation::Min
+                    } else {
+                        BlendOperation::Max
+                    },
+                },
+                alpha: over,
+            },
+            _ => {
+                let component = match mode.compose {
+                    Compose::Clear => component(Zero, Zero),
+                    Compose::Copy => component(One, Zero),
+                    Compose::Dest => component(Zero, One),
+                    Compose::SrcOver => over,
+                    Compose::DestOver => component(OneMinusDstAlpha, One),
+                    Compose::SrcIn => component(DstAlpha, Zero),
+                    Compose::DestIn => component(Zero, SrcAlpha),
+                    Compose::SrcOut => component(OneMinusDstAlpha, Zero),
+                    Compose::DestOut => component(Zero, OneMinusSrcAlpha),
+                    Compose::SrcAtop => component(DstAlpha, OneMinusSrcAlpha),
+                    Compose::DestAtop => component(OneMinusDstAlpha, SrcAlpha),
+                    Compose::Xor => component(OneMinusDstAlpha, OneMinusSrcAlpha),
+                    Compose::Plus | Compose::PlusLighter => component(One, One),
+                };
+                BlendState {
+                    color: component,
+                    alpha: component,
+                }
+            }
+        };
+        (state, "fs_premultiplied")
+    }
+}
+
+pub(crate) struct TextureCompositor {
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    /// The pipelines created so far, by blend state and fragment shader entry point.
+    pipelines: HashMap<(BlendState, &'static str), wgpu::RenderPipeline>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl TextureCompositor {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Texture compositor shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Texture compositor bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Texture compositor pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Texture compositor sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            shader,
+            pipeline_layout,
+            format,
+            sample_count,
+            pipelines: HashMap::new(),
+            bind_group_layout,
+            sampler,
+        }
+    }
+
+    /// Creates the pipelines `draws` are drawn with, if they don't exist yet.
+    fn create_pipelines(&mut self, device: &wgpu::Device, draws: &[&TextureDraw]) {
+        for draw in draws {
+            let (blend, entry_point) = draw.blend.pipeline_key();
+            if self.pipelines.contains_key(&(blend, entry_point)) {
+                continue;
+            }
+            let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Texture compositor pipeline"),
+                layout: Some(&self.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &self.shader,
+                    entry_point: "vs_main",
+                    compilation_options: Default::default(),
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: (VERTEX_FLOATS * std::mem::size_of::<f32>()) as u64,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &wgpu::vertex_attr_array![
+                            0 => Float32x2,
+                            1 => Float32x2,
+                            2 => Float32,
+                            3 => Float32x4,
+                            4 => Float32,
+                        ],
+                    }],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.shader,
+                    entry_point,
+                    compilation_options: Default::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: self.format,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
+                multiview: None,
+                cache: None,
+            });
+            self.pipelines.insert((blend, entry_point), pipeline);
+        }
+    }
+
+    /// Records a pass drawing `draws` in order over the target of `color_attachment`, which is
+    /// `width` x `height` physical pixels.
+    pub fn encode(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachment: wgpu::RenderPassColorAttachment<'_>,
+        width: u32,
+        height: u32,
+        draws: &[&TextureDraw],
+    ) {
+        if draws.is_empty() || width == 0 || height == 0 {
+            return;
+        }
+        self.create_pipelines(device, draws);
+
+        let frame = Rect::new(0.0, 0.0, width as f64, height as f64);
+        let to_ndc =
+            |[x, y]: [f32; 2]| [x / width as f32 * 2.0 - 1.0, 1.0 - y / height as f32 * 2.0];
+        const UVS: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        let mut vertices: Vec<f32> = Vec::with_capacity(draws.len() * 6 * VERTEX_FLOATS);
+        for draw in draws.iter() {
+            let (clip, radius) = draw.clip.unwrap_or((frame, 0.0));
+            for corner in [0, 1, 2, 0, 2, 3] {
+                vertices.extend(to_ndc(draw.corners[corner]));
+                vertices.extend(UVS[corner]);
+                vertices.push(draw.alpha);
+                vertices.extend([clip.x0, clip.y0, clip.x1, clip.y1].map(|v| v as f32));
+                vertices.push(radius);
+            }
+        }
+        let contents: Vec<u8> = vertices.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Texture compositor vertices"),
+            contents: &contents,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let bind_groups: Vec<_> = draws
+            .iter()
+            .map(|draw| {
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Texture compositor bind group"),
+                    layout: &self.bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&draw.texture),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&self.sampler),
+                        },
+                    ],
+                })
+            })
+            .collect();
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Texture compositor pass"),
+            color_attachments: &[Some(color_attachment)],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+
+        for (i, (draw, bind_group)) in draws.iter().zip(&bind_groups).enumerate() {
+            let clip = draw.clip.map_or(frame, |(clip, _)| clip.intersect(frame));
+            let clip = Rect::new(
+                clip.x0.floor(),
+                clip.y0.floor(),
+                clip.x1.ceil(),
+                clip.y1.ceil(),
+            )
+            .intersect(frame);
+            if clip.width() <= 0.0 || clip.height() <= 0.0 {
+                continue;
+            }
+            pass.set_pipeline(&self.pipelines[&draw.blend.pipeline_key()]);
+            pass.set_scissor_rect(
+                clip.x0 as u32,
+                clip.y0 as u32,
+                clip.width() as u32,
+                clip.height() as u32,
+            );
+            pass.set_bind_group(0, bind_group, &[]);
+            let first = (i * 6) as u32;
+            pass.draw(first..first + 6, 0..1);
+        }
+    }
+}